@@ -7,7 +7,8 @@
 //! - Velocity: meters/second (m/s)
 //! - Acceleration: meters/second² (m/s²)
 
-use crate::vector::Vec3;
+use crate::thrust::Burn;
+use crate::vector::{Quat, Vec3};
 use serde::{Deserialize, Serialize};
 
 /// Unique identifier for a body
@@ -94,6 +95,9 @@ pub struct Atmosphere {
     /// (dust, cloud droplets, haze). White for clean, salmon for iron-oxide dust, etc.
     #[serde(default = "Atmosphere::default_mie_color")]
     pub mie_color: [f64; 3],
+    /// Gas density at the surface in kg/m³ (0 = no drag)
+    #[serde(default)]
+    pub surface_density: f64,
 }
 
 impl Atmosphere {
@@ -112,6 +116,7 @@ impl Atmosphere {
             mie_direction: 0.758,
             height: 100_000.0, // 100 km
             mie_color: [1.0, 1.0, 1.0], // White (water droplets / clean air)
+            surface_density: 1.225,     // US Standard Atmosphere, sea level
         }
     }
 
@@ -125,6 +130,7 @@ impl Atmosphere {
             mie_direction: 0.9,        // Strong forward scattering
             height: 200_000.0,
             mie_color: [0.85, 0.55, 0.35], // Iron-oxide dust — salmon/butterscotch
+            surface_density: 0.020,
         }
     }
 
@@ -138,6 +144,7 @@ impl Atmosphere {
             mie_direction: 0.85,
             height: 250_000.0,         // ~250 km
             mie_color: [0.95, 0.88, 0.55], // Pale yellow (H₂SO₄ droplets)
+            surface_density: 65.0,
         }
     }
 
@@ -218,10 +225,8 @@ pub struct Body {
     #[serde(default)]
     pub softening_length: f64,
 
-    /// Sidereal angular velocity (rad/s)
-    // TODO(rotation): Use this in the renderer to spin body meshes.
-    //   Requires storing a rotation quaternion that accumulates each frame:
-    //   `quat = Quat::from_axis_angle(spin_axis, rotation_rate * dt) * quat`
+    /// Sidereal angular velocity (rad/s) about `spin_axis()`; negative for
+    /// retrograde rotation. `orientation` accumulates it each step.
     #[serde(default)]
     pub rotation_rate: f64,
 
@@ -321,8 +326,85 @@ pub struct Body {
     #[serde(default)]
     pub moment_of_inertia_factor: f64,
 
+    /// Zonal harmonic J2, normalized to `radius`. Derived from oblateness if zero.
+    #[serde(default)]
+    pub j2: f64,
+
+    /// Zonal harmonic J4, normalized to `radius`. Derived from J2 if zero.
+    #[serde(default)]
+    pub j4: f64,
+
+    /// Tidal Love number k2 (0 = no tides raised on this body)
+    #[serde(default)]
+    pub k2: f64,
+
+    /// Constant tidal time lag Δt in seconds (≈ 1 / (Q · tidal frequency))
+    #[serde(default)]
+    pub tidal_time_lag: f64,
+
+    /// Tidal torque on this body's spin in N·m (computed each step)
+    #[serde(default)]
+    pub tidal_torque: Vec3,
+
+    /// Tidal power dissipated in this body in W (computed each step)
+    #[serde(default)]
+    pub tidal_heating: f64,
+
+    /// Atmospheric drag coefficient C_d (0 = DEFAULT_DRAG_COEFFICIENT)
+    #[serde(default)]
+    pub drag_coefficient: f64,
+
+    /// Drag reference area in m² (0 = cross-section π·radius²)
+    #[serde(default)]
+    pub drag_area: f64,
+
+    /// Marsden-Sekanina non-gravitational parameters A1, A2, A3 of a comet
+    /// (radial, transverse, normal acceleration at 1 AU in m/s²)
+    #[serde(default)]
+    pub nongrav_params: [f64; 3],
+
+    /// Surface thermal inertia Γ in J m⁻² K⁻¹ s⁻½
+    /// (0 = DEFAULT_THERMAL_INERTIA)
+    #[serde(default)]
+    pub thermal_inertia: f64,
+
+    /// Dimensionless YORP efficiency (0 = no YORP; its sign sets whether
+    /// the body spins up or down)
+    #[serde(default)]
+    pub yorp_coefficient: f64,
+
+    /// Orientation of the body frame (z = spin axis) in the simulation
+    /// frame, advanced every step by `rotation::advance_rotation`
+    #[serde(default)]
+    pub orientation: Quat,
+
+    /// Angular velocity vector `spin_axis() * rotation_rate` in rad/s
+    /// (kept in sync with the spin state)
+    #[serde(default)]
+    pub angular_velocity: Vec3,
+
+    // ─── propulsion (spacecraft / players) ──────────────────────
+    /// Scheduled engine burns, see `thrust`
+    #[serde(default)]
+    pub burns: Vec<Burn>,
+
+    /// Propellant on board in kg (included in `mass`)
+    #[serde(default)]
+    pub propellant_mass: f64,
+
+    /// Effective exhaust velocity Isp·g₀ in m/s (0 = thrust burns no
+    /// propellant)
+    #[serde(default)]
+    pub exhaust_velocity: f64,
+
+    /// Engine thrust acceleration in m/s² over the current substep
+    /// (computed each substep)
+    #[serde(default)]
+    pub thrust: Vec3,
+
     // ─── orbital elements (optional, for presets / orbit viz) ───
-    /// Semi-major axis a (m). 0 if unset / free-flying.
+    /// Semi-major axis a (m). 0 if unset / free-flying, negative if hyperbolic.
+    /// Set by presets; refreshed by `Simulation::update_orbital_elements`.
     #[serde(default)]
     pub semi_major_axis: f64,
     /// Eccentricity e (unitless)
@@ -361,6 +443,10 @@ pub struct Body {
     
     /// Parent body ID for hierarchical systems (e.g., moon orbiting planet)
     pub parent_id: Option<BodyId>,
+
+    /// Debris of a tidally disrupted body (never disrupted again)
+    #[serde(default)]
+    pub is_fragment: bool,
 }
 
 fn default_true() -> bool { true }
@@ -418,6 +504,23 @@ impl Body {
             scale_height: 0.0,
             oblateness: 0.0,
             moment_of_inertia_factor: 0.0,
+            j2: 0.0,
+            j4: 0.0,
+            k2: 0.0,
+            tidal_time_lag: 0.0,
+            tidal_torque: Vec3::ZERO,
+            tidal_heating: 0.0,
+            drag_coefficient: 0.0,
+            drag_area: 0.0,
+            nongrav_params: [0.0; 3],
+            thermal_inertia: 0.0,
+            yorp_coefficient: 0.0,
+            orientation: Quat::IDENTITY,
+            angular_velocity: Vec3::ZERO,
+            burns: Vec::new(),
+            propellant_mass: 0.0,
+            exhaust_velocity: 0.0,
+            thrust: Vec3::ZERO,
             semi_major_axis: 0.0,
             eccentricity: 0.0,
             inclination: 0.0,
@@ -430,6 +533,7 @@ impl Body {
             color: [1.0, 1.0, 1.0],
             is_active: true,
             parent_id: None,
+            is_fragment: false,
         }
     }
 
@@ -490,7 +594,8 @@ impl Body {
     /// Compute derived physical quantities from mass and radius.
     /// Fills bulk_density, surface_gravity, escape_velocity_surface if they
     /// are currently zero. Also dispatches to type-specific derive functions
-    /// for stars and planets/moons. Safe to call multiple times.
+    /// for stars and planets/moons, and aligns `orientation` with the spin
+    /// axis. Safe to call multiple times.
     pub fn compute_derived(&mut self) {
         self.compute_derived_with_parent(None);
     }
@@ -499,6 +604,7 @@ impl Body {
     /// For planets/moons, the parent star is used to compute equilibrium
     /// temperature and scale height from stellar irradiance.
     pub fn compute_derived_with_parent(&mut self, parent: Option<&Body>) {
+        self.align_orientation();
         if self.mass <= 0.0 || self.radius <= 0.0 {
             return;
         }
@@ -538,6 +644,76 @@ impl Body {
         }
     }
 
+    /// Unit spin axis in the simulation frame (ecliptic J2000).
+    /// Uses `pole_ra`/`pole_dec` when both are set, otherwise +z.
+    pub fn spin_axis(&self) -> Vec3 {
+        match (self.pole_ra, self.pole_dec) {
+            (Some(ra), Some(dec)) => {
+                let equatorial = Vec3::new(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin());
+                // Rotate about x from the equator to the ecliptic
+                let (sin_e, cos_e) = crate::constants::OBLIQUITY_J2000.sin_cos();
+                Vec3::new(
+                    equatorial.x,
+                    cos_e * equatorial.y + sin_e * equatorial.z,
+                    -sin_e * equatorial.y + cos_e * equatorial.z,
+                )
+            }
+            _ => Vec3::Z,
+        }
+    }
+
+    /// Point the spin axis along `axis` (simulation frame) by setting
+    /// `pole_ra`/`pole_dec`
+    pub fn set_spin_axis(&mut self, axis: Vec3) {
+        let axis = axis.normalize();
+        // Rotate about x from the ecliptic to the equator
+        let (sin_e, cos_e) = crate::constants::OBLIQUITY_J2000.sin_cos();
+        let equatorial = Vec3::new(axis.x, cos_e * axis.y - sin_e * axis.z, sin_e * axis.y + cos_e * axis.z);
+        self.pole_ra = Some(equatorial.y.atan2(equatorial.x).rem_euclid(std::f64::consts::TAU));
+        self.pole_dec = Some(equatorial.z.clamp(-1.0, 1.0).asin());
+    }
+
+    /// Turn `orientation` so the body's z axis lies along `spin_axis()`,
+    /// and refresh `angular_velocity`. A no-op when already aligned.
+    pub fn align_orientation(&mut self) {
+        let axis = self.spin_axis();
+        let body_z = self.orientation.rotate(Vec3::Z);
+        if body_z.dot(axis) < 1.0 - 1e-15 {
+            self.orientation = (Quat::from_rotation_arc(body_z, axis) * self.orientation).normalize();
+        }
+        self.angular_velocity = axis * self.rotation_rate;
+    }
+
+    /// Copy of this body as a piece of debris named `name`, with id 0, no
+    /// parent, atmosphere, rings, engine or mass loss, and its
+    /// size-dependent derived fields cleared for `Simulation::add_body`.
+    /// Position and velocity are left to the caller.
+    pub fn as_fragment(&self, name: impl Into<String>, mass: f64, radius: f64, seed: u64) -> Body {
+        let mut fragment = self.clone();
+        fragment.id = 0;
+        fragment.name = name.into();
+        fragment.mass = mass;
+        fragment.radius = radius;
+        fragment.seed = seed;
+        fragment.is_fragment = true;
+        fragment.parent_id = None;
+        fragment.atmosphere = None;
+        fragment.rings = None;
+        fragment.burns.clear();
+        fragment.thrust = Vec3::ZERO;
+        fragment.propellant_mass = 0.0;
+        fragment.exhaust_velocity = 0.0;
+        fragment.mass_loss_rate = 0.0;
+        fragment.bulk_density = 0.0;
+        fragment.surface_gravity = 0.0;
+        fragment.escape_velocity_surface = 0.0;
+        fragment.scale_height = 0.0;
+        fragment.oblateness = 0.0;
+        fragment.j2 = 0.0;
+        fragment.j4 = 0.0;
+        fragment
+    }
+
     /// Calculate the gravitational parameter μ = G * M
     pub fn gravitational_parameter(&self) -> f64 {
         crate::constants::G * self.mass
@@ -627,6 +803,23 @@ impl Default for Body {
             scale_height: 0.0,
             oblateness: 0.0,
             moment_of_inertia_factor: 0.0,
+            j2: 0.0,
+            j4: 0.0,
+            k2: 0.0,
+            tidal_time_lag: 0.0,
+            tidal_torque: Vec3::ZERO,
+            tidal_heating: 0.0,
+            drag_coefficient: 0.0,
+            drag_area: 0.0,
+            nongrav_params: [0.0; 3],
+            thermal_inertia: 0.0,
+            yorp_coefficient: 0.0,
+            orientation: Quat::IDENTITY,
+            angular_velocity: Vec3::ZERO,
+            burns: Vec::new(),
+            propellant_mass: 0.0,
+            exhaust_velocity: 0.0,
+            thrust: Vec3::ZERO,
             semi_major_axis: 0.0,
             eccentricity: 0.0,
             inclination: 0.0,
@@ -639,6 +832,7 @@ impl Default for Body {
             color: [1.0, 1.0, 1.0],
             is_active: true,
             parent_id: None,
+            is_fragment: false,
         }
     }
 }
@@ -706,6 +900,25 @@ mod tests {
         assert!(player.feels_gravity, "Players should feel gravity");
     }
 
+    #[test]
+    fn test_as_fragment() {
+        let mut craft = Body::new(7, "Probe", BodyType::Spacecraft, 1000.0, 2.0, Vec3::X, Vec3::Y);
+        craft.parent_id = Some(1);
+        craft.propellant_mass = 400.0;
+        craft.exhaust_velocity = 3000.0;
+        craft.thrust = Vec3::Z;
+        craft.burns.push(Burn { delay: 0.0, duration: 10.0, thrust: Vec3::Z, frame: Default::default() });
+        craft.compute_derived();
+
+        let piece = craft.as_fragment("Probe_1", 250.0, 1.0, 42);
+        assert_eq!((piece.id, piece.name.as_str(), piece.seed), (0, "Probe_1", 42));
+        assert!(piece.is_fragment && piece.parent_id.is_none());
+        assert!(piece.burns.is_empty() && piece.thrust == Vec3::ZERO);
+        assert_eq!((piece.propellant_mass, piece.exhaust_velocity), (0.0, 0.0));
+        assert_eq!((piece.bulk_density, piece.surface_gravity), (0.0, 0.0));
+        assert_eq!((piece.position, piece.velocity), (craft.position, craft.velocity));
+    }
+
     #[test]
     fn test_effective_softening() {
         let mut body = Body::default();
@@ -0,0 +1,1293 @@
+//! Compact binary encoding for snapshots and deltas
+//!
+//! Layout (all integers little-endian, lengths as LEB128 varints):
+//!
+//! ```text
+//! magic "SYMB" | codec version u8 | layout u64 | kind u8 | flags u8 | [quantization]
+//! snapshot: head | static section | dynamic section
+//! delta:    head | removed ids | records (id, tag, [static], dynamic)
+//! ```
+//!
+//! The static section holds every `Body` field except the kinematic and
+//! rotational state (position, velocity, acceleration, prev_acceleration,
+//! orientation, angular_velocity), written positionally by a minimal serde
+//! serializer so new `Body` fields are picked up automatically. The dynamic
+//! section carries that state, either as raw f64 bits (lossless, bit-exact)
+//! or quantized to a fixed step as zigzag varints. Quantized deltas store
+//! positions, velocities and orientations relative to the base snapshot, so
+//! per-tick records are usually a few bytes per component. Quantized
+//! encodings drop accelerations, which are recomputed on restore, and derive
+//! angular velocities from the spin state.
+//!
+//! The layout is positional, so it is tied to the `Body` definition of the
+//! build that wrote it. The header therefore carries a fingerprint of that
+//! layout (every struct, field, enum variant and primitive type the records
+//! are built from, as seen by serde) and blobs from a build with a different
+//! layout are rejected rather than decoded into the wrong fields. Use JSON
+//! for long-term saves.
+
+use crate::body::Body;
+use crate::snapshot::{
+    DeltaSnapshot, SerializableForceConfig, SerializableIntegratorConfig,
+    SerializableSimulationConfig, Snapshot, SnapshotMetadata,
+};
+use crate::vector::{Quat, Vec3};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Magic bytes at the start of every binary blob
+const MAGIC: &[u8; 4] = b"SYMB";
+
+/// Binary codec framing version. Changes to `Body` and the snapshot
+/// structs are caught by the layout fingerprint instead.
+pub const CODEC_VERSION: u8 = 3;
+
+const KIND_SNAPSHOT: u8 = 0;
+const KIND_DELTA: u8 = 1;
+
+const FLAG_QUANTIZED: u8 = 1;
+
+const RECORD_DYNAMIC: u8 = 0;
+const RECORD_FULL: u8 = 1;
+
+/// Body fields carried by the dynamic section instead of the static record,
+/// with their number of f64 components
+const DYNAMIC_FIELDS: [(&str, usize); 6] = [
+    ("position", 3),
+    ("velocity", 3),
+    ("acceleration", 3),
+    ("prev_acceleration", 3),
+    ("orientation", 4),
+    ("angular_velocity", 3),
+];
+
+/// Resolution of quantized orientation quaternion components (~0.04″)
+const ORIENTATION_STEP: f64 = 1.0e-7;
+
+/// Number of components of a dynamic `Body` field, or `None` for a static one
+fn dynamic_components(field: &str) -> Option<usize> {
+    DYNAMIC_FIELDS.iter().find(|(name, _)| *name == field).map(|&(_, n)| n)
+}
+
+/// Error produced by the binary codec
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodecError(String);
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "binary codec: {}", self.0)
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl ser::Error for CodecError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for CodecError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl From<&'static str> for CodecError {
+    fn from(msg: &'static str) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Fixed-step quantization of the dynamic section
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantization {
+    /// Position resolution in meters
+    pub position_step: f64,
+    /// Velocity resolution in m/s
+    pub velocity_step: f64,
+}
+
+impl Quantization {
+    pub fn new(position_step: f64, velocity_step: f64) -> Self {
+        Self { position_step, velocity_step }
+    }
+
+    fn validate(&self) -> Result<(), CodecError> {
+        let ok = |s: f64| s.is_finite() && s > 0.0;
+        if ok(self.position_step) && ok(self.velocity_step) {
+            Ok(())
+        } else {
+            Err("quantization steps must be finite and positive".into())
+        }
+    }
+}
+
+// ─── primitive writer / reader ──────────────────────────────────
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn u8(&mut self, v: u8) {
+        self.buf.push(v);
+    }
+
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.buf.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        self.buf.push(v as u8);
+    }
+
+    fn zigzag(&mut self, v: i64) {
+        self.varint(((v << 1) ^ (v >> 63)) as u64);
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f64(&mut self, v: f64) {
+        self.u64(v.to_bits());
+    }
+
+    fn f32(&mut self, v: f32) {
+        self.buf.extend_from_slice(&v.to_bits().to_le_bytes());
+    }
+
+    fn bytes(&mut self, v: &[u8]) {
+        self.varint(v.len() as u64);
+        self.buf.extend_from_slice(v);
+    }
+
+    fn vec3(&mut self, v: Vec3) {
+        self.f64(v.x);
+        self.f64(v.y);
+        self.f64(v.z);
+    }
+
+    fn quantized(&mut self, v: f64, step: f64) -> Result<(), CodecError> {
+        let q = (v / step).round();
+        if !q.is_finite() || q.abs() >= 9.0e18 {
+            return Err("value out of range for quantization step".into());
+        }
+        self.zigzag(q as i64);
+        Ok(())
+    }
+
+    fn quantized_vec3(&mut self, v: Vec3, step: f64) -> Result<(), CodecError> {
+        for c in v.to_array() {
+            self.quantized(c, step)?;
+        }
+        Ok(())
+    }
+}
+
+struct Reader<'de> {
+    input: &'de [u8],
+    pos: usize,
+}
+
+impl<'de> Reader<'de> {
+    fn new(input: &'de [u8]) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'de [u8], CodecError> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.input.len());
+        let end = end.ok_or(CodecError::from("unexpected end of input"))?;
+        let slice = &self.input[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn remaining(&self) -> usize {
+        self.input.len() - self.pos
+    }
+
+    fn u8(&mut self) -> Result<u8, CodecError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, CodecError> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            v |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err("varint too long".into())
+    }
+
+    fn zigzag(&mut self) -> Result<i64, CodecError> {
+        let v = self.varint()?;
+        Ok(((v >> 1) as i64) ^ -((v & 1) as i64))
+    }
+
+    fn len(&mut self) -> Result<usize, CodecError> {
+        let len = self.varint()?;
+        usize::try_from(len).map_err(|_| CodecError::from("length overflow"))
+    }
+
+    fn u64(&mut self) -> Result<u64, CodecError> {
+        let bytes: [u8; 8] = self.take(8)?.try_into().unwrap();
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn f64(&mut self) -> Result<f64, CodecError> {
+        Ok(f64::from_bits(self.u64()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, CodecError> {
+        let bytes: [u8; 4] = self.take(4)?.try_into().unwrap();
+        Ok(f32::from_bits(u32::from_le_bytes(bytes)))
+    }
+
+    fn bytes(&mut self) -> Result<&'de [u8], CodecError> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn vec3(&mut self) -> Result<Vec3, CodecError> {
+        Ok(Vec3::new(self.f64()?, self.f64()?, self.f64()?))
+    }
+
+    fn quantized(&mut self, step: f64) -> Result<f64, CodecError> {
+        Ok(self.zigzag()? as f64 * step)
+    }
+
+    fn quantized_vec3(&mut self, step: f64) -> Result<Vec3, CodecError> {
+        Ok(Vec3::new(self.quantized(step)?, self.quantized(step)?, self.quantized(step)?))
+    }
+}
+
+// ─── positional serde serializer ────────────────────────────────
+
+struct BinarySerializer<'a> {
+    out: &'a mut Writer,
+}
+
+/// Compound serializer; `strip` drops the dynamic fields of a `Body`
+struct Compound<'a, 'b> {
+    ser: &'b mut BinarySerializer<'a>,
+    strip: bool,
+}
+
+impl<'a> BinarySerializer<'a> {
+    fn compound<'b>(&'b mut self) -> Compound<'a, 'b> {
+        Compound { ser: self, strip: false }
+    }
+}
+
+impl<'a, 'b> Serializer for &'b mut BinarySerializer<'a> {
+    type Ok = ();
+    type Error = CodecError;
+    type SerializeSeq = Compound<'a, 'b>;
+    type SerializeTuple = Compound<'a, 'b>;
+    type SerializeTupleStruct = Compound<'a, 'b>;
+    type SerializeTupleVariant = Compound<'a, 'b>;
+    type SerializeMap = Compound<'a, 'b>;
+    type SerializeStruct = Compound<'a, 'b>;
+    type SerializeStructVariant = Compound<'a, 'b>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), CodecError> {
+        self.out.u8(v as u8);
+        Ok(())
+    }
+    fn serialize_i8(self, v: i8) -> Result<(), CodecError> {
+        self.out.zigzag(v as i64);
+        Ok(())
+    }
+    fn serialize_i16(self, v: i16) -> Result<(), CodecError> {
+        self.out.zigzag(v as i64);
+        Ok(())
+    }
+    fn serialize_i32(self, v: i32) -> Result<(), CodecError> {
+        self.out.zigzag(v as i64);
+        Ok(())
+    }
+    fn serialize_i64(self, v: i64) -> Result<(), CodecError> {
+        self.out.zigzag(v);
+        Ok(())
+    }
+    fn serialize_u8(self, v: u8) -> Result<(), CodecError> {
+        self.out.u8(v);
+        Ok(())
+    }
+    fn serialize_u16(self, v: u16) -> Result<(), CodecError> {
+        self.out.varint(v as u64);
+        Ok(())
+    }
+    fn serialize_u32(self, v: u32) -> Result<(), CodecError> {
+        self.out.varint(v as u64);
+        Ok(())
+    }
+    fn serialize_u64(self, v: u64) -> Result<(), CodecError> {
+        self.out.varint(v);
+        Ok(())
+    }
+    fn serialize_f32(self, v: f32) -> Result<(), CodecError> {
+        self.out.f32(v);
+        Ok(())
+    }
+    fn serialize_f64(self, v: f64) -> Result<(), CodecError> {
+        self.out.f64(v);
+        Ok(())
+    }
+    fn serialize_char(self, v: char) -> Result<(), CodecError> {
+        self.out.varint(v as u64);
+        Ok(())
+    }
+    fn serialize_str(self, v: &str) -> Result<(), CodecError> {
+        self.out.bytes(v.as_bytes());
+        Ok(())
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), CodecError> {
+        self.out.bytes(v);
+        Ok(())
+    }
+    fn serialize_none(self) -> Result<(), CodecError> {
+        self.out.u8(0);
+        Ok(())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), CodecError> {
+        self.out.u8(1);
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<(), CodecError> {
+        Ok(())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), CodecError> {
+        Ok(())
+    }
+    fn serialize_unit_variant(self, _name: &'static str, index: u32, _variant: &'static str) -> Result<(), CodecError> {
+        self.out.varint(index as u64);
+        Ok(())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), CodecError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), CodecError> {
+        self.out.varint(index as u64);
+        value.serialize(self)
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, CodecError> {
+        let len = len.ok_or(CodecError::from("sequence length must be known"))?;
+        self.out.varint(len as u64);
+        Ok(self.compound())
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, CodecError> {
+        Ok(self.compound())
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, CodecError> {
+        Ok(self.compound())
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, CodecError> {
+        self.out.varint(index as u64);
+        Ok(self.compound())
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, CodecError> {
+        let len = len.ok_or(CodecError::from("map length must be known"))?;
+        self.out.varint(len as u64);
+        Ok(self.compound())
+    }
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Self::SerializeStruct, CodecError> {
+        Ok(Compound { ser: self, strip: name == "Body" })
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, CodecError> {
+        self.out.varint(index as u64);
+        Ok(self.compound())
+    }
+}
+
+macro_rules! impl_compound {
+    ($($trait:ident :: $method:ident),*) => {
+        $(
+            impl ser::$trait for Compound<'_, '_> {
+                type Ok = ();
+                type Error = CodecError;
+                fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CodecError> {
+                    value.serialize(&mut *self.ser)
+                }
+                fn end(self) -> Result<(), CodecError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl ser::SerializeMap for Compound<'_, '_> {
+    type Ok = ();
+    type Error = CodecError;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), CodecError> {
+        key.serialize(&mut *self.ser)
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CodecError> {
+        value.serialize(&mut *self.ser)
+    }
+    fn end(self) -> Result<(), CodecError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_, '_> {
+    type Ok = ();
+    type Error = CodecError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), CodecError> {
+        if self.strip && dynamic_components(key).is_some() {
+            return Ok(());
+        }
+        value.serialize(&mut *self.ser)
+    }
+    fn end(self) -> Result<(), CodecError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_, '_> {
+    type Ok = ();
+    type Error = CodecError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _key: &'static str, value: &T) -> Result<(), CodecError> {
+        value.serialize(&mut *self.ser)
+    }
+    fn end(self) -> Result<(), CodecError> {
+        Ok(())
+    }
+}
+
+fn write_value<T: Serialize>(out: &mut Writer, value: &T) -> Result<(), CodecError> {
+    value.serialize(&mut BinarySerializer { out })
+}
+
+// ─── positional serde deserializer ──────────────────────────────
+
+struct BinaryDeserializer<'a, 'de> {
+    input: &'a mut Reader<'de>,
+}
+
+impl<'de> Deserializer<'de> for &mut BinaryDeserializer<'_, 'de> {
+    type Error = CodecError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err("binary format is not self-describing".into())
+    }
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        match self.input.u8()? {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            _ => Err("invalid bool".into()),
+        }
+    }
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_i64(self.input.zigzag()?)
+    }
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_i64(self.input.zigzag()?)
+    }
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_i64(self.input.zigzag()?)
+    }
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_i64(self.input.zigzag()?)
+    }
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_u8(self.input.u8()?)
+    }
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_u64(self.input.varint()?)
+    }
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_u64(self.input.varint()?)
+    }
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_u64(self.input.varint()?)
+    }
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_f32(self.input.f32()?)
+    }
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_f64(self.input.f64()?)
+    }
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        let code = u32::try_from(self.input.varint()?).ok().and_then(char::from_u32);
+        visitor.visit_char(code.ok_or(CodecError::from("invalid char"))?)
+    }
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        let s = std::str::from_utf8(self.input.bytes()?).map_err(|_| CodecError::from("invalid utf-8"))?;
+        visitor.visit_borrowed_str(s)
+    }
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        self.deserialize_str(visitor)
+    }
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_borrowed_bytes(self.input.bytes()?)
+    }
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        self.deserialize_bytes(visitor)
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        match self.input.u8()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            _ => Err("invalid option tag".into()),
+        }
+    }
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_unit()
+    }
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_unit()
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_newtype_struct(self)
+    }
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        let len = self.input.len()?;
+        visitor.visit_seq(Elements { de: self, fields: &[], index: 0, len, strip: false })
+    }
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_seq(Elements { de: self, fields: &[], index: 0, len, strip: false })
+    }
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        self.deserialize_tuple(len, visitor)
+    }
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        let len = self.input.len()?;
+        visitor.visit_map(Elements { de: self, fields: &[], index: 0, len, strip: false })
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        let strip = name == "Body";
+        visitor.visit_seq(Elements { de: self, fields, index: 0, len: fields.len(), strip })
+    }
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_enum(self)
+    }
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err("binary format has no identifiers".into())
+    }
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err("binary format cannot skip values".into())
+    }
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Sequence / map / struct access. For a stripped `Body` the dynamic fields
+/// are not in the stream and are produced as zeros instead.
+struct Elements<'a, 'b, 'de> {
+    de: &'a mut BinaryDeserializer<'b, 'de>,
+    fields: &'static [&'static str],
+    index: usize,
+    len: usize,
+    strip: bool,
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_, '_, 'de> {
+    type Error = CodecError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, CodecError> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let field = self.fields.get(self.index).copied();
+        self.index += 1;
+        if let Some(components) = field.filter(|_| self.strip).and_then(dynamic_components) {
+            let zero = de::value::SeqDeserializer::<_, CodecError>::new(std::iter::repeat_n(0.0f64, components));
+            return seed.deserialize(zero).map(Some);
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index).min(self.de.input.remaining()))
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Elements<'_, '_, 'de> {
+    type Error = CodecError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, CodecError> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        self.index += 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, CodecError> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut BinaryDeserializer<'_, 'de> {
+    type Error = CodecError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), CodecError> {
+        let index = u32::try_from(self.input.varint()?).map_err(|_| CodecError::from("invalid variant"))?;
+        let value = seed.deserialize(IntoDeserializer::<CodecError>::into_deserializer(index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut BinaryDeserializer<'_, 'de> {
+    type Error = CodecError;
+
+    fn unit_variant(self) -> Result<(), CodecError> {
+        Ok(())
+    }
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, CodecError> {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, CodecError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_seq(Elements { de: self, fields, index: 0, len: fields.len(), strip: false })
+    }
+}
+
+fn read_value<'de, T: Deserialize<'de>>(input: &mut Reader<'de>) -> Result<T, CodecError> {
+    T::deserialize(&mut BinaryDeserializer { input })
+}
+
+// ─── layout fingerprint ─────────────────────────────────────────
+
+/// Deserializer that walks a type's serde layout instead of data: it hashes
+/// every struct and field name, enum and variant name and primitive type it
+/// is asked for (FNV-1a), producing placeholder values as it goes.
+/// Sequences and maps are walked with one element so their element layout
+/// is included; enums are walked through their first variant.
+struct LayoutTracer {
+    hash: u64,
+}
+
+impl LayoutTracer {
+    fn new() -> Self {
+        Self { hash: 0xcbf2_9ce4_8422_2325 }
+    }
+
+    fn token(&mut self, token: &str) {
+        for byte in token.bytes().chain([0]) {
+            self.hash ^= byte as u64;
+            self.hash = self.hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn tokens(&mut self, tokens: &[&str]) {
+        for token in tokens {
+            self.token(token);
+        }
+        self.token("end");
+    }
+
+    fn trace<'de, T: Deserialize<'de>>(&mut self) -> Result<(), CodecError> {
+        T::deserialize(&mut *self).map(drop)
+    }
+}
+
+macro_rules! trace_primitive {
+    ($($method:ident => $visit:ident($value:expr)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+                self.token(stringify!($method));
+                visitor.$visit($value)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for &mut LayoutTracer {
+    type Error = CodecError;
+
+    trace_primitive!(
+        deserialize_bool => visit_bool(false),
+        deserialize_i8 => visit_i8(0),
+        deserialize_i16 => visit_i16(0),
+        deserialize_i32 => visit_i32(0),
+        deserialize_i64 => visit_i64(0),
+        deserialize_u8 => visit_u8(0),
+        deserialize_u16 => visit_u16(0),
+        deserialize_u32 => visit_u32(0),
+        deserialize_u64 => visit_u64(0),
+        deserialize_f32 => visit_f32(0.0),
+        deserialize_f64 => visit_f64(0.0),
+        deserialize_char => visit_char('\0'),
+        deserialize_str => visit_borrowed_str(""),
+        deserialize_string => visit_borrowed_str(""),
+        deserialize_bytes => visit_borrowed_bytes(&[]),
+        deserialize_byte_buf => visit_borrowed_bytes(&[]),
+    );
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        self.token("deserialize_unit");
+        visitor.visit_unit()
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err("binary format is not self-describing".into())
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        self.token("option");
+        visitor.visit_some(self)
+    }
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, CodecError> {
+        self.token(name);
+        visitor.visit_unit()
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, CodecError> {
+        self.token(name);
+        visitor.visit_newtype_struct(self)
+    }
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        self.token("seq");
+        visitor.visit_seq(TraceElements { tracer: self, len: 1 })
+    }
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, CodecError> {
+        self.token("tuple");
+        visitor.visit_seq(TraceElements { tracer: self, len })
+    }
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, name: &'static str, len: usize, visitor: V) -> Result<V::Value, CodecError> {
+        self.token(name);
+        visitor.visit_seq(TraceElements { tracer: self, len })
+    }
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        self.token("map");
+        visitor.visit_map(TraceElements { tracer: self, len: 1 })
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        self.token(name);
+        self.tokens(fields);
+        visitor.visit_seq(TraceElements { tracer: self, len: fields.len() })
+    }
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        self.token(name);
+        self.tokens(variants);
+        visitor.visit_enum(self)
+    }
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err("binary format has no identifiers".into())
+    }
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err("binary format cannot skip values".into())
+    }
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Placeholder elements of a traced sequence, tuple, map or struct
+struct TraceElements<'a> {
+    tracer: &'a mut LayoutTracer,
+    len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for TraceElements<'_> {
+    type Error = CodecError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, CodecError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.tracer).map(Some)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for TraceElements<'_> {
+    type Error = CodecError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, CodecError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.tracer).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, CodecError> {
+        seed.deserialize(&mut *self.tracer)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut LayoutTracer {
+    type Error = CodecError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), CodecError> {
+        let value = seed.deserialize(IntoDeserializer::<CodecError>::into_deserializer(0u32))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut LayoutTracer {
+    type Error = CodecError;
+
+    fn unit_variant(self) -> Result<(), CodecError> {
+        Ok(())
+    }
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, CodecError> {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, CodecError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, CodecError> {
+        self.tokens(fields);
+        visitor.visit_seq(TraceElements { tracer: self, len: fields.len() })
+    }
+}
+
+/// Fingerprint of the layout of every record the codec writes
+fn layout_fingerprint() -> u64 {
+    static FINGERPRINT: OnceLock<u64> = OnceLock::new();
+    *FINGERPRINT.get_or_init(|| {
+        let mut tracer = LayoutTracer::new();
+        tracer.trace::<SnapshotHead>().expect("snapshot head layout is traceable");
+        tracer.trace::<DeltaHead>().expect("delta head layout is traceable");
+        tracer.trace::<Body>().expect("body layout is traceable");
+        tracer.hash
+    })
+}
+
+// ─── snapshot / delta framing ───────────────────────────────────
+
+/// Everything in a `Snapshot` except the bodies
+#[derive(Serialize, Deserialize)]
+struct SnapshotHead {
+    version: u32,
+    sequence: u64,
+    time: f64,
+    tick: u64,
+    rng_state: (u64, u64),
+    force_config: SerializableForceConfig,
+    integrator_config: SerializableIntegratorConfig,
+    simulation_config: SerializableSimulationConfig,
+    metadata: Option<SnapshotMetadata>,
+}
+
+/// Everything in a `DeltaSnapshot` except the body records
+#[derive(Serialize, Deserialize)]
+struct DeltaHead {
+    base_sequence: u64,
+    sequence: u64,
+    time: f64,
+    tick: u64,
+    removed_body_ids: Vec<u32>,
+}
+
+fn write_header(out: &mut Writer, kind: u8, quantization: Option<Quantization>) -> Result<(), CodecError> {
+    out.buf.extend_from_slice(MAGIC);
+    out.u8(CODEC_VERSION);
+    out.u64(layout_fingerprint());
+    out.u8(kind);
+    match quantization {
+        Some(q) => {
+            q.validate()?;
+            out.u8(FLAG_QUANTIZED);
+            out.f64(q.position_step);
+            out.f64(q.velocity_step);
+        }
+        None => out.u8(0),
+    }
+    Ok(())
+}
+
+fn read_header(input: &mut Reader<'_>, kind: u8) -> Result<Option<Quantization>, CodecError> {
+    if input.take(MAGIC.len())? != MAGIC {
+        return Err("not a binary snapshot".into());
+    }
+    if input.u8()? != CODEC_VERSION {
+        return Err("unsupported binary codec version".into());
+    }
+    if input.u64()? != layout_fingerprint() {
+        return Err("binary snapshot was written by a build with a different body layout".into());
+    }
+    if input.u8()? != kind {
+        return Err(if kind == KIND_SNAPSHOT { "expected a snapshot, found a delta" } else { "expected a delta, found a snapshot" }.into());
+    }
+    match input.u8()? {
+        0 => Ok(None),
+        FLAG_QUANTIZED => {
+            let q = Quantization::new(input.f64()?, input.f64()?);
+            q.validate()?;
+            Ok(Some(q))
+        }
+        _ => Err("unknown binary flags".into()),
+    }
+}
+
+fn finish(input: &Reader<'_>) -> Result<(), CodecError> {
+    if input.remaining() == 0 {
+        Ok(())
+    } else {
+        Err("trailing bytes after binary payload".into())
+    }
+}
+
+/// Write the kinematic and rotational state of a body, optionally relative
+/// to a base body
+fn write_dynamic(out: &mut Writer, body: &Body, base: Option<&Body>, quantization: Option<Quantization>) -> Result<(), CodecError> {
+    match quantization {
+        None => {
+            out.vec3(body.position);
+            out.vec3(body.velocity);
+            out.vec3(body.acceleration);
+            out.vec3(body.prev_acceleration);
+            for c in body.orientation.to_array() {
+                out.f64(c);
+            }
+            out.vec3(body.angular_velocity);
+        }
+        Some(q) => {
+            let (p0, v0) = base.map_or((Vec3::ZERO, Vec3::ZERO), |b| (b.position, b.velocity));
+            out.quantized_vec3(body.position - p0, q.position_step)?;
+            out.quantized_vec3(body.velocity - v0, q.velocity_step)?;
+            let o0 = base.map_or([0.0; 4], |b| b.orientation.to_array());
+            for (c, c0) in body.orientation.to_array().into_iter().zip(o0) {
+                out.quantized(c - c0, ORIENTATION_STEP)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_dynamic(input: &mut Reader<'_>, body: &mut Body, base: Option<&Body>, quantization: Option<Quantization>) -> Result<(), CodecError> {
+    match quantization {
+        None => {
+            body.position = input.vec3()?;
+            body.velocity = input.vec3()?;
+            body.acceleration = input.vec3()?;
+            body.prev_acceleration = input.vec3()?;
+            body.orientation = Quat::new(input.f64()?, input.f64()?, input.f64()?, input.f64()?);
+            body.angular_velocity = input.vec3()?;
+        }
+        Some(q) => {
+            let (p0, v0) = base.map_or((Vec3::ZERO, Vec3::ZERO), |b| (b.position, b.velocity));
+            body.position = p0 + input.quantized_vec3(q.position_step)?;
+            body.velocity = v0 + input.quantized_vec3(q.velocity_step)?;
+            body.acceleration = Vec3::ZERO;
+            body.prev_acceleration = Vec3::ZERO;
+            let o0 = base.map_or([0.0; 4], |b| b.orientation.to_array());
+            let mut o = [0.0; 4];
+            for (c, c0) in o.iter_mut().zip(o0) {
+                *c = c0 + input.quantized(ORIENTATION_STEP)?;
+            }
+            body.orientation = Quat::new(o[0], o[1], o[2], o[3]).normalize();
+            body.align_orientation();
+        }
+    }
+    Ok(())
+}
+
+/// Static record bytes of a body (everything but the kinematic state)
+fn static_record(body: &Body) -> Result<Vec<u8>, CodecError> {
+    let mut out = Writer::default();
+    write_value(&mut out, body)?;
+    Ok(out.buf)
+}
+
+/// Encode a full snapshot
+pub fn encode_snapshot(snapshot: &Snapshot, quantization: Option<Quantization>) -> Result<Vec<u8>, CodecError> {
+    let mut out = Writer::default();
+    write_header(&mut out, KIND_SNAPSHOT, quantization)?;
+
+    let head = SnapshotHead {
+        version: snapshot.version,
+        sequence: snapshot.sequence,
+        time: snapshot.time,
+        tick: snapshot.tick,
+        rng_state: snapshot.rng_state,
+        force_config: snapshot.force_config.clone(),
+        integrator_config: snapshot.integrator_config.clone(),
+        simulation_config: snapshot.simulation_config.clone(),
+        metadata: snapshot.metadata.clone(),
+    };
+    write_value(&mut out, &head)?;
+
+    // Static section
+    out.varint(snapshot.bodies.len() as u64);
+    for body in &snapshot.bodies {
+        write_value(&mut out, body)?;
+    }
+
+    // Dynamic section
+    for body in &snapshot.bodies {
+        write_dynamic(&mut out, body, None, quantization)?;
+    }
+
+    Ok(out.buf)
+}
+
+/// Decode a full snapshot (no version migration is applied here)
+pub fn decode_snapshot(bytes: &[u8]) -> Result<Snapshot, CodecError> {
+    let mut input = Reader::new(bytes);
+    let quantization = read_header(&mut input, KIND_SNAPSHOT)?;
+    let head: SnapshotHead = read_value(&mut input)?;
+
+    let count = input.len()?;
+    let mut bodies = Vec::with_capacity(count.min(input.remaining()));
+    for _ in 0..count {
+        bodies.push(read_value::<Body>(&mut input)?);
+    }
+    for body in &mut bodies {
+        read_dynamic(&mut input, body, None, quantization)?;
+    }
+    finish(&input)?;
+
+    Ok(Snapshot {
+        version: head.version,
+        sequence: head.sequence,
+        time: head.time,
+        tick: head.tick,
+        rng_state: head.rng_state,
+        bodies,
+        force_config: head.force_config,
+        integrator_config: head.integrator_config,
+        simulation_config: head.simulation_config,
+        metadata: head.metadata,
+    })
+}
+
+/// Encode a delta relative to the snapshot it was diffed against.
+///
+/// Bodies whose static record matches the base are sent as dynamic-only
+/// records; new bodies and bodies with changed static properties are sent
+/// in full.
+pub fn encode_delta(delta: &DeltaSnapshot, base: &Snapshot, quantization: Option<Quantization>) -> Result<Vec<u8>, CodecError> {
+    if delta.base_sequence != base.sequence {
+        return Err("delta does not apply to this base snapshot".into());
+    }
+
+    let mut out = Writer::default();
+    write_header(&mut out, KIND_DELTA, quantization)?;
+
+    let head = DeltaHead {
+        base_sequence: delta.base_sequence,
+        sequence: delta.sequence,
+        time: delta.time,
+        tick: delta.tick,
+        removed_body_ids: delta.removed_body_ids.clone(),
+    };
+    write_value(&mut out, &head)?;
+
+    out.varint(delta.changed_bodies.len() as u64);
+    for body in &delta.changed_bodies {
+        let base_body = base.bodies.iter().find(|b| b.id == body.id);
+        let record = static_record(body)?;
+        out.varint(body.id as u64);
+        match base_body {
+            Some(old) if static_record(old)? == record => {
+                out.u8(RECORD_DYNAMIC);
+                write_dynamic(&mut out, body, Some(old), quantization)?;
+            }
+            _ => {
+                out.u8(RECORD_FULL);
+                out.buf.extend_from_slice(&record);
+                write_dynamic(&mut out, body, None, quantization)?;
+            }
+        }
+    }
+
+    Ok(out.buf)
+}
+
+/// Decode a delta produced by `encode_delta` against the same base snapshot
+pub fn decode_delta(bytes: &[u8], base: &Snapshot) -> Result<DeltaSnapshot, CodecError> {
+    let mut input = Reader::new(bytes);
+    let quantization = read_header(&mut input, KIND_DELTA)?;
+    let head: DeltaHead = read_value(&mut input)?;
+    if head.base_sequence != base.sequence {
+        return Err("delta does not apply to this base snapshot".into());
+    }
+
+    let count = input.len()?;
+    let mut changed_bodies = Vec::with_capacity(count.min(input.remaining()));
+    for _ in 0..count {
+        let id = u32::try_from(input.varint()?).map_err(|_| CodecError::from("invalid body id"))?;
+        let body = match input.u8()? {
+            RECORD_DYNAMIC => {
+                let old = base
+                    .bodies
+                    .iter()
+                    .find(|b| b.id == id)
+                    .ok_or(CodecError::from("delta references a body missing from the base"))?;
+                let mut body = old.clone();
+                read_dynamic(&mut input, &mut body, Some(old), quantization)?;
+                body
+            }
+            RECORD_FULL => {
+                let mut body: Body = read_value(&mut input)?;
+                if body.id != id {
+                    return Err("delta record id mismatch".into());
+                }
+                read_dynamic(&mut input, &mut body, None, quantization)?;
+                body
+            }
+            _ => return Err("unknown delta record tag".into()),
+        };
+        changed_bodies.push(body);
+    }
+    finish(&input)?;
+
+    Ok(DeltaSnapshot {
+        base_sequence: head.base_sequence,
+        sequence: head.sequence,
+        time: head.time,
+        tick: head.tick,
+        changed_bodies,
+        removed_body_ids: head.removed_body_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::{Atmosphere, BodyType, RingParameters};
+
+    #[test]
+    fn test_varint_zigzag_roundtrip() {
+        let mut out = Writer::default();
+        let values = [0i64, 1, -1, 63, -64, 1 << 40, i64::MIN, i64::MAX];
+        for v in values {
+            out.zigzag(v);
+        }
+        out.varint(u64::MAX);
+
+        let mut input = Reader::new(&out.buf);
+        for v in values {
+            assert_eq!(input.zigzag().unwrap(), v);
+        }
+        assert_eq!(input.varint().unwrap(), u64::MAX);
+        assert_eq!(input.remaining(), 0);
+    }
+
+    #[test]
+    fn test_static_record_skips_dynamic_fields() {
+        let mut body = Body::new(3, "Saturn", BodyType::Planet, 5.68e26, 5.8e7, Vec3::new(1.0, 2.0, 3.0), Vec3::new(4.0, 5.0, 6.0));
+        body.atmosphere = Some(Atmosphere::earth_like());
+        body.rings = Some(RingParameters {
+            inner_radius_mult: 1.2,
+            outer_radius_mult: 2.3,
+            texture_preset: "saturn".to_string(),
+            base_opacity: 0.8,
+        });
+        body.pole_ra = Some(0.7);
+        body.parent_id = Some(0);
+
+        let record = static_record(&body).unwrap();
+        let mut moved = body.clone();
+        moved.position = Vec3::new(-9.0, 0.5, 1e12);
+        moved.acceleration = Vec3::X;
+        assert_eq!(record, static_record(&moved).unwrap());
+
+        let decoded: Body = read_value(&mut Reader::new(&record)).unwrap();
+        assert_eq!(decoded.position, Vec3::ZERO);
+        assert_eq!(static_record(&decoded).unwrap(), record);
+        assert_eq!(decoded.rings.unwrap().texture_preset, "saturn");
+    }
+
+    #[test]
+    fn test_collision_outcome_indices_are_stable() {
+        use crate::collision::CollisionOutcome;
+        // Variants are written by index; new ones must be appended
+        let outcomes = [CollisionOutcome::Merge, CollisionOutcome::Bounce, CollisionOutcome::Ignore, CollisionOutcome::Fragment];
+        for (index, outcome) in outcomes.into_iter().enumerate() {
+            let mut out = Writer::default();
+            write_value(&mut out, &outcome).unwrap();
+            assert_eq!(out.buf, [index as u8]);
+        }
+    }
+
+    #[test]
+    fn test_corrupt_input_rejected() {
+        assert!(decode_snapshot(b"").is_err());
+        assert!(decode_snapshot(b"JSON{}").is_err());
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(CODEC_VERSION);
+        bytes.extend_from_slice(&layout_fingerprint().to_le_bytes());
+        bytes.extend_from_slice(&[KIND_SNAPSHOT, 0, 0xff]);
+        assert!(decode_snapshot(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_layout_mismatch_rejected() {
+        let snapshot = crate::simulation::Simulation::new(1).snapshot();
+        let mut bytes = encode_snapshot(&snapshot, None).unwrap();
+        assert!(decode_snapshot(&bytes).is_ok());
+        // A build whose Body or config layout differs writes another fingerprint
+        bytes[MAGIC.len() + 1] ^= 1;
+        let err = decode_snapshot(&bytes).unwrap_err();
+        assert!(err.to_string().contains("different body layout"), "{}", err);
+
+        // The fingerprint sees field names and enum variants, not just types
+        let mut a = LayoutTracer::new();
+        a.trace::<Body>().unwrap();
+        let mut b = LayoutTracer::new();
+        b.trace::<crate::body::Atmosphere>().unwrap();
+        assert_ne!(a.hash, b.hash);
+        assert_eq!(layout_fingerprint(), layout_fingerprint());
+    }
+}
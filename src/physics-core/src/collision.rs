@@ -0,0 +1,353 @@
+//! Collision detection and response
+//!
+//! Bodies are treated as spheres of `Body::radius`. Detection is swept over
+//! the whole tick: each body's motion is approximated by the straight segment
+//! between its pre- and post-step positions, so fast bodies can't tunnel
+//! through each other between samples. A sort-and-sweep on the swept x-extent
+//! keeps the broad phase close to O(N log N).
+//!
+//! Each `BodyType` is assigned a `CollisionOutcome`. When two types disagree
+//! the more conservative outcome wins (Ignore > Bounce > Fragment > Merge),
+//! so marking test particles or players as Ignore keeps them out of every
+//! collision. Fragmenting impacts are resolved by the `fragmentation` module.
+
+use crate::body::{Body, BodyType};
+use crate::fragmentation::DEFAULT_DEBRIS_FRAGMENTS;
+use crate::vector::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Number of `BodyType` variants (outcome table size)
+const BODY_TYPE_COUNT: usize = 8;
+
+/// What happens when two bodies touch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollisionOutcome {
+    /// Perfect merge conserving mass and linear momentum
+    Merge,
+    /// Bounce along the contact normal with the configured restitution
+    Bounce,
+    /// Pass through (only softening keeps forces finite)
+    Ignore,
+    /// Merge, hit-and-run or break up depending on the impact energy.
+    /// Declared last so the binary codec's variant indices stay stable.
+    Fragment,
+}
+
+impl CollisionOutcome {
+    /// Precedence when two body types disagree (higher wins)
+    fn precedence(self) -> u8 {
+        match self {
+            Self::Merge => 0,
+            Self::Fragment => 1,
+            Self::Bounce => 2,
+            Self::Ignore => 3,
+        }
+    }
+
+    /// Lowercase name used in events and the WASM API
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Fragment => "fragment",
+            Self::Bounce => "bounce",
+            Self::Ignore => "ignore",
+        }
+    }
+
+    /// Parse a lowercase name ("merge", "fragment", "bounce", "ignore")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "merge" => Some(Self::Merge),
+            "fragment" => Some(Self::Fragment),
+            "bounce" => Some(Self::Bounce),
+            "ignore" => Some(Self::Ignore),
+            _ => None,
+        }
+    }
+}
+
+/// Collision configuration
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionConfig {
+    /// Master switch (off by default; bodies pass through each other)
+    pub enabled: bool,
+
+    /// Outcome per body type, indexed by `BodyType as usize`
+    pub outcomes: [CollisionOutcome; BODY_TYPE_COUNT],
+
+    /// Coefficient of restitution for bounces (0 = perfectly inelastic, 1 = elastic)
+    pub restitution: f64,
+
+    /// Debris bodies per fragmenting impact (rounded up to an even number)
+    pub debris: u32,
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        let mut outcomes = [CollisionOutcome::Merge; BODY_TYPE_COUNT];
+        outcomes[BodyType::Spacecraft as usize] = CollisionOutcome::Bounce;
+        outcomes[BodyType::TestParticle as usize] = CollisionOutcome::Ignore;
+        outcomes[BodyType::Player as usize] = CollisionOutcome::Ignore;
+        Self {
+            enabled: false,
+            outcomes,
+            restitution: 0.5,
+            debris: DEFAULT_DEBRIS_FRAGMENTS,
+        }
+    }
+}
+
+impl CollisionConfig {
+    /// Outcome configured for a single body type
+    pub fn outcome(&self, body_type: BodyType) -> CollisionOutcome {
+        self.outcomes[body_type as usize]
+    }
+
+    /// Set the outcome for a body type
+    pub fn set_outcome(&mut self, body_type: BodyType, outcome: CollisionOutcome) {
+        self.outcomes[body_type as usize] = outcome;
+    }
+
+    /// Resolve the outcome for a pair of body types
+    pub fn pair_outcome(&self, a: BodyType, b: BodyType) -> CollisionOutcome {
+        let (oa, ob) = (self.outcome(a), self.outcome(b));
+        if oa.precedence() >= ob.precedence() { oa } else { ob }
+    }
+}
+
+/// Collision event for the event queue and snapshot metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollisionEvent {
+    pub id: u64,
+    /// Simulation time of first contact in seconds
+    pub time: f64,
+    /// The two bodies involved (survivor first for merges)
+    pub body_ids: Vec<u32>,
+    /// "merge", "bounce", or a fragmentation regime ("hit_and_run",
+    /// "partial_accretion", "erosion")
+    pub outcome: String,
+    /// Relative speed at contact in m/s
+    pub relative_speed: f64,
+    /// Debris created by a fragmenting impact
+    #[serde(default)]
+    pub fragment_ids: Vec<u32>,
+}
+
+/// A detected contact between bodies `i` and `j` (indices into the body slice)
+#[derive(Debug, Clone, Copy)]
+pub struct Contact {
+    pub i: usize,
+    pub j: usize,
+    /// Fraction of the tick at first contact, in [0, 1]
+    pub fraction: f64,
+}
+
+/// Whether a body can take part in collisions at all
+#[inline]
+fn collidable(body: &Body, config: &CollisionConfig) -> bool {
+    body.is_active && body.radius > 0.0 && config.outcome(body.body_type) != CollisionOutcome::Ignore
+}
+
+/// Earliest fraction t ∈ [0, 1] at which |d0 + t·(d1 − d0)| ≤ reach, if any.
+fn first_contact(d0: Vec3, d1: Vec3, reach: f64) -> Option<f64> {
+    let c = d0.length_squared() - reach * reach;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let motion = d1 - d0;
+    let a = motion.length_squared();
+    if a <= 0.0 {
+        return None;
+    }
+    let b = d0.dot(motion);
+    if b >= 0.0 {
+        // Moving apart over the whole tick
+        return None;
+    }
+    let disc = b * b - a * c;
+    if disc < 0.0 {
+        return None;
+    }
+    let t = (-b - disc.sqrt()) / a;
+    (t <= 1.0).then_some(t.max(0.0))
+}
+
+/// Find all contacts during a tick, sorted by time of first contact.
+///
+/// `previous` holds each body's position at the start of the tick; the
+/// current `body.position` is taken as the end of the swept segment.
+pub fn detect_collisions(bodies: &[Body], previous: &[Vec3], config: &CollisionConfig) -> Vec<Contact> {
+    // Broad phase: sort by the low end of each swept x-interval
+    let mut spans: Vec<(f64, f64, usize)> = bodies
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| collidable(b, config))
+        .map(|(i, b)| {
+            let (x0, x1) = (previous[i].x, b.position.x);
+            (x0.min(x1) - b.radius, x0.max(x1) + b.radius, i)
+        })
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.2.cmp(&b.2)));
+
+    let mut contacts = Vec::new();
+    for (k, &(_, hi, i)) in spans.iter().enumerate() {
+        for &(lo_j, _, j) in &spans[k + 1..] {
+            if lo_j > hi {
+                break;
+            }
+            let (bi, bj) = (&bodies[i], &bodies[j]);
+            if config.pair_outcome(bi.body_type, bj.body_type) == CollisionOutcome::Ignore {
+                continue;
+            }
+            let d0 = previous[j] - previous[i];
+            let d1 = bj.position - bi.position;
+            if let Some(fraction) = first_contact(d0, d1, bi.radius + bj.radius) {
+                let (i, j) = if i < j { (i, j) } else { (j, i) };
+                contacts.push(Contact { i, j, fraction });
+            }
+        }
+    }
+
+    contacts.sort_by(|a, b| {
+        a.fraction.total_cmp(&b.fraction).then(a.i.cmp(&b.i)).then(a.j.cmp(&b.j))
+    });
+    contacts
+}
+
+/// Merge body `b` into body `a` (in place), conserving mass and momentum.
+/// Volume is conserved for the radius; `b` is deactivated.
+pub fn merge_bodies(a: &mut Body, b: &mut Body) {
+    let total = a.mass + b.mass;
+    if total > 0.0 {
+        a.position = (a.position * a.mass + b.position * b.mass) / total;
+        a.velocity = (a.velocity * a.mass + b.velocity * b.mass) / total;
+    }
+    a.mass = total;
+    a.radius = (a.radius.powi(3) + b.radius.powi(3)).cbrt();
+
+    // Re-derive bulk properties for the new mass and radius
+    a.bulk_density = 0.0;
+    a.surface_gravity = 0.0;
+    a.escape_velocity_surface = 0.0;
+    a.compute_derived();
+
+    b.is_active = false;
+}
+
+/// Bounce two overlapping bodies off each other along the line of centers.
+///
+/// Applies an impulse with coefficient of restitution `e` (only if they are
+/// approaching) and separates them so they no longer overlap. Massless bodies
+/// reflect off massive ones.
+pub fn bounce_bodies(a: &mut Body, b: &mut Body, e: f64) {
+    let (normal, dist) = (b.position - a.position).normalize_with_length();
+    let normal = if dist > 0.0 { normal } else { Vec3::X };
+
+    let (wa, wb) = match (a.mass > 0.0, b.mass > 0.0) {
+        (true, true) => (1.0 / a.mass, 1.0 / b.mass),
+        (false, true) => (1.0, 0.0),
+        (true, false) => (0.0, 1.0),
+        (false, false) => (1.0, 1.0),
+    };
+
+    let approach = (b.velocity - a.velocity).dot(normal);
+    if approach < 0.0 {
+        let impulse = -(1.0 + e) * approach / (wa + wb);
+        a.velocity -= normal * (impulse * wa);
+        b.velocity += normal * (impulse * wb);
+    }
+
+    let overlap = a.radius + b.radius - dist;
+    if overlap > 0.0 {
+        a.position -= normal * (overlap * wa / (wa + wb));
+        b.position += normal * (overlap * wb / (wa + wb));
+    }
+}
+
+/// Whether `a` survives a merge with `b` (heavier body wins, then larger, then lower id)
+pub fn survives(a: &Body, b: &Body) -> bool {
+    if a.mass != b.mass {
+        return a.mass > b.mass;
+    }
+    if a.radius != b.radius {
+        return a.radius > b.radius;
+    }
+    a.id < b.id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+
+    fn rock(id: u32, mass: f64, radius: f64, pos: Vec3, vel: Vec3) -> Body {
+        Body::new(id, "rock", BodyType::Asteroid, mass, radius, pos, vel)
+    }
+
+    fn enabled() -> CollisionConfig {
+        CollisionConfig { enabled: true, ..CollisionConfig::default() }
+    }
+
+    #[test]
+    fn test_pair_outcome_precedence() {
+        let config = CollisionConfig::default();
+        assert_eq!(config.pair_outcome(BodyType::Star, BodyType::Comet), CollisionOutcome::Merge);
+        assert_eq!(config.pair_outcome(BodyType::Planet, BodyType::Spacecraft), CollisionOutcome::Bounce);
+        assert_eq!(config.pair_outcome(BodyType::Spacecraft, BodyType::Player), CollisionOutcome::Ignore);
+
+        let mut config = config;
+        config.set_outcome(BodyType::Asteroid, CollisionOutcome::Fragment);
+        assert_eq!(config.pair_outcome(BodyType::Planet, BodyType::Asteroid), CollisionOutcome::Fragment);
+        assert_eq!(config.pair_outcome(BodyType::Asteroid, BodyType::Spacecraft), CollisionOutcome::Bounce);
+    }
+
+    #[test]
+    fn test_swept_detection_catches_tunnelling() {
+        // The bodies swap sides within one tick without ever being sampled together
+        let bodies = vec![
+            rock(0, 1e15, 1000.0, Vec3::new(1e5, 0.0, 0.0), Vec3::ZERO),
+            rock(1, 1e15, 1000.0, Vec3::new(-1e5, 0.0, 0.0), Vec3::ZERO),
+        ];
+        let previous = vec![Vec3::new(-1e5, 0.0, 0.0), Vec3::new(1e5, 0.0, 0.0)];
+        let contacts = detect_collisions(&bodies, &previous, &enabled());
+        assert_eq!(contacts.len(), 1);
+        assert!((contacts[0].fraction - 0.495).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_contact_when_missing() {
+        let bodies = vec![
+            rock(0, 1e15, 1000.0, Vec3::new(1e5, 0.0, 0.0), Vec3::ZERO),
+            rock(1, 1e15, 1000.0, Vec3::new(-1e5, 5000.0, 0.0), Vec3::ZERO),
+        ];
+        let previous = vec![Vec3::new(-1e5, 0.0, 0.0), Vec3::new(1e5, 5000.0, 0.0)];
+        assert!(detect_collisions(&bodies, &previous, &enabled()).is_empty());
+    }
+
+    #[test]
+    fn test_merge_conserves_mass_and_momentum() {
+        let mut a = rock(0, 3.0 * M_MOON, R_MOON, Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0));
+        let mut b = rock(1, M_MOON, R_MOON, Vec3::new(R_MOON, 0.0, 0.0), Vec3::new(-300.0, 50.0, 0.0));
+        let p_before = a.velocity * a.mass + b.velocity * b.mass;
+        let m_before = a.mass + b.mass;
+
+        merge_bodies(&mut a, &mut b);
+
+        assert!(!b.is_active);
+        assert_eq!(a.mass, m_before);
+        assert!((a.velocity * a.mass - p_before).length() < 1e-9 * p_before.length());
+        assert!((a.radius - R_MOON * 2.0f64.cbrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bounce_restitution() {
+        let mut a = rock(0, 1e12, 10.0, Vec3::ZERO, Vec3::new(1.0, 0.0, 0.0));
+        let mut b = rock(1, 1e12, 10.0, Vec3::new(19.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+
+        bounce_bodies(&mut a, &mut b, 0.5);
+
+        assert!((a.velocity.x + 0.5).abs() < 1e-12);
+        assert!((b.velocity.x - 0.5).abs() < 1e-12);
+        assert!(b.position.x - a.position.x >= 20.0 - 1e-9);
+    }
+}
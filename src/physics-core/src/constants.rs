@@ -45,6 +45,33 @@ pub const DEFAULT_SOFTENING: f64 = 10_000.0;
 /// θ < 0.5 is accurate, θ > 1.0 is fast but inaccurate
 pub const DEFAULT_BARNES_HUT_THETA: f64 = 0.5;
 
+/// Default FMM expansion order (highest multipole degree kept)
+/// Error falls roughly as θ^(p+1); cost grows as p⁶ per cell pair.
+/// At p = 3 FMM already outruns Barnes-Hut from a few thousand bodies with
+/// several times lower error.
+pub const DEFAULT_FMM_ORDER: u32 = 3;
+
+/// Default FMM opening criterion: cells interact through their expansions
+/// when (r_A + r_B) < θ·d
+pub const DEFAULT_FMM_THETA: f64 = 0.5;
+
+/// Default IAS15 tolerance: steps are (5040·ε)^(1/7) ≈ 0.18 of the shortest
+/// acceleration timescale, keeping per-step errors below round-off
+pub const DEFAULT_IAS15_EPSILON: f64 = 1e-9;
+
+/// Default adaptive substep fraction: substeps last 0.02 of the shortest
+/// dynamical timescale (~300 per circular orbit)
+pub const DEFAULT_ADAPTIVE_ETA: f64 = 0.02;
+
+/// Default cap on adaptive substeps per tick
+pub const DEFAULT_MAX_SUBSTEPS: u32 = 1024;
+
+/// Default drag coefficient C_d for bodies without one (satellites ~2.2)
+pub const DEFAULT_DRAG_COEFFICIENT: f64 = 2.2;
+
+/// Default thermal inertia in J m⁻² K⁻¹ s⁻½ (km-sized asteroids)
+pub const DEFAULT_THERMAL_INERTIA: f64 = 200.0;
+
 /// Maximum number of massive bodies
 pub const MAX_MASSIVE_BODIES: usize = 100;
 
@@ -78,6 +105,9 @@ pub const OMEGA_EARTH: f64 = 7.2921159e-5;
 /// Earth axial tilt in radians (~23.44°)
 pub const AXIAL_TILT_EARTH: f64 = 0.4091;
 
+/// Mean obliquity of the ecliptic at J2000 in radians (23.4392911°)
+pub const OBLIQUITY_J2000: f64 = 0.409_092_804_222_329;
+
 // ─── Thermodynamic & molecular constants ────────────────────────
 
 /// Boltzmann constant k_B in J/K (CODATA 2018, exact by redefinition)
@@ -0,0 +1,174 @@
+//! Tidal disruption at the Roche limit
+//!
+//! With disruption enabled, a planet, moon, asteroid or comet that comes
+//! within `planet::roche_limit` of a heavier body is torn apart: it is
+//! deactivated and replaced by `fragments` equal-mass pieces of the same
+//! density, radius R / N^⅓. The pieces are scattered through the body's
+//! volume and leave with random velocities of up to its surface escape
+//! speed on top of its orbital motion and spin, drawn from a `Pcg32` seeded
+//! with `Body::seed`, so the same body always breaks up the same way. Both
+//! offsets are recentred so the fragments carry exactly the body's mass,
+//! centre of mass and momentum. Orbital shear then draws the swarm out
+//! along the orbit into a ring.
+//!
+//! Fragments are marked `is_fragment` and are never disrupted again, so a
+//! swarm inside the limit does not keep splitting.
+
+use crate::body::{Body, BodyId, BodyType};
+use crate::constants::G;
+use crate::planet::roche_limit;
+use crate::prng::Pcg32;
+use crate::vector::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Default number of fragments per disruption
+pub const DEFAULT_DISRUPTION_FRAGMENTS: u32 = 16;
+
+/// Tidal disruption settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DisruptionConfig {
+    /// Master switch (off by default)
+    pub enabled: bool,
+    /// Fragments each disrupted body breaks into (at least 2)
+    pub fragments: u32,
+}
+
+impl Default for DisruptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fragments: DEFAULT_DISRUPTION_FRAGMENTS,
+        }
+    }
+}
+
+/// Disruption event for the event queue and snapshot metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisruptionEvent {
+    pub id: u64,
+    /// Simulation time in seconds
+    pub time: f64,
+    /// The disrupted body
+    pub body_id: BodyId,
+    /// The body whose tides tore it apart
+    pub primary_id: BodyId,
+    /// The fragments that replaced it
+    pub fragment_ids: Vec<BodyId>,
+    /// Distance from the primary in meters
+    pub distance: f64,
+    /// Roche limit in meters
+    pub roche_limit: f64,
+}
+
+/// Whether `body` can be tidally disrupted
+pub fn can_disrupt(body: &Body) -> bool {
+    body.is_active
+        && !body.is_fragment
+        && matches!(body.body_type, BodyType::Planet | BodyType::Moon | BodyType::Asteroid | BodyType::Comet)
+        && body.mass > 0.0
+        && body.radius > 0.0
+        && body.bulk_density > 0.0
+}
+
+/// Bodies inside the Roche limit of a heavier body, as
+/// (body index, primary index, distance, Roche limit). Each body is paired
+/// with the primary it is deepest inside the limit of.
+pub fn find_disruptions(bodies: &[Body]) -> Vec<(usize, usize, f64, f64)> {
+    let mut found = Vec::new();
+    for (i, body) in bodies.iter().enumerate() {
+        if !can_disrupt(body) {
+            continue;
+        }
+        let mut deepest: Option<(usize, f64, f64)> = None;
+        for (j, primary) in bodies.iter().enumerate() {
+            if j == i || !primary.is_active || !primary.contributes_gravity || primary.mass <= body.mass {
+                continue;
+            }
+            let limit = roche_limit(primary, body);
+            let distance = body.position.distance(primary.position);
+            if distance < limit && deepest.is_none_or(|(_, d, l)| distance / limit < d / l) {
+                deepest = Some((j, distance, limit));
+            }
+        }
+        if let Some((j, distance, limit)) = deepest {
+            found.push((i, j, distance, limit));
+        }
+    }
+    found
+}
+
+/// Random point in the unit ball
+fn unit_ball(rng: &mut Pcg32) -> Vec3 {
+    loop {
+        let p = Vec3::new(rng.next_f64_range(-1.0, 1.0), rng.next_f64_range(-1.0, 1.0), rng.next_f64_range(-1.0, 1.0));
+        if p.length_squared() <= 1.0 {
+            return p;
+        }
+    }
+}
+
+/// Break `body` into `count` fragments (at least 2), built with
+/// `Body::as_fragment`.
+pub fn fragment_body(body: &Body, count: u32) -> Vec<Body> {
+    let count = count.max(2) as usize;
+    let mut rng = Pcg32::new(body.seed);
+    let mass = body.mass / count as f64;
+    let radius = body.radius / (count as f64).cbrt();
+    let escape_speed = (2.0 * G * body.mass / body.radius).sqrt();
+
+    let mut offsets: Vec<Vec3> = (0..count).map(|_| unit_ball(&mut rng) * (body.radius - radius)).collect();
+    let mut kicks: Vec<Vec3> = (0..count).map(|_| unit_ball(&mut rng) * escape_speed).collect();
+    let mean_offset = offsets.iter().fold(Vec3::ZERO, |sum, &p| sum + p) / count as f64;
+    let mean_kick = kicks.iter().fold(Vec3::ZERO, |sum, &v| sum + v) / count as f64;
+    for (offset, kick) in offsets.iter_mut().zip(kicks.iter_mut()) {
+        *offset -= mean_offset;
+        *kick -= mean_kick;
+    }
+
+    let spin = body.spin_axis() * body.rotation_rate;
+    offsets
+        .into_iter()
+        .zip(kicks)
+        .enumerate()
+        .map(|(k, (offset, kick))| {
+            let mut fragment = body.as_fragment(format!("{}_{}", body.name, k + 1), mass, radius, rng.next_u64());
+            fragment.position = body.position + offset;
+            fragment.velocity = body.velocity + spin.cross(offset) + kick;
+            fragment
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moon() -> Body {
+        let mut moon = Body::new(3, "Moonlet", BodyType::Moon, 4.0e18, 1.0e5, Vec3::new(1.0e8, 2.0e7, 0.0), Vec3::new(-3.0e3, 1.7e4, 50.0));
+        moon.seed = 99;
+        moon.rotation_rate = 1.0e-4;
+        moon.compute_derived();
+        moon
+    }
+
+    #[test]
+    fn test_fragments_conserve_mass_and_momentum() {
+        let moon = moon();
+        let fragments = fragment_body(&moon, 12);
+        assert_eq!(fragments.len(), 12);
+        let mass: f64 = fragments.iter().map(|f| f.mass).sum();
+        let center = fragments.iter().fold(Vec3::ZERO, |sum, f| sum + f.position * f.mass) / mass;
+        let momentum = fragments.iter().fold(Vec3::ZERO, |sum, f| sum + f.velocity * f.mass);
+        assert!((mass / moon.mass - 1.0).abs() < 1e-12);
+        assert!((center - moon.position).length() < 1e-6);
+        assert!((momentum - moon.velocity * moon.mass).length() < 1e-12 * moon.mass * moon.velocity.length());
+        for f in &fragments {
+            assert!(f.is_fragment);
+            assert!((f.position - moon.position).length() < moon.radius);
+            assert!(!can_disrupt(f));
+        }
+        // Deterministic from the seed
+        let again = fragment_body(&moon, 12);
+        assert!(fragments.iter().zip(&again).all(|(a, b)| a.position == b.position && a.velocity == b.velocity));
+    }
+}
@@ -0,0 +1,129 @@
+//! Atmospheric drag
+//!
+//! Small bodies (spacecraft, players, asteroids, comets) moving through a
+//! body's `Atmosphere` feel
+//!
+//!   a = −½ ρ(h) (C_d A / m) |v_rel| v_rel,   ρ(h) = ρ₀ exp(−h / H)
+//!
+//! with h the altitude above the body's radius, ρ₀ the atmosphere's
+//! `surface_density` and H its `scale_height`. The atmosphere co-rotates
+//! with the body at `rotation_rate` about `spin_axis()`, so v_rel is the
+//! velocity relative to the local air. A single exponential is a fair fit
+//! to Earth's atmosphere up to ~200 km and falls off much faster than the
+//! real thermosphere above that.
+//!
+//! The equal and opposite force acts on the atmospheric body, so momentum
+//! is conserved.
+
+use crate::body::{Body, BodyType};
+use crate::constants::DEFAULT_DRAG_COEFFICIENT;
+use crate::vector::Vec3;
+
+/// Above this many scale heights the density (< 2e-22 ρ₀) is treated as zero
+pub const DRAG_CUTOFF_SCALE_HEIGHTS: f64 = 50.0;
+
+/// Whether `body_type` is subject to atmospheric drag
+pub fn feels_drag(body_type: BodyType) -> bool {
+    matches!(body_type, BodyType::Spacecraft | BodyType::Player | BodyType::Asteroid | BodyType::Comet)
+}
+
+/// C_d·A/m in m²/kg, from the body's drag settings and its mass (or its
+/// bulk density for massless bodies)
+pub fn ballistic_coefficient(body: &Body) -> Option<f64> {
+    if body.radius <= 0.0 && body.drag_area <= 0.0 {
+        return None;
+    }
+    let mass = if body.mass > 0.0 {
+        body.mass
+    } else {
+        4.0 / 3.0 * std::f64::consts::PI * body.radius.powi(3) * body.bulk_density
+    };
+    if mass <= 0.0 {
+        return None;
+    }
+    let cd = if body.drag_coefficient > 0.0 { body.drag_coefficient } else { DEFAULT_DRAG_COEFFICIENT };
+    let area = if body.drag_area > 0.0 { body.drag_area } else { std::f64::consts::PI * body.radius * body.radius };
+    Some(cd * area / mass)
+}
+
+/// Atmospheric density (kg/m³) of `planet` at `offset` from its center
+pub fn atmospheric_density(planet: &Body, offset: Vec3) -> f64 {
+    let Some(atmosphere) = planet.atmosphere.as_ref() else {
+        return 0.0;
+    };
+    if atmosphere.surface_density <= 0.0 || atmosphere.scale_height <= 0.0 {
+        return 0.0;
+    }
+    let altitude = (offset.length() - planet.radius).max(0.0);
+    if altitude > DRAG_CUTOFF_SCALE_HEIGHTS * atmosphere.scale_height {
+        return 0.0;
+    }
+    atmosphere.surface_density * (-altitude / atmosphere.scale_height).exp()
+}
+
+/// Add drag on every drag-feeling body inside any atmosphere
+pub fn apply_atmospheric_drag(bodies: &mut [Body]) {
+    let atmospheres: Vec<usize> = (0..bodies.len())
+        .filter(|&i| bodies[i].is_active && bodies[i].atmosphere.is_some_and(|a| a.surface_density > 0.0))
+        .collect();
+    if atmospheres.is_empty() {
+        return;
+    }
+
+    for i in 0..bodies.len() {
+        let body = &bodies[i];
+        if !body.is_active || !body.feels_gravity || !feels_drag(body.body_type) {
+            continue;
+        }
+        let Some(ballistic) = ballistic_coefficient(body) else {
+            continue;
+        };
+        for &p in &atmospheres {
+            if p == i {
+                continue;
+            }
+            let planet = &bodies[p];
+            let offset = bodies[i].position - planet.position;
+            let density = atmospheric_density(planet, offset);
+            if density == 0.0 {
+                continue;
+            }
+            let air_velocity = planet.velocity + (planet.spin_axis() * planet.rotation_rate).cross(offset);
+            let relative = bodies[i].velocity - air_velocity;
+            let acc = relative * (-0.5 * density * ballistic * relative.length());
+
+            let (mass, planet_mass) = (bodies[i].mass, planet.mass);
+            bodies[i].acceleration += acc;
+            if mass > 0.0 && planet_mass > 0.0 && bodies[p].feels_gravity {
+                bodies[p].acceleration -= acc * (mass / planet_mass);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::Atmosphere;
+    use crate::constants::*;
+
+    #[test]
+    fn test_drag_opposes_motion_through_corotating_air() {
+        let mut earth = Body::new(0, "Earth", BodyType::Planet, M_EARTH, R_EARTH, Vec3::ZERO, Vec3::ZERO);
+        earth.atmosphere = Some(Atmosphere::earth_like());
+        earth.rotation_rate = OMEGA_EARTH;
+        let r = R_EARTH + 100_000.0;
+        let speed = (G * M_EARTH / r).sqrt();
+        let capsule = Body::new(1, "Capsule", BodyType::Spacecraft, 5000.0, 2.0, Vec3::new(r, 0.0, 0.0), Vec3::new(0.0, speed, 0.0));
+        let mut bodies = vec![earth, capsule];
+        apply_atmospheric_drag(&mut bodies);
+
+        let density = 1.225 * (-100_000.0_f64 / 8500.0).exp();
+        let relative = speed - OMEGA_EARTH * r;
+        let expected = -0.5 * density * 2.2 * std::f64::consts::PI * 4.0 / 5000.0 * relative * relative;
+        assert!((bodies[1].acceleration.y - expected).abs() < 1e-12 * expected.abs());
+        assert_eq!(bodies[1].acceleration.x, 0.0);
+        // Momentum is conserved
+        assert!((bodies[0].acceleration * M_EARTH + bodies[1].acceleration * 5000.0).length() < 1e-15);
+    }
+}
@@ -0,0 +1,838 @@
+//! Fast Multipole Method for O(N) gravitational force calculation
+//!
+//! Cartesian Taylor-series FMM with a dual tree walk (Dehnen 2002/2014).
+//! Each cell carries multipole moments about its center of mass; pairs of
+//! well-separated cells interact through a multipole-to-local (M2L)
+//! translation, and the accumulated local expansions are shifted down the
+//! tree (L2L) and evaluated at each body (L2P). Pairs that are too close fall
+//! back to softened direct summation (P2P) with the same per-pair softening
+//! as `compute_accelerations_direct`.
+//!
+//! Unlike Barnes-Hut, which evaluates a multipole once per body, the field of
+//! a distant cell is evaluated once per *cell*, so the cost grows linearly
+//! with N at a fixed expansion order.
+//!
+//! Notation (multi-indices α = (t, u, v), α! = t!·u!·v!, xᵅ = xᵗ·yᵘ·zᵛ):
+//! - Multipole about z_A:  M_α = Σ m_j (x_j − z_A)ᵅ / α!
+//! - Local about z_B:      Φ(z_B + y) = Σ L_β yᵝ
+//! - M2L:                  L_β = −G/β! · Σ_α (−1)^|α| M_α D_(α+β)(z_B − z_A)
+//!
+//! where D_α = ∂ᵅ(1/r) is evaluated with the McMurchie-Davidson recurrence.
+//!
+//! Reference: W. Dehnen, "A fast multipole method for stellar dynamics",
+//! Computational Astrophysics and Cosmology 1:1 (2014)
+
+use crate::body::Body;
+use crate::constants::G;
+use crate::force::{apply_additional_forces, ForceConfig};
+use crate::vector::Vec3;
+
+/// Highest supported expansion order. Beyond this the Cartesian tensors get
+/// expensive and intermediate powers of r approach the f64 exponent range.
+pub const MAX_FMM_ORDER: u32 = 12;
+
+/// Number of multi-indices at MAX_FMM_ORDER: (p+1)(p+2)(p+3)/6
+const MAX_TERMS: usize = 455;
+
+/// Maximum number of bodies in a leaf cell
+const LEAF_SIZE: usize = 32;
+
+/// Maximum tree depth (guards against coincident positions)
+const MAX_DEPTH: usize = 32;
+
+/// Cell pairs with fewer body-body interactions than this are summed directly;
+/// one M2L costs about as much as a few hundred softened pair kernels
+const DIRECT_PAIR_LIMIT: usize = 1024;
+
+/// Marker for "no such multi-index" in lookup tables
+const NONE: usize = usize::MAX;
+
+#[inline]
+fn axis(v: Vec3, a: usize) -> f64 {
+    match a {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+/// Precomputed multi-index bookkeeping for a given expansion order.
+struct Expansion {
+    order: usize,
+    /// Multi-indices sorted by total degree
+    terms: Vec<[usize; 3]>,
+    /// Total degree |α| of each term
+    degree: Vec<usize>,
+    /// 1 / α!
+    inv_factorial: Vec<f64>,
+    /// Index of α − e_a for each axis a (NONE if α_a == 0)
+    lower: Vec<[usize; 3]>,
+    /// Recurrence axis for each term (first non-zero component)
+    recur_axis: Vec<usize>,
+    /// (α, γ, α − γ): M'_α += M_γ · s^(α−γ)/(α−γ)!
+    m2m: Vec<(usize, usize, usize)>,
+    /// (α, α + β) pairs for each β, as rows delimited by `m2l_rows`
+    m2l: Vec<(u16, u16)>,
+    m2l_rows: Vec<usize>,
+    /// (γ, β, β − γ, β!/γ!): L'_γ += L_β · (β!/γ!) · s^(β−γ)/(β−γ)!
+    l2l: Vec<(usize, usize, usize, f64)>,
+}
+
+impl Expansion {
+    fn new(order: usize) -> Self {
+        let side = order + 1;
+        let mut lookup = vec![NONE; side * side * side];
+        let key = |t: usize, u: usize, v: usize| (t * side + u) * side + v;
+
+        let mut terms = Vec::new();
+        for n in 0..=order {
+            for t in (0..=n).rev() {
+                for u in (0..=(n - t)).rev() {
+                    let v = n - t - u;
+                    lookup[key(t, u, v)] = terms.len();
+                    terms.push([t, u, v]);
+                }
+            }
+        }
+
+        let find = |a: [usize; 3]| -> usize {
+            if a.iter().any(|&c| c > order) || a[0] + a[1] + a[2] > order {
+                NONE
+            } else {
+                lookup[key(a[0], a[1], a[2])]
+            }
+        };
+
+        let factorial = |k: usize| -> f64 { (1..=k).map(|i| i as f64).product() };
+        let multi_factorial = |a: [usize; 3]| factorial(a[0]) * factorial(a[1]) * factorial(a[2]);
+
+        let degree: Vec<usize> = terms.iter().map(|a| a[0] + a[1] + a[2]).collect();
+        let inv_factorial: Vec<f64> = terms.iter().map(|&a| 1.0 / multi_factorial(a)).collect();
+
+        let lower: Vec<[usize; 3]> = terms
+            .iter()
+            .map(|&a| {
+                let mut out = [NONE; 3];
+                for (ax, slot) in out.iter_mut().enumerate() {
+                    if a[ax] > 0 {
+                        let mut b = a;
+                        b[ax] -= 1;
+                        *slot = find(b);
+                    }
+                }
+                out
+            })
+            .collect();
+
+        let recur_axis: Vec<usize> = terms
+            .iter()
+            .map(|a| a.iter().position(|&c| c > 0).unwrap_or(0))
+            .collect();
+
+        let mut m2m = Vec::new();
+        let mut l2l = Vec::new();
+        let mut m2l = Vec::new();
+        for (ia, &a) in terms.iter().enumerate() {
+            for (ig, &g) in terms.iter().enumerate() {
+                // γ ≤ α component-wise
+                if g[0] <= a[0] && g[1] <= a[1] && g[2] <= a[2] {
+                    let d = find([a[0] - g[0], a[1] - g[1], a[2] - g[2]]);
+                    m2m.push((ia, ig, d));
+                    // For L2L the roles are (γ = g, β = a)
+                    l2l.push((ig, ia, d, multi_factorial(a) / multi_factorial(g)));
+                }
+            }
+        }
+        let mut m2l_rows = vec![0];
+        for &b in &terms {
+            for (ia, &a) in terms.iter().enumerate() {
+                let sum = find([a[0] + b[0], a[1] + b[1], a[2] + b[2]]);
+                if sum != NONE {
+                    m2l.push((ia as u16, sum as u16));
+                }
+            }
+            m2l_rows.push(m2l.len());
+        }
+
+        Self {
+            order,
+            terms,
+            degree,
+            inv_factorial,
+            lower,
+            recur_axis,
+            m2m,
+            m2l,
+            m2l_rows,
+            l2l,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Monomials dᵅ (optionally divided by α!) for all terms.
+    fn monomials(&self, d: Vec3, out: &mut [f64], with_factorial: bool) {
+        out[0] = 1.0;
+        for i in 1..self.len() {
+            let a = self.recur_axis[i];
+            out[i] = out[self.lower[i][a]] * axis(d, a);
+        }
+        if with_factorial {
+            for (o, f) in out.iter_mut().zip(&self.inv_factorial) {
+                *o *= f;
+            }
+        }
+    }
+
+    /// Derivative tensor D_α(r) = ∂ᵅ (1/|r|) for all |α| ≤ order.
+    ///
+    /// The recurrence runs on the unit vector and is rescaled by |r|^-(|α|+1)
+    /// afterwards, so intermediate values stay O(1) even for parsec-scale
+    /// separations at high order.
+    fn derivatives(&self, r: Vec3, out: &mut [f64], aux: &mut Vec<f64>) {
+        let nt = self.len();
+        let p = self.order;
+        let (rhat, len) = r.normalize_with_length();
+        aux.clear();
+        aux.resize((p + 1) * nt, 0.0);
+
+        // R⁽ⁿ⁾_000 = (−1)ⁿ (2n − 1)!!
+        let mut base = 1.0;
+        for n in 0..=p {
+            aux[n * nt] = base;
+            base *= -((2 * n + 1) as f64);
+        }
+
+        // R⁽ⁿ⁾_(α+e_a) = α_a · R⁽ⁿ⁺¹⁾_(α−e_a) + r̂_a · R⁽ⁿ⁺¹⁾_α
+        for i in 1..nt {
+            let a = self.recur_axis[i];
+            let prev = self.lower[i][a];
+            let prev2 = self.lower[prev][a];
+            let k = self.terms[i][a] - 1;
+            let c = axis(rhat, a);
+            for n in 0..=(p - self.degree[i]) {
+                let mut value = c * aux[(n + 1) * nt + prev];
+                if k > 0 {
+                    value += k as f64 * aux[(n + 1) * nt + prev2];
+                }
+                aux[n * nt + i] = value;
+            }
+        }
+
+        let inv_r = 1.0 / len;
+        let mut scale = [inv_r; MAX_FMM_ORDER as usize + 1];
+        for n in 1..=p {
+            scale[n] = scale[n - 1] * inv_r;
+        }
+        for ((o, a), &deg) in out.iter_mut().zip(aux.iter()).zip(&self.degree) {
+            *o = a * scale[deg];
+        }
+    }
+
+    /// M2L: L_β += −G/β! · Σ_α (−1)^|α| M_α D_(α+β)(r).
+    ///
+    /// With `reversed` the tensor is taken as D(−r) = (−1)^|α+β| D(r), so the
+    /// same derivatives serve both directions of a mutual interaction and the
+    /// sign collapses to (−1)^|β|.
+    fn m2l(&self, multipole: &[f64], tensor: &[f64], local: &mut [f64], reversed: bool) {
+        let mut signed = [0.0; MAX_TERMS];
+        for ((s, m), &deg) in signed.iter_mut().zip(multipole).zip(&self.degree) {
+            *s = if reversed || deg.is_multiple_of(2) { *m } else { -*m };
+        }
+        for (beta, l) in local.iter_mut().enumerate() {
+            let row = &self.m2l[self.m2l_rows[beta]..self.m2l_rows[beta + 1]];
+            let mut sum = 0.0;
+            for &(alpha, index) in row {
+                sum += signed[alpha as usize] * tensor[index as usize];
+            }
+            let coeff = -G * self.inv_factorial[beta];
+            let flip = reversed && !self.degree[beta].is_multiple_of(2);
+            *l += if flip { -coeff * sum } else { coeff * sum };
+        }
+    }
+}
+
+/// A cell of the FMM tree. Children of a cell are stored contiguously.
+struct Cell {
+    /// Expansion center (center of mass, or geometric center if massless)
+    center: Vec3,
+    /// Geometric center of the cube
+    cube_center: Vec3,
+    /// Half the cube side length
+    half_size: f64,
+    /// Upper bound on the distance from `center` to any member body
+    radius: f64,
+    /// Total source mass
+    mass: f64,
+    /// Number of bodies that receive forces
+    sinks: usize,
+    /// Range into `order`
+    first: usize,
+    count: usize,
+    /// Range into `cells`
+    child_first: usize,
+    child_count: usize,
+}
+
+impl Cell {
+    #[inline]
+    fn is_leaf(&self) -> bool {
+        self.child_count == 0
+    }
+}
+
+/// FMM solver state for one force evaluation.
+struct Solver<'a> {
+    exp: &'a Expansion,
+    theta: f64,
+    cells: Vec<Cell>,
+    /// Body indices sorted so each cell owns a contiguous range
+    order: Vec<usize>,
+    positions: Vec<Vec3>,
+    /// Source mass per body (0 for bodies that don't contribute gravity)
+    source_mass: Vec<f64>,
+    is_sink: Vec<bool>,
+    softening: Vec<f64>,
+    multipoles: Vec<f64>,
+    locals: Vec<f64>,
+    accelerations: Vec<Vec3>,
+    potentials: Vec<f64>,
+    // Scratch buffers
+    tensor: Vec<f64>,
+    aux: Vec<f64>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(bodies: &[Body], config: &ForceConfig, exp: &'a Expansion) -> Self {
+        let n = bodies.len();
+        let mut order = Vec::with_capacity(n);
+        let mut positions = Vec::with_capacity(n);
+        let mut source_mass = Vec::with_capacity(n);
+        let mut is_sink = Vec::with_capacity(n);
+        let mut softening = Vec::with_capacity(n);
+
+        for (i, body) in bodies.iter().enumerate() {
+            let source = body.is_active && body.contributes_gravity && body.mass > 0.0;
+            let sink = body.is_active && body.feels_gravity;
+            positions.push(body.position);
+            source_mass.push(if source { body.mass } else { 0.0 });
+            is_sink.push(sink);
+            softening.push(body.effective_softening(config.softening));
+            if source || sink {
+                order.push(i);
+            }
+        }
+
+        Self {
+            exp,
+            theta: config.fmm_theta,
+            cells: Vec::new(),
+            order,
+            positions,
+            source_mass,
+            is_sink,
+            softening,
+            multipoles: Vec::new(),
+            locals: Vec::new(),
+            accelerations: vec![Vec3::ZERO; n],
+            potentials: vec![0.0; n],
+            tensor: vec![0.0; exp.len()],
+            aux: Vec::new(),
+        }
+    }
+
+    fn build_tree(&mut self) {
+        let mut min = Vec3::new(f64::MAX, f64::MAX, f64::MAX);
+        let mut max = Vec3::new(f64::MIN, f64::MIN, f64::MIN);
+        for &i in &self.order {
+            min = min.min(self.positions[i]);
+            max = max.max(self.positions[i]);
+        }
+        let size = max - min;
+        let half_size = size.x.max(size.y).max(size.z) * 0.5 * 1.001 + 1.0;
+
+        self.cells.push(Cell {
+            center: Vec3::ZERO,
+            cube_center: (min + max) * 0.5,
+            half_size,
+            radius: 0.0,
+            mass: 0.0,
+            sinks: 0,
+            first: 0,
+            count: self.order.len(),
+            child_first: 0,
+            child_count: 0,
+        });
+        self.split(0, 0);
+    }
+
+    fn split(&mut self, cell: usize, depth: usize) {
+        let (first, count, cube_center, half_size) = {
+            let c = &self.cells[cell];
+            (c.first, c.count, c.cube_center, c.half_size)
+        };
+        if count <= LEAF_SIZE || depth >= MAX_DEPTH {
+            return;
+        }
+
+        let positions = &self.positions;
+        let octant = |i: usize| -> usize {
+            let p = positions[i];
+            (p.x >= cube_center.x) as usize
+                | ((p.y >= cube_center.y) as usize) << 1
+                | ((p.z >= cube_center.z) as usize) << 2
+        };
+        self.order[first..first + count].sort_by_key(|&i| octant(i));
+
+        let child_first = self.cells.len();
+        let quarter = half_size * 0.5;
+        let mut start = first;
+        for oct in 0..8 {
+            let mut end = start;
+            while end < first + count && octant(self.order[end]) == oct {
+                end += 1;
+            }
+            if end > start {
+                let offset = Vec3::new(
+                    if oct & 1 != 0 { quarter } else { -quarter },
+                    if oct & 2 != 0 { quarter } else { -quarter },
+                    if oct & 4 != 0 { quarter } else { -quarter },
+                );
+                self.cells.push(Cell {
+                    center: Vec3::ZERO,
+                    cube_center: cube_center + offset,
+                    half_size: quarter,
+                    radius: 0.0,
+                    mass: 0.0,
+                    sinks: 0,
+                    first: start,
+                    count: end - start,
+                    child_first: 0,
+                    child_count: 0,
+                });
+            }
+            start = end;
+        }
+
+        let child_count = self.cells.len() - child_first;
+        self.cells[cell].child_first = child_first;
+        self.cells[cell].child_count = child_count;
+        for child in child_first..child_first + child_count {
+            self.split(child, depth + 1);
+        }
+    }
+
+    /// Upward pass: P2M at leaves, M2M towards the root.
+    fn upward(&mut self) {
+        let nt = self.exp.len();
+        self.multipoles = vec![0.0; self.cells.len() * nt];
+        self.locals = vec![0.0; self.cells.len() * nt];
+        let mut mono = vec![0.0; nt];
+
+        // Children always have larger indices than their parent
+        for c in (0..self.cells.len()).rev() {
+            let (first, count, child_first, child_count, cube_center, half_size) = {
+                let cell = &self.cells[c];
+                (cell.first, cell.count, cell.child_first, cell.child_count, cell.cube_center, cell.half_size)
+            };
+
+            let mut mass = 0.0;
+            let mut weighted = Vec3::ZERO;
+            let mut sinks = 0;
+            if child_count == 0 {
+                for &i in &self.order[first..first + count] {
+                    mass += self.source_mass[i];
+                    weighted += self.positions[i] * self.source_mass[i];
+                    sinks += self.is_sink[i] as usize;
+                }
+            } else {
+                for child in &self.cells[child_first..child_first + child_count] {
+                    mass += child.mass;
+                    weighted += child.center * child.mass;
+                    sinks += child.sinks;
+                }
+            }
+            let center = if mass > 0.0 { weighted / mass } else { cube_center };
+
+            let mut radius = 0.0f64;
+            let target = c * nt;
+            if child_count == 0 {
+                for k in first..first + count {
+                    let i = self.order[k];
+                    let d = self.positions[i] - center;
+                    radius = radius.max(d.length());
+                    let m = self.source_mass[i];
+                    if m > 0.0 {
+                        self.exp.monomials(d, &mut mono, true);
+                        for (slot, v) in self.multipoles[target..target + nt].iter_mut().zip(&mono) {
+                            *slot += m * v;
+                        }
+                    }
+                }
+            } else {
+                for child in child_first..child_first + child_count {
+                    let (child_center, child_radius, child_mass) = {
+                        let cc = &self.cells[child];
+                        (cc.center, cc.radius, cc.mass)
+                    };
+                    radius = radius.max((child_center - center).length() + child_radius);
+                    if child_mass <= 0.0 {
+                        continue;
+                    }
+                    self.exp.monomials(child_center - center, &mut mono, true);
+                    let source = child * nt;
+                    for &(a, g, d) in &self.exp.m2m {
+                        self.multipoles[target + a] += self.multipoles[source + g] * mono[d];
+                    }
+                }
+                // The cube corner distance is a second valid bound
+                let corner = (cube_center - center).abs() + Vec3::new(half_size, half_size, half_size);
+                radius = radius.min(corner.length());
+            }
+
+            let cell = &mut self.cells[c];
+            cell.center = center;
+            cell.mass = mass;
+            cell.sinks = sinks;
+            cell.radius = radius;
+        }
+    }
+
+    /// Dual tree walk deciding between M2L and P2P for every cell pair.
+    fn interact(&mut self, a: usize, b: usize) {
+        if a == b {
+            let (leaf, child_first, child_count, count) = {
+                let c = &self.cells[a];
+                (c.is_leaf(), c.child_first, c.child_count, c.count)
+            };
+            if leaf || count * count <= DIRECT_PAIR_LIMIT {
+                self.p2p_self(a);
+            } else {
+                for i in child_first..child_first + child_count {
+                    for j in i..child_first + child_count {
+                        self.interact(i, j);
+                    }
+                }
+            }
+            return;
+        }
+
+        let (ca, cb) = (&self.cells[a], &self.cells[b]);
+        if ca.mass <= 0.0 && cb.mass <= 0.0 {
+            return;
+        }
+        if ca.sinks == 0 && cb.sinks == 0 {
+            return;
+        }
+
+        let separation = (ca.center - cb.center).length();
+        if ca.radius + cb.radius < self.theta * separation {
+            self.m2l_mutual(a, b);
+            return;
+        }
+
+        if (ca.is_leaf() && cb.is_leaf()) || ca.count * cb.count <= DIRECT_PAIR_LIMIT {
+            self.p2p(a, b);
+            return;
+        }
+
+        // Split the larger cell
+        let split_a = !ca.is_leaf() && (cb.is_leaf() || ca.radius >= cb.radius);
+        if split_a {
+            let (first, count) = (ca.child_first, ca.child_count);
+            for child in first..first + count {
+                self.interact(child, b);
+            }
+        } else {
+            let (first, count) = (cb.child_first, cb.child_count);
+            for child in first..first + count {
+                self.interact(a, child);
+            }
+        }
+    }
+
+    /// M2L in both directions for an accepted cell pair.
+    fn m2l_mutual(&mut self, a: usize, b: usize) {
+        let nt = self.exp.len();
+        let r = self.cells[b].center - self.cells[a].center;
+        self.exp.derivatives(r, &mut self.tensor, &mut self.aux);
+
+        // a → b uses D(z_b − z_a); b → a uses D(z_a − z_b)
+        if self.cells[a].mass > 0.0 && self.cells[b].sinks > 0 {
+            let multipole = &self.multipoles[a * nt..(a + 1) * nt];
+            let local = &mut self.locals[b * nt..(b + 1) * nt];
+            self.exp.m2l(multipole, &self.tensor, local, false);
+        }
+        if self.cells[b].mass > 0.0 && self.cells[a].sinks > 0 {
+            let multipole = &self.multipoles[b * nt..(b + 1) * nt];
+            let local = &mut self.locals[a * nt..(a + 1) * nt];
+            self.exp.m2l(multipole, &self.tensor, local, true);
+        }
+    }
+
+    /// Softened pairwise interaction, identical to the direct-sum kernel.
+    #[inline]
+    fn pair(&mut self, i: usize, j: usize) {
+        let eps = self.softening[i].max(self.softening[j]);
+        let r = self.positions[j] - self.positions[i];
+        let r2 = r.length_squared() + eps * eps;
+        if r2 <= 0.0 {
+            return;
+        }
+        let inv_r = 1.0 / r2.sqrt();
+        let inv_r3 = inv_r * inv_r * inv_r;
+
+        if self.is_sink[i] && self.source_mass[j] > 0.0 {
+            let gm = G * self.source_mass[j];
+            self.accelerations[i] += r * (gm * inv_r3);
+            self.potentials[i] -= gm * inv_r;
+        }
+        if self.is_sink[j] && self.source_mass[i] > 0.0 {
+            let gm = G * self.source_mass[i];
+            self.accelerations[j] -= r * (gm * inv_r3);
+            self.potentials[j] -= gm * inv_r;
+        }
+    }
+
+    fn p2p_self(&mut self, a: usize) {
+        let (first, count) = (self.cells[a].first, self.cells[a].count);
+        for x in first..first + count {
+            for y in (x + 1)..first + count {
+                let (i, j) = (self.order[x], self.order[y]);
+                self.pair(i, j);
+            }
+        }
+    }
+
+    fn p2p(&mut self, a: usize, b: usize) {
+        let (fa, na) = (self.cells[a].first, self.cells[a].count);
+        let (fb, nb) = (self.cells[b].first, self.cells[b].count);
+        for x in fa..fa + na {
+            for y in fb..fb + nb {
+                let (i, j) = (self.order[x], self.order[y]);
+                self.pair(i, j);
+            }
+        }
+    }
+
+    /// Downward pass: L2L towards the leaves, then L2P for every sink.
+    fn downward(&mut self) {
+        let nt = self.exp.len();
+        let mut mono = vec![0.0; nt];
+
+        // Parents always have smaller indices than their children
+        for c in 0..self.cells.len() {
+            let (center, child_first, child_count) = {
+                let cell = &self.cells[c];
+                (cell.center, cell.child_first, cell.child_count)
+            };
+            let parent = c * nt;
+            for child in child_first..child_first + child_count {
+                if self.cells[child].sinks == 0 {
+                    continue;
+                }
+                self.exp.monomials(self.cells[child].center - center, &mut mono, true);
+                let target = child * nt;
+                for &(gamma, beta, d, coeff) in &self.exp.l2l {
+                    self.locals[target + gamma] += coeff * self.locals[parent + beta] * mono[d];
+                }
+            }
+        }
+
+        for c in 0..self.cells.len() {
+            let cell = &self.cells[c];
+            if !cell.is_leaf() || cell.sinks == 0 {
+                continue;
+            }
+            let local = &self.locals[c * nt..(c + 1) * nt];
+            for &i in &self.order[cell.first..cell.first + cell.count] {
+                if !self.is_sink[i] {
+                    continue;
+                }
+                self.exp.monomials(self.positions[i] - cell.center, &mut mono, false);
+                let mut potential = 0.0;
+                let mut gradient = [0.0; 3];
+                for k in 0..nt {
+                    potential += local[k] * mono[k];
+                    for (ax, g) in gradient.iter_mut().enumerate() {
+                        let lower = self.exp.lower[k][ax];
+                        if lower != NONE {
+                            *g += local[k] * self.exp.terms[k][ax] as f64 * mono[lower];
+                        }
+                    }
+                }
+                self.potentials[i] += potential;
+                self.accelerations[i] -= Vec3::from_array(gradient);
+            }
+        }
+    }
+}
+
+/// Compute gravitational accelerations using the Fast Multipole Method.
+///
+/// Expansion order and opening angle come from `config.fmm_order` and
+/// `config.fmm_theta`. Returns the total potential energy, computed the
+/// same way as the Barnes-Hut path (½ Σ m_i Φ_i over bodies that feel gravity).
+pub fn compute_accelerations_fmm(bodies: &mut [Body], config: &ForceConfig) -> f64 {
+    for body in bodies.iter_mut() {
+        body.acceleration = Vec3::ZERO;
+    }
+
+    let order = config.fmm_order.clamp(1, MAX_FMM_ORDER) as usize;
+    let exp = Expansion::new(order);
+    let mut solver = Solver::new(bodies, config, &exp);
+    if solver.order.is_empty() {
+        return 0.0;
+    }
+
+    solver.build_tree();
+    solver.upward();
+    solver.interact(0, 0);
+    solver.downward();
+
+    let mut total_pe = 0.0;
+    for (i, body) in bodies.iter_mut().enumerate() {
+        if solver.is_sink[i] {
+            body.acceleration = solver.accelerations[i];
+            total_pe += body.mass * solver.potentials[i];
+        }
+    }
+
+    total_pe / 2.0 + apply_additional_forces(bodies, config)
+}
+
+/// Compare FMM accuracy against direct sum
+/// Returns (max_relative_error, mean_relative_error)
+pub fn compare_accuracy(bodies: &[Body], config: &ForceConfig) -> (f64, f64) {
+    use crate::force::compute_accelerations_direct;
+
+    let mut direct_bodies = bodies.to_vec();
+    let mut fmm_bodies = bodies.to_vec();
+
+    compute_accelerations_direct(&mut direct_bodies, config);
+    compute_accelerations_fmm(&mut fmm_bodies, config);
+
+    let mut max_error = 0.0f64;
+    let mut total_error = 0.0;
+    let mut count = 0;
+
+    for (direct, fmm) in direct_bodies.iter().zip(fmm_bodies.iter()) {
+        if !direct.is_active || !direct.feels_gravity {
+            continue;
+        }
+
+        let direct_mag = direct.acceleration.length();
+        if direct_mag > 0.0 {
+            let relative_error = (direct.acceleration - fmm.acceleration).length() / direct_mag;
+            max_error = max_error.max(relative_error);
+            total_error += relative_error;
+            count += 1;
+        }
+    }
+
+    let mean_error = if count > 0 { total_error / count as f64 } else { 0.0 };
+    (max_error, mean_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BodyType;
+    use crate::constants::*;
+    use crate::prng::Pcg32;
+
+    fn random_cluster(n: usize, seed: u64) -> Vec<Body> {
+        let mut rng = Pcg32::new(seed);
+        (0..n)
+            .map(|i| {
+                let pos = Vec3::new(
+                    rng.next_f64_range(-1.0, 1.0),
+                    rng.next_f64_range(-1.0, 1.0),
+                    rng.next_f64_range(-1.0, 1.0),
+                ) * AU;
+                let mut body = Body::new(
+                    i as u32, "p", BodyType::Asteroid, M_EARTH * rng.next_f64_range(0.1, 1.0),
+                    1.0e6, pos, Vec3::ZERO,
+                );
+                body.softening_length = 1.0;
+                body
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_derivative_tensor_matches_analytic() {
+        let exp = Expansion::new(3);
+        let r = Vec3::new(1.3, -0.7, 2.1);
+        let mut d = vec![0.0; exp.len()];
+        let mut aux = Vec::new();
+        exp.derivatives(r, &mut d, &mut aux);
+
+        let len = r.length();
+        // ∂x(1/r) = −x/r³, ∂x∂y(1/r) = 3xy/r⁵
+        let dx = exp.terms.iter().position(|&a| a == [1, 0, 0]).unwrap();
+        let dxy = exp.terms.iter().position(|&a| a == [1, 1, 0]).unwrap();
+        assert!((d[0] - 1.0 / len).abs() < 1e-14);
+        assert!((d[dx] + r.x / len.powi(3)).abs() < 1e-14);
+        assert!((d[dxy] - 3.0 * r.x * r.y / len.powi(5)).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_two_distant_groups() {
+        // Two tight clumps far apart: the interaction is almost entirely M2L
+        let mut bodies = random_cluster(64, 7);
+        for body in bodies.iter_mut().skip(32) {
+            body.position += Vec3::new(40.0 * AU, 0.0, 0.0);
+        }
+        let config = ForceConfig { fmm_order: 6, ..ForceConfig::default() };
+        let (max_error, mean_error) = compare_accuracy(&bodies, &config);
+        assert!(max_error < 1e-4, "max error {}", max_error);
+        assert!(mean_error < 1e-5, "mean error {}", mean_error);
+    }
+
+    #[test]
+    fn test_accuracy_improves_with_order() {
+        let bodies = random_cluster(1000, 42);
+        let mut previous = f64::MAX;
+        for order in [2, 4, 6] {
+            let config = ForceConfig { fmm_order: order, ..ForceConfig::default() };
+            let (_, mean_error) = compare_accuracy(&bodies, &config);
+            println!("order {}: mean error {:.3e}", order, mean_error);
+            assert!(mean_error < previous, "order {} error {} !< {}", order, mean_error, previous);
+            previous = mean_error;
+        }
+        assert!(previous < 1e-3, "order 6 mean error {}", previous);
+    }
+
+    #[test]
+    fn test_potential_energy_matches_direct() {
+        use crate::force::compute_accelerations_direct;
+
+        let mut direct = random_cluster(500, 3);
+        let mut fmm = direct.clone();
+        let config = ForceConfig::default();
+        let pe_direct = compute_accelerations_direct(&mut direct, &config);
+        let pe_fmm = compute_accelerations_fmm(&mut fmm, &config);
+        assert!(((pe_fmm - pe_direct) / pe_direct).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_participation_flags() {
+        let mut bodies = random_cluster(200, 11);
+        // A probe that feels but doesn't pull, and a massive body that doesn't feel
+        bodies[0].contributes_gravity = false;
+        bodies[1].feels_gravity = false;
+        bodies[2].is_active = false;
+
+        let config = ForceConfig { fmm_order: 6, ..ForceConfig::default() };
+        let (max_error, _) = compare_accuracy(&bodies, &config);
+        assert!(max_error < 1e-2);
+
+        compute_accelerations_fmm(&mut bodies, &config);
+        assert_eq!(bodies[1].acceleration, Vec3::ZERO);
+        assert_eq!(bodies[2].acceleration, Vec3::ZERO);
+    }
+}
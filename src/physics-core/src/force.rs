@@ -1,10 +1,19 @@
 //! Force calculation kernels
 //!
-//! Implements both direct-sum O(N²) and Barnes-Hut O(N log N) gravity solvers.
+//! Implements the direct-sum O(N²) gravity solver and the shared configuration
+//! for the Barnes-Hut O(N log N) (`octree`) and FMM O(N) (`fmm`) solvers.
 //! Uses per-body or global softening to prevent singularities in close encounters.
 
 use crate::body::Body;
-use crate::constants::{G, DEFAULT_SOFTENING, DEFAULT_BARNES_HUT_THETA};
+use crate::drag::apply_atmospheric_drag;
+use crate::harmonics::{apply_zonal_harmonics, zonal_potential_energy};
+use crate::outgassing::apply_outgassing;
+use crate::relativity::{apply_radiation_reaction, apply_relativity, RelativityMode};
+use crate::radiation::apply_radiation;
+use crate::thrust::apply_thrust;
+use crate::tides::apply_tides;
+use crate::yarkovsky::apply_yarkovsky;
+use crate::constants::{G, DEFAULT_SOFTENING, DEFAULT_BARNES_HUT_THETA, DEFAULT_FMM_ORDER, DEFAULT_FMM_THETA};
 use crate::vector::Vec3;
 
 /// Configuration for force calculation
@@ -17,6 +26,43 @@ pub struct ForceConfig {
     /// Barnes-Hut opening angle θ
     /// Lower = more accurate, higher = faster
     pub barnes_hut_theta: f64,
+
+    /// FMM expansion order p (multipoles up to degree p)
+    /// Higher = more accurate, cost grows steeply with p
+    pub fmm_order: u32,
+
+    /// FMM opening angle: cells interact via expansions when (r_A + r_B) < θ·d
+    pub fmm_theta: f64,
+
+    /// Post-Newtonian corrections (off by default)
+    pub relativity: RelativityMode,
+
+    /// Gravitational-wave radiation reaction between compact stars, as a
+    /// multiple of the physical strength (0 = off, 1 = physical)
+    pub radiation_reaction: f64,
+
+    /// Constant-time-lag tides on bodies with a Love number (off by default)
+    pub tides: bool,
+
+    /// Radiation pressure and Poynting-Robertson drag from luminous bodies
+    /// (off by default)
+    pub radiation: bool,
+
+    /// Drag on small bodies inside planetary atmospheres (off by default)
+    pub atmospheric_drag: bool,
+
+    /// Non-gravitational outgassing forces on comets (off by default)
+    pub outgassing: bool,
+
+    /// Yarkovsky thermal drift of asteroids (off by default)
+    pub yarkovsky: bool,
+
+    /// YORP evolution of asteroid spin rates and obliquities (off by default)
+    pub yorp: bool,
+
+    /// Spin-axis precession of oblate bodies under gravitational torques
+    /// (off by default)
+    pub precession: bool,
 }
 
 impl Default for ForceConfig {
@@ -24,10 +70,35 @@ impl Default for ForceConfig {
         Self {
             softening: DEFAULT_SOFTENING,
             barnes_hut_theta: DEFAULT_BARNES_HUT_THETA,
+            fmm_order: DEFAULT_FMM_ORDER,
+            fmm_theta: DEFAULT_FMM_THETA,
+            relativity: RelativityMode::None,
+            radiation_reaction: 0.0,
+            tides: false,
+            radiation: false,
+            atmospheric_drag: false,
+            outgassing: false,
+            yarkovsky: false,
+            yorp: false,
+            precession: false,
         }
     }
 }
 
+impl ForceConfig {
+    /// Whether any enabled force depends on velocities, so integrators
+    /// must supply velocities at the time accelerations are evaluated
+    pub fn velocity_dependent(&self) -> bool {
+        self.relativity != RelativityMode::None
+            || self.radiation_reaction != 0.0
+            || self.tides
+            || self.radiation
+            || self.atmospheric_drag
+            || self.outgassing
+            || self.yarkovsky
+    }
+}
+
 /// Calculate gravitational acceleration on body i due to body j.
 /// Returns the acceleration vector (m/s²).
 /// 
@@ -57,6 +128,37 @@ pub fn gravitational_acceleration(
     r * (G * mass_j / denom)
 }
 
+/// Softened gravitational acceleration on body i from body j together with
+/// its time derivative (the jerk), for Hermite-style integrators and
+/// timestep criteria.
+///
+/// j = G m [v / (r² + ε²)^(3/2) − 3 (r·v) r / (r² + ε²)^(5/2)],
+/// with r and v the position and velocity of j relative to i.
+pub fn gravitational_acceleration_and_jerk(
+    pos_i: Vec3,
+    vel_i: Vec3,
+    pos_j: Vec3,
+    vel_j: Vec3,
+    mass_j: f64,
+    softening_squared: f64,
+) -> (Vec3, Vec3) {
+    if mass_j <= 0.0 {
+        return (Vec3::ZERO, Vec3::ZERO);
+    }
+
+    let r = pos_j - pos_i;
+    let v = vel_j - vel_i;
+    let r2 = r.length_squared() + softening_squared;
+    if r2 <= 0.0 {
+        return (Vec3::ZERO, Vec3::ZERO);
+    }
+
+    let gm_inv_r3 = G * mass_j / (r2 * r2.sqrt());
+    let acceleration = r * gm_inv_r3;
+    let jerk = (v - r * (3.0 * r.dot(v) / r2)) * gm_inv_r3;
+    (acceleration, jerk)
+}
+
 /// Direct-sum O(N²) force calculation.
 /// 
 /// Computes gravitational acceleration for all bodies.
@@ -75,14 +177,18 @@ pub fn compute_accelerations_direct(bodies: &mut [Body], config: &ForceConfig) -
         body.acceleration = Vec3::ZERO;
     }
 
-    // O(N²) pairwise calculation
+    // Only gravity sources pull, so massless test particles (ring
+    // particles, probes) cost O(N·sources) rather than O(N²)
+    let sources: Vec<usize> = (0..n).filter(|&j| bodies[j].is_active && bodies[j].contributes_gravity).collect();
+
+    // Pairwise calculation
     for i in 0..n {
         if !bodies[i].is_active || !bodies[i].feels_gravity {
             continue;
         }
 
-        for j in 0..n {
-            if i == j || !bodies[j].is_active || !bodies[j].contributes_gravity {
+        for &j in &sources {
+            if i == j {
                 continue;
             }
 
@@ -109,12 +215,44 @@ pub fn compute_accelerations_direct(bodies: &mut [Body], config: &ForceConfig) -
             }
         }
     }
-    
-    total_pe
+
+    total_pe + apply_additional_forces(bodies, config)
+}
+
+/// Whether `apply_additional_forces` can add anything to these bodies
+pub fn has_additional_forces(bodies: &[Body], config: &ForceConfig) -> bool {
+    config.velocity_dependent()
+        || bodies.iter().any(|b| b.is_active && (b.j2 != 0.0 || b.j4 != 0.0 || b.thrust != Vec3::ZERO))
+}
+
+/// Forces beyond point-mass gravity, added on top of the accelerations
+/// from any solver (direct, Barnes-Hut, FMM). Returns their potential energy
+/// (velocity-dependent terms have none).
+pub fn apply_additional_forces(bodies: &mut [Body], config: &ForceConfig) -> f64 {
+    let mut energy = apply_zonal_harmonics(bodies);
+    if config.tides {
+        energy += apply_tides(bodies);
+    }
+    if config.radiation {
+        energy += apply_radiation(bodies);
+    }
+    if config.atmospheric_drag {
+        apply_atmospheric_drag(bodies);
+    }
+    if config.outgassing {
+        apply_outgassing(bodies);
+    }
+    if config.yarkovsky {
+        apply_yarkovsky(bodies);
+    }
+    apply_thrust(bodies);
+    apply_relativity(bodies, config.relativity);
+    apply_radiation_reaction(bodies, config.radiation_reaction);
+    energy
 }
 
 /// Compute total gravitational potential energy of the system.
-/// U = -G * Σ(i<j) m_i * m_j / r_ij
+/// U = -G * Σ(i<j) m_i * m_j / r_ij, plus the J2/J4 terms of oblate bodies
 ///
 /// Includes all active bodies that contribute gravity.
 pub fn compute_potential_energy(bodies: &[Body], softening: f64) -> f64 {
@@ -144,7 +282,7 @@ pub fn compute_potential_energy(bodies: &[Body], softening: f64) -> f64 {
         }
     }
 
-    energy
+    energy + zonal_potential_energy(bodies)
 }
 
 /// Compute total kinetic energy of the system.
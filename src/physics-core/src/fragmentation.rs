@@ -0,0 +1,339 @@
+//! Fragmenting impacts after Leinhardt & Stewart (2012)
+//!
+//! Pairs resolved with `CollisionOutcome::Fragment` are classified by the
+//! impact speed v, the impact parameter b = sin θ at first contact and the
+//! mass of the largest remnant the impact leaves:
+//!
+//! - Merge: v below the mutual escape speed, computed from each body's
+//!   `escape_velocity_surface`; the bodies merge as with `Merge`
+//! - Hit-and-run: a grazing impact (b > R_t / (R_t + R_p)) that would not
+//!   erode the target; the projectile passes through the overlap and both
+//!   bodies leave intact
+//! - Partial accretion: the largest remnant outweighs the target
+//! - Erosion: the largest remnant weighs less than the target
+//!
+//! The largest remnant follows the universal law
+//! M_lr = M_tot (1 − Q_R / 2Q'*_RD), and the super-catastrophic power law
+//! beyond Q_R = 1.8 Q'*_RD. Q_R = μ v² / 2M_tot is the specific impact
+//! energy and Q'*_RD the catastrophic disruption threshold, scaled from the
+//! equal-mass value c* (4/5) π ρ G R_C1² for the mass ratio and, through the
+//! interacting mass fraction, for the impact angle. ρ is the combined
+//! `bulk_density` of the pair (rather than L&S's fixed 1000 kg/m³) and R_C1
+//! the radius of the combined mass at that density. Material strength is
+//! ignored, so bodies below about a kilometre break up too easily.
+//!
+//! In the fragmenting regimes the heavier body becomes the largest remnant
+//! at the pair's centre of mass and the rest is shed as equal-mass debris,
+//! modelled on the lighter body and marked `is_fragment`. Debris leaves in
+//! opposite pairs from twice the contact distance at 1–1.2 times the
+//! remnant's escape speed there, so mass, centre of mass and momentum are
+//! conserved exactly. Directions and speeds come from a `Pcg32` seeded
+//! with both bodies' `seed`, so the same impact always produces the same
+//! debris. Debris that would weigh under a thousandth of the projectile is
+//! folded into a merge, and fragments never fragment again: they merge.
+
+use crate::body::Body;
+use crate::collision::{merge_bodies, survives};
+use crate::constants::G;
+use crate::prng::Pcg32;
+use crate::vector::Vec3;
+use std::f64::consts::PI;
+
+/// Default number of debris bodies per fragmenting impact
+pub const DEFAULT_DEBRIS_FRAGMENTS: u32 = 8;
+
+/// Disruption scaling constant c* for gravity-dominated bodies
+const C_STAR: f64 = 1.9;
+
+/// Velocity exponent μ̄ of the coupling parameter
+const MU_BAR: f64 = 0.36;
+
+/// Smallest debris mass, as a fraction of the projectile, worth shedding
+const MIN_DEBRIS_FRACTION: f64 = 1e-3;
+
+/// Leinhardt & Stewart collision regime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactRegime {
+    Merge,
+    HitAndRun,
+    PartialAccretion,
+    Erosion,
+}
+
+impl ImpactRegime {
+    /// Lowercase name used in collision events
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::HitAndRun => "hit_and_run",
+            Self::PartialAccretion => "partial_accretion",
+            Self::Erosion => "erosion",
+        }
+    }
+}
+
+/// Classification of an impact between a target and a projectile
+#[derive(Debug, Clone, Copy)]
+pub struct Impact {
+    pub regime: ImpactRegime,
+    /// Impact speed in m/s
+    pub speed: f64,
+    /// Mutual escape speed at contact in m/s
+    pub escape_speed: f64,
+    /// Impact parameter b = sin θ
+    pub impact_parameter: f64,
+    /// Specific impact energy Q_R in J/kg
+    pub specific_energy: f64,
+    /// Catastrophic disruption threshold Q'*_RD in J/kg
+    pub disruption_energy: f64,
+    /// Mass of the largest remnant in kg
+    pub largest_remnant: f64,
+}
+
+/// Whether a body has the mass, size and derived properties to fragment
+fn can_fragment(body: &Body) -> bool {
+    !body.is_fragment && body.mass > 0.0 && body.radius > 0.0 && body.bulk_density > 0.0 && body.escape_velocity_surface > 0.0
+}
+
+/// Density of the combined material of two bodies
+fn combined_density(a: &Body, b: &Body) -> f64 {
+    (a.mass + b.mass) / (a.mass / a.bulk_density + b.mass / b.bulk_density)
+}
+
+/// Radius of a sphere of `mass` at `density`
+fn sphere_radius(mass: f64, density: f64) -> f64 {
+    (3.0 * mass / (4.0 * PI * density)).cbrt()
+}
+
+/// Classify the impact of `projectile` on the heavier `target`, both at
+/// first contact
+pub fn classify_impact(target: &Body, projectile: &Body) -> Impact {
+    let total = target.mass + projectile.mass;
+    let reach = target.radius + projectile.radius;
+    let offset = projectile.position - target.position;
+    let relative = projectile.velocity - target.velocity;
+    let speed = relative.length();
+
+    let mut impact = Impact {
+        regime: ImpactRegime::Merge,
+        speed,
+        escape_speed: 0.0,
+        impact_parameter: 0.0,
+        specific_energy: 0.0,
+        disruption_energy: f64::INFINITY,
+        largest_remnant: total,
+    };
+    if !can_fragment(target) || !can_fragment(projectile) {
+        return impact;
+    }
+
+    // 2G M_tot / (R_t + R_p) from the surface escape speeds
+    let escape_speed = ((target.escape_velocity_surface.powi(2) * target.radius
+        + projectile.escape_velocity_surface.powi(2) * projectile.radius)
+        / reach)
+        .sqrt();
+    let distance = offset.length();
+    let b = if speed > 0.0 && distance > 0.0 { (offset.cross(relative).length() / (distance * speed)).min(1.0) } else { 0.0 };
+
+    // Equal-mass threshold, scaled for the mass ratio γ
+    let density = combined_density(target, projectile);
+    let q_equal = C_STAR * 0.8 * PI * density * G * sphere_radius(total, density).powi(2);
+    let gamma = projectile.mass / target.mass;
+    let mut q_star = q_equal * ((1.0 + gamma).powi(2) / (4.0 * gamma)).powf(2.0 / (3.0 * MU_BAR) - 1.0);
+
+    // Only the part of the projectile overlapping the target interacts
+    let overlap = reach * (1.0 - b);
+    let rp = projectile.radius;
+    let alpha = if overlap < 2.0 * rp { (3.0 * rp * overlap.powi(2) - overlap.powi(3)) / (4.0 * rp.powi(3)) } else { 1.0 };
+    let mu = target.mass * projectile.mass / total;
+    let mu_alpha = alpha * projectile.mass * target.mass / (alpha * projectile.mass + target.mass);
+    q_star *= (mu / mu_alpha).powf(2.0 - 1.5 * MU_BAR);
+
+    let q_r = 0.5 * mu * speed * speed / total;
+    let ratio = q_r / q_star;
+    let largest = if ratio < 1.8 { total * (1.0 - 0.5 * ratio) } else { total * 0.1 * (ratio / 1.8).powf(-1.5) };
+
+    impact.escape_speed = escape_speed;
+    impact.impact_parameter = b;
+    impact.specific_energy = q_r;
+    impact.disruption_energy = q_star;
+    impact.largest_remnant = largest;
+    impact.regime = if speed <= escape_speed {
+        ImpactRegime::Merge
+    } else if b > target.radius / reach && largest >= target.mass {
+        ImpactRegime::HitAndRun
+    } else if total - largest < MIN_DEBRIS_FRACTION * projectile.mass {
+        ImpactRegime::Merge
+    } else if largest >= target.mass {
+        ImpactRegime::PartialAccretion
+    } else {
+        ImpactRegime::Erosion
+    };
+    if impact.regime == ImpactRegime::Merge {
+        impact.largest_remnant = total;
+    }
+    impact
+}
+
+/// Uniformly distributed unit vector
+fn unit_vector(rng: &mut Pcg32) -> Vec3 {
+    let z = rng.next_f64_range(-1.0, 1.0);
+    let phi = rng.next_f64_range(0.0, 2.0 * PI);
+    let s = (1.0 - z * z).sqrt();
+    Vec3::new(s * phi.cos(), s * phi.sin(), z)
+}
+
+/// Move two overlapping bodies along their relative path to where they
+/// separate again, keeping their centre of mass
+fn pass_through(a: &mut Body, b: &mut Body) {
+    let offset = b.position - a.position;
+    let relative = b.velocity - a.velocity;
+    let v2 = relative.length_squared();
+    let total = a.mass + b.mass;
+    if v2 <= 0.0 || total <= 0.0 {
+        return;
+    }
+    let shift = relative * (-2.0 * offset.dot(relative) / v2).max(0.0);
+    a.position -= shift * (b.mass / total);
+    b.position += shift * (a.mass / total);
+}
+
+/// Resolve a fragmenting impact between two bodies at first contact.
+/// The heavier body survives as the largest remnant; the lighter one is
+/// deactivated unless the impact is a hit-and-run. Returns the regime and
+/// the debris, built from the projectile with `Body::as_fragment`.
+pub fn fragment_collision(a: &mut Body, b: &mut Body, debris: u32) -> (ImpactRegime, Vec<Body>) {
+    let (target, projectile) = if survives(a, b) { (a, b) } else { (b, a) };
+    let impact = classify_impact(target, projectile);
+    match impact.regime {
+        ImpactRegime::Merge => {
+            merge_bodies(target, projectile);
+            return (impact.regime, Vec::new());
+        }
+        ImpactRegime::HitAndRun => {
+            pass_through(target, projectile);
+            return (impact.regime, Vec::new());
+        }
+        _ => {}
+    }
+
+    let total = target.mass + projectile.mass;
+    let density = combined_density(target, projectile);
+    let center = (target.position * target.mass + projectile.position * projectile.mass) / total;
+    let velocity = (target.velocity * target.mass + projectile.velocity * projectile.mass) / total;
+    let mut rng = Pcg32::new(target.seed ^ projectile.seed.rotate_left(32));
+
+    let pairs = (debris.max(2) as usize).div_ceil(2);
+    let mass = (total - impact.largest_remnant) / (2 * pairs) as f64;
+    let radius = sphere_radius(mass, density);
+    let remnant_radius = sphere_radius(impact.largest_remnant, density);
+    let launch = 2.0 * (remnant_radius + radius);
+    let escape_speed = (2.0 * G * impact.largest_remnant / launch).sqrt();
+
+    let mut fragments = Vec::with_capacity(2 * pairs);
+    for _ in 0..pairs {
+        let direction = unit_vector(&mut rng);
+        let speed = escape_speed * rng.next_f64_range(1.0, 1.2);
+        for sign in [1.0, -1.0] {
+            let name = format!("{}_{}", projectile.name, fragments.len() + 1);
+            let mut fragment = projectile.as_fragment(name, mass, radius, rng.next_u64());
+            fragment.position = center + direction * (sign * launch);
+            fragment.velocity = velocity + direction * (sign * speed);
+            fragments.push(fragment);
+        }
+    }
+
+    target.mass = impact.largest_remnant;
+    target.radius = remnant_radius;
+    target.position = center;
+    target.velocity = velocity;
+    target.bulk_density = 0.0;
+    target.surface_gravity = 0.0;
+    target.escape_velocity_surface = 0.0;
+    target.compute_derived();
+    projectile.is_active = false;
+
+    (impact.regime, fragments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BodyType;
+
+    /// A 10 km rubble asteroid of 2000 kg/m³ (surface escape speed 10.6 m/s)
+    fn rock(id: u32, position: Vec3, velocity: Vec3) -> Body {
+        let mass = 4.0 / 3.0 * PI * 1.0e12 * 2000.0;
+        let mut rock = Body::new(id, "Rock", BodyType::Asteroid, mass, 1.0e4, position, velocity);
+        rock.seed = 11 + id as u64;
+        rock.compute_derived();
+        rock
+    }
+
+    /// Equal rocks in contact, approaching at `speed` with impact parameter `b`
+    fn pair(speed: f64, b: f64) -> (Body, Body) {
+        let offset = Vec3::new(-(1.0 - b * b).sqrt(), b, 0.0) * 2.0e4;
+        (rock(0, Vec3::ZERO, Vec3::ZERO), rock(1, offset, Vec3::new(speed, 0.0, 0.0)))
+    }
+
+    #[test]
+    fn test_regimes() {
+        let regime = |speed, b| {
+            let (target, projectile) = pair(speed, b);
+            classify_impact(&target, &projectile).regime
+        };
+        assert_eq!(regime(8.0, 0.0), ImpactRegime::Merge);
+        assert_eq!(regime(20.0, 0.0), ImpactRegime::PartialAccretion);
+        assert_eq!(regime(50.0, 0.0), ImpactRegime::Erosion);
+        assert_eq!(regime(30.0, 0.9), ImpactRegime::HitAndRun);
+        // Fast enough, even a graze erodes
+        assert_eq!(regime(2000.0, 0.9), ImpactRegime::Erosion);
+
+        // Equal masses head-on: Q_R = v²/8 and M_lr = M_tot (1 − Q_R / 2Q*)
+        let (target, projectile) = pair(20.0, 0.0);
+        let impact = classify_impact(&target, &projectile);
+        assert!((impact.escape_speed - target.escape_velocity_surface).abs() < 1e-9);
+        assert!((impact.specific_energy - 50.0).abs() < 1e-9);
+        let expected = 2.0 * target.mass * (1.0 - 25.0 / impact.disruption_energy);
+        assert!((impact.largest_remnant / expected - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_debris_conserves_mass_and_momentum() {
+        let (mut target, mut projectile) = pair(50.0, 0.3);
+        let total = target.mass + projectile.mass;
+        let center = (target.position * target.mass + projectile.position * projectile.mass) / total;
+        let momentum = target.velocity * target.mass + projectile.velocity * projectile.mass;
+        let (regime, debris) = fragment_collision(&mut target, &mut projectile, 7);
+        assert_eq!(regime, ImpactRegime::Erosion);
+        assert_eq!(debris.len(), 8);
+        assert!(!projectile.is_active && target.mass < total / 2.0);
+
+        let mass = target.mass + debris.iter().map(|f| f.mass).sum::<f64>();
+        let moment = debris.iter().fold(target.position * target.mass, |sum, f| sum + f.position * f.mass);
+        let p = debris.iter().fold(target.velocity * target.mass, |sum, f| sum + f.velocity * f.mass);
+        assert!((mass / total - 1.0).abs() < 1e-12);
+        assert!((moment / mass - center).length() < 1e-6);
+        assert!((p - momentum).length() < 1e-12 * total * 50.0);
+        for f in &debris {
+            assert!(f.is_fragment && f.name.starts_with("Rock_"));
+            assert!((f.position - target.position).length() > target.radius + f.radius);
+        }
+
+        // Deterministic from the seeds
+        let (mut a, mut b) = pair(50.0, 0.3);
+        let (_, again) = fragment_collision(&mut a, &mut b, 7);
+        assert!(debris.iter().zip(&again).all(|(x, y)| x.position == y.position && x.velocity == y.velocity));
+    }
+
+    #[test]
+    fn test_hit_and_run_passes_through() {
+        let (mut target, mut projectile) = pair(30.0, 0.9);
+        let (regime, debris) = fragment_collision(&mut target, &mut projectile, 8);
+        assert_eq!(regime, ImpactRegime::HitAndRun);
+        assert!(debris.is_empty() && target.is_active && projectile.is_active);
+        let offset = projectile.position - target.position;
+        assert!((offset.length() - 2.0e4).abs() < 1e-6);
+        assert!(offset.dot(projectile.velocity - target.velocity) > 0.0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Zonal harmonic gravity (J2, J4) of oblate bodies
+//!
+//! An axisymmetric body of mass M and radius R has the exterior potential
+//!
+//!   U(r) = −GM/r · [1 − J2 (R/r)² P2(cos θ) − J4 (R/r)⁴ P4(cos θ)]
+//!
+//! with θ measured from its spin axis. The point-mass term is handled by the
+//! gravity solvers; this module adds the J2/J4 terms for bodies within
+//! `ZONAL_RANGE_RADII` of an oblate body, plus the equal and opposite
+//! reaction on the oblate body so momentum is conserved. The spin axis is
+//! held fixed, so the extra potential is conservative.
+
+use crate::body::Body;
+use crate::constants::G;
+use crate::vector::Vec3;
+
+/// Beyond this many radii an oblate body acts as a point mass
+/// (J2·(R/r)² is then below ~1e-8 even for the giant planets)
+pub const ZONAL_RANGE_RADII: f64 = 1000.0;
+
+/// J2/J4 field of one oblate body, frozen for a force pass
+#[derive(Debug, Clone, Copy)]
+pub struct ZonalField {
+    pub center: Vec3,
+    pub pole: Vec3,
+    pub gm: f64,
+    pub radius: f64,
+    pub j2: f64,
+    pub j4: f64,
+}
+
+impl ZonalField {
+    /// Field of `body`, if it is an active, massive, oblate source
+    pub fn of(body: &Body) -> Option<Self> {
+        if !body.is_active || !body.contributes_gravity || body.mass <= 0.0 || body.radius <= 0.0 {
+            return None;
+        }
+        if body.j2 == 0.0 && body.j4 == 0.0 {
+            return None;
+        }
+        Some(Self {
+            center: body.position,
+            pole: body.spin_axis(),
+            gm: G * body.mass,
+            radius: body.radius,
+            j2: body.j2,
+            j4: body.j4,
+        })
+    }
+
+    /// Whether `offset` (from the center) lies outside the body and inside
+    /// the near field
+    pub fn in_range(&self, offset: Vec3) -> bool {
+        let r2 = offset.length_squared();
+        let range = ZONAL_RANGE_RADII * self.radius;
+        r2 > self.radius * self.radius && r2 <= range * range
+    }
+
+    /// Potential per unit mass of the J2/J4 terms at `offset`
+    pub fn potential(&self, offset: Vec3) -> f64 {
+        let r = offset.length();
+        let u = offset.dot(self.pole) / r;
+        let s2 = (self.radius / r).powi(2);
+        let p2 = 0.5 * (3.0 * u * u - 1.0);
+        let p4 = (35.0 * u.powi(4) - 30.0 * u * u + 3.0) / 8.0;
+        self.gm / r * (self.j2 * s2 * p2 + self.j4 * s2 * s2 * p4)
+    }
+
+    /// Acceleration of a body at `offset` from the J2/J4 terms, −∇ of
+    /// `potential`, evaluated in the pole frame (u = cos θ)
+    pub fn acceleration(&self, offset: Vec3) -> Vec3 {
+        let r = offset.length();
+        let r_hat = offset / r;
+        let u = r_hat.dot(self.pole);
+        // ∇u = (pole − u·r̂) / r
+        let meridional = self.pole - r_hat * u;
+        let s2 = (self.radius / r).powi(2);
+        let scale = self.gm / (r * r);
+
+        let p2 = 0.5 * (3.0 * u * u - 1.0);
+        let dp2 = 3.0 * u;
+        let p4 = (35.0 * u.powi(4) - 30.0 * u * u + 3.0) / 8.0;
+        let dp4 = (35.0 * u.powi(3) - 15.0 * u) / 2.0;
+
+        // ∇[r^-(n+1) Pn(u)] = r^-(n+2) [−(n+1) Pn r̂ + Pn′(u)(pole − u r̂)]
+        let j2_term = (r_hat * (-3.0 * p2) + meridional * dp2) * (self.j2 * s2);
+        let j4_term = (r_hat * (-5.0 * p4) + meridional * dp4) * (self.j4 * s2 * s2);
+        (j2_term + j4_term) * -scale
+    }
+}
+
+/// Add J2/J4 accelerations between every oblate body and the bodies in its
+/// near field. Returns the potential energy of those terms.
+pub fn apply_zonal_harmonics(bodies: &mut [Body]) -> f64 {
+    let mut energy = 0.0;
+    for i in 0..bodies.len() {
+        let Some(field) = ZonalField::of(&bodies[i]) else {
+            continue;
+        };
+        let source_mass = bodies[i].mass;
+        for j in 0..bodies.len() {
+            if i == j || !bodies[j].is_active {
+                continue;
+            }
+            let offset = bodies[j].position - field.center;
+            if !field.in_range(offset) {
+                continue;
+            }
+            let acc = field.acceleration(offset);
+            if bodies[j].feels_gravity {
+                bodies[j].acceleration += acc;
+            }
+            if bodies[j].contributes_gravity {
+                let mass = bodies[j].mass;
+                if bodies[i].feels_gravity {
+                    bodies[i].acceleration -= acc * (mass / source_mass);
+                }
+                energy += mass * field.potential(offset);
+            }
+        }
+    }
+    energy
+}
+
+/// Potential energy of the J2/J4 terms (the part `apply_zonal_harmonics` adds)
+pub fn zonal_potential_energy(bodies: &[Body]) -> f64 {
+    let mut energy = 0.0;
+    for (i, source) in bodies.iter().enumerate() {
+        let Some(field) = ZonalField::of(source) else {
+            continue;
+        };
+        for (j, body) in bodies.iter().enumerate() {
+            if i == j || !body.is_active || !body.contributes_gravity {
+                continue;
+            }
+            let offset = body.position - field.center;
+            if field.in_range(offset) {
+                energy += body.mass * field.potential(offset);
+            }
+        }
+    }
+    energy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+
+    fn jupiter_field() -> ZonalField {
+        ZonalField {
+            center: Vec3::new(1.0e9, -2.0e9, 3.0e8),
+            pole: Vec3::new(0.1, -0.2, 1.0).normalize(),
+            gm: G * M_JUPITER,
+            radius: R_JUPITER,
+            j2: 0.014_696_5,
+            j4: -5.866e-4,
+        }
+    }
+
+    #[test]
+    fn test_acceleration_is_potential_gradient() {
+        let field = jupiter_field();
+        let offset = Vec3::new(1.3e8, 0.7e8, 0.9e8);
+        let acc = field.acceleration(offset);
+        let h = 1.0;
+        let axes = [Vec3::X, Vec3::Y, Vec3::Z];
+        for (axis, component) in axes.iter().zip(acc.to_array()) {
+            let gradient = (field.potential(offset + *axis * h) - field.potential(offset - *axis * h)) / (2.0 * h);
+            assert!((component + gradient).abs() < 1e-6 * acc.length(), "{} vs {}", component, -gradient);
+        }
+    }
+
+    #[test]
+    fn test_equatorial_j2_pull() {
+        // In the equatorial plane J2 strengthens the inward pull by 3/2 J2 (R/r)²
+        let field = ZonalField { j4: 0.0, center: Vec3::ZERO, pole: Vec3::Z, ..jupiter_field() };
+        let r = 4.0 * R_JUPITER;
+        let acc = field.acceleration(Vec3::new(r, 0.0, 0.0));
+        let expected = -1.5 * field.j2 * (R_JUPITER / r).powi(2) * field.gm / (r * r);
+        assert!((acc.x - expected).abs() < 1e-12 * expected.abs());
+        assert!(acc.y.abs() < 1e-20 && acc.z.abs() < 1e-20);
+    }
+}
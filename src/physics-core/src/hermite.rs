@@ -0,0 +1,361 @@
+//! Fourth-order Hermite predictor-corrector with block timesteps
+//!
+//! Every body carries its own step, a power-of-two fraction dt/2^k of the
+//! tick, chosen from its acceleration and derivatives with Aarseth's
+//! criterion. At each block time only the bodies whose step ends there are
+//! corrected; everyone else is predicted to that time with a Taylor series
+//! in the acceleration and jerk. A tight binary therefore takes thousands of
+//! small steps while the bodies around it take a handful, and all bodies
+//! meet again at the end of the tick.
+//!
+//! Accelerations and jerks come from direct summation over the predicted
+//! states, since the jerk needs relative velocities. The terms from
+//! `force::apply_additional_forces` are evaluated on a scratch copy of the
+//! bodies at the same predicted positions and velocities and added to the
+//! acceleration without a jerk, so they are integrated to second order.
+//! Times within a tick are counted in integer quanta of dt/2^MAX_LEVEL, so
+//! the schedule is exact and deterministic.
+//!
+//! Reference: J. Makino & S. J. Aarseth, "On a Hermite integrator with
+//! Ahmad-Cohen scheme for gravitational many-body problems",
+//! PASJ 44, 141 (1992)
+
+use crate::body::Body;
+use crate::force::{apply_additional_forces, gravitational_acceleration_and_jerk, has_additional_forces, ForceConfig};
+use crate::integrator::AccelerationFn;
+use crate::vector::Vec3;
+
+/// Deepest block level: the shortest step is dt / 2^MAX_LEVEL
+const MAX_LEVEL: u32 = 24;
+
+/// Aarseth accuracy parameter for steps after the first
+const ETA: f64 = 0.02;
+
+/// Accuracy parameter for the first step of a tick (|a|/|jerk| only)
+const ETA_START: f64 = 0.01;
+
+/// Block step of a level, in quanta
+fn level_quanta(level: u32) -> u64 {
+    1 << (MAX_LEVEL - level)
+}
+
+/// Shallowest level whose step dt/2^k does not exceed `step`
+fn level_for(step: f64, dt: f64) -> u32 {
+    let mut level = 0;
+    let mut block = dt.abs();
+    while level < MAX_LEVEL && block > step {
+        block *= 0.5;
+        level += 1;
+    }
+    level
+}
+
+/// Acceleration and jerk on body `i` from all sources at the given states
+fn evaluate(
+    bodies: &[Body],
+    positions: &[Vec3],
+    velocities: &[Vec3],
+    i: usize,
+    force_config: &ForceConfig,
+) -> (Vec3, Vec3) {
+    let (mut acc, mut jerk) = (Vec3::ZERO, Vec3::ZERO);
+    if !bodies[i].feels_gravity {
+        return (acc, jerk);
+    }
+    let eps_i = bodies[i].effective_softening(force_config.softening);
+    for (j, other) in bodies.iter().enumerate() {
+        if i == j || !other.is_active || !other.contributes_gravity {
+            continue;
+        }
+        let eps = eps_i.max(other.effective_softening(force_config.softening));
+        let (a, jk) = gravitational_acceleration_and_jerk(
+            positions[i],
+            velocities[i],
+            positions[j],
+            velocities[j],
+            other.mass,
+            eps * eps,
+        );
+        acc += a;
+        jerk += jk;
+    }
+    (acc, jerk)
+}
+
+/// Add the `apply_additional_forces` accelerations at the predicted states
+/// to `acc`, for the bodies listed in `indices`. `scratch` is a copy of the
+/// bodies whose dynamic fields are overwritten.
+fn add_additional_forces(
+    scratch: &mut [Body],
+    positions: &[Vec3],
+    velocities: &[Vec3],
+    acc: &mut [Vec3],
+    indices: &[usize],
+    force_config: &ForceConfig,
+) {
+    for (k, body) in scratch.iter_mut().enumerate() {
+        body.position = positions[k];
+        body.velocity = velocities[k];
+        body.acceleration = Vec3::ZERO;
+    }
+    apply_additional_forces(scratch, force_config);
+    for &i in indices {
+        acc[i] += scratch[i].acceleration;
+    }
+}
+
+/// Integrate all active bodies over `dt` with individual block timesteps.
+///
+/// Levels are chosen afresh each tick from the current state, so a tick
+/// depends only on the bodies passed in. Accelerations are current on
+/// return (evaluated with `accel_fn`); `prev_acceleration` holds each
+/// body's acceleration at the start of its last block step.
+/// Returns the potential energy from the final force evaluation.
+pub fn step_hermite_with(
+    bodies: &mut [Body],
+    dt: f64,
+    force_config: &ForceConfig,
+    accel_fn: AccelerationFn,
+) -> f64 {
+    if dt != 0.0 {
+        integrate(bodies, dt, force_config);
+    }
+    accel_fn(bodies, force_config)
+}
+
+/// Block-timestep integration over one tick; returns the number of steps
+/// each body took
+fn integrate(bodies: &mut [Body], dt: f64, force_config: &ForceConfig) -> Vec<u32> {
+    let n = bodies.len();
+
+    let end = level_quanta(0);
+    let quantum = dt / end as f64;
+
+    let mut predicted_x: Vec<Vec3> = bodies.iter().map(|b| b.position).collect();
+    let mut predicted_v: Vec<Vec3> = bodies.iter().map(|b| b.velocity).collect();
+    let mut acc = vec![Vec3::ZERO; n];
+    let mut jerk = vec![Vec3::ZERO; n];
+    let mut time = vec![0u64; n];
+    let mut level = vec![0u32; n];
+    let mut steps = vec![0u32; n];
+    let mut scratch = has_additional_forces(bodies, force_config).then(|| bodies.to_vec());
+
+    let mut active: Vec<usize> = (0..n).filter(|&i| bodies[i].is_active).collect();
+    for i in 0..n {
+        if !bodies[i].is_active {
+            // Never scheduled
+            time[i] = end;
+            continue;
+        }
+        (acc[i], jerk[i]) = evaluate(bodies, &predicted_x, &predicted_v, i, force_config);
+    }
+    if let Some(scratch) = scratch.as_mut() {
+        add_additional_forces(scratch, &predicted_x, &predicted_v, &mut acc, &active, force_config);
+    }
+    for &i in &active {
+        let (a, j) = (acc[i].length(), jerk[i].length());
+        if j > 0.0 {
+            level[i] = level_for(ETA_START * a / j, dt);
+        }
+    }
+
+    loop {
+        let next = (0..n)
+            .filter(|&i| time[i] < end)
+            .map(|i| time[i] + level_quanta(level[i]))
+            .min()
+            .unwrap_or(end);
+
+        // Predict every body to the block time
+        active.clear();
+        for (i, body) in bodies.iter().enumerate() {
+            if !body.is_active {
+                continue;
+            }
+            let tau = (next - time[i]) as f64 * quantum;
+            predicted_x[i] = body.position
+                + body.velocity * tau
+                + acc[i] * (tau * tau / 2.0)
+                + jerk[i] * (tau * tau * tau / 6.0);
+            predicted_v[i] = body.velocity + acc[i] * tau + jerk[i] * (tau * tau / 2.0);
+            if time[i] + level_quanta(level[i]) == next {
+                active.push(i);
+            }
+        }
+
+        let mut updates: Vec<(Vec3, Vec3)> = active
+            .iter()
+            .map(|&i| evaluate(bodies, &predicted_x, &predicted_v, i, force_config))
+            .collect();
+        if let Some(scratch) = scratch.as_mut() {
+            let mut extra = vec![Vec3::ZERO; n];
+            add_additional_forces(scratch, &predicted_x, &predicted_v, &mut extra, &active, force_config);
+            for (update, &i) in updates.iter_mut().zip(&active) {
+                update.0 += extra[i];
+            }
+        }
+
+        // Correct the active bodies and pick their next steps
+        for (&i, &(acc1, jerk1)) in active.iter().zip(&updates) {
+            let h = level_quanta(level[i]) as f64 * quantum;
+            let (acc0, jerk0) = (acc[i], jerk[i]);
+            let body = &mut bodies[i];
+
+            let velocity = body.velocity + (acc0 + acc1) * (h / 2.0) + (jerk0 - jerk1) * (h * h / 12.0);
+            body.position = body.position + (body.velocity + velocity) * (h / 2.0) + (acc0 - acc1) * (h * h / 12.0);
+            body.velocity = velocity;
+            body.prev_acceleration = acc0;
+
+            // Second and third derivatives of the acceleration at the step end
+            let snap0 = ((acc1 - acc0) * 6.0 - (jerk0 * 4.0 + jerk1 * 2.0) * h) / (h * h);
+            let crackle = ((acc0 - acc1) * 12.0 + (jerk0 + jerk1) * (6.0 * h)) / (h * h * h);
+            let snap1 = snap0 + crackle * h;
+
+            acc[i] = acc1;
+            jerk[i] = jerk1;
+            time[i] = next;
+            steps[i] += 1;
+
+            let (a, j, s, c) = (acc1.length(), jerk1.length(), snap1.length(), crackle.length());
+            let denominator = j * c + s * s;
+            let wanted = if denominator > 0.0 {
+                (ETA * (a * s + j * j) / denominator).sqrt()
+            } else {
+                f64::INFINITY
+            };
+            if wanted.is_nan() {
+                continue;
+            }
+            // Grow by at most a factor two, and only onto an aligned block
+            let mut new_level = level_for(wanted, dt).max(level[i].saturating_sub(1));
+            if new_level < level[i] && !next.is_multiple_of(level_quanta(new_level)) {
+                new_level = level[i];
+            }
+            level[i] = new_level;
+        }
+
+        if next == end {
+            return steps;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BodyType;
+    use crate::constants::*;
+    use crate::force::compute_accelerations_direct;
+    use crate::ias15::step_ias15_with;
+    use crate::relativity::RelativityMode;
+
+    #[test]
+    fn test_block_levels() {
+        assert_eq!(level_for(2.0, 1.0), 0);
+        assert_eq!(level_for(0.5, 1.0), 1);
+        assert_eq!(level_for(0.3, 1.0), 2);
+        assert_eq!(level_for(0.0, 1.0), MAX_LEVEL);
+        assert_eq!(level_quanta(0), 1 << MAX_LEVEL);
+    }
+
+    #[test]
+    fn test_bodies_take_their_own_steps() {
+        // A tight Earth-Moon pair and a distant Neptune around the Sun
+        let v_earth = (G * M_SUN / AU).sqrt();
+        let v_moon = (G * M_EARTH / 3.844e8).sqrt();
+        let v_neptune = (G * M_SUN / (30.0 * AU)).sqrt();
+        let mut bodies = vec![
+            Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO),
+            Body::new(1, "Earth", BodyType::Planet, M_EARTH, R_EARTH, Vec3::new(AU, 0.0, 0.0), Vec3::new(0.0, v_earth, 0.0)),
+            Body::new(
+                2, "Moon", BodyType::Moon, M_MOON, R_MOON,
+                Vec3::new(AU + 3.844e8, 0.0, 0.0),
+                Vec3::new(0.0, v_earth + v_moon, 0.0),
+            ),
+            Body::new(
+                3, "Neptune", BodyType::Planet, 1.024e26, 2.476e7,
+                Vec3::new(-30.0 * AU, 0.0, 0.0),
+                Vec3::new(0.0, -v_neptune, 0.0),
+            ),
+        ];
+        let config = ForceConfig::default();
+        compute_accelerations_direct(&mut bodies, &config);
+
+        let dt = 10.0 * SECONDS_PER_DAY;
+        let mut reference = bodies.clone();
+        step_ias15_with(&mut reference, dt, DEFAULT_IAS15_EPSILON, &config, compute_accelerations_direct);
+        let steps = integrate(&mut bodies, dt, &config);
+        assert_eq!(steps[3], 1, "Neptune should cover the tick in one block step");
+        assert!(steps[2] >= 16 * steps[3], "Moon took {} steps", steps[2]);
+
+        // The single Neptune step is still accurate to 4th order
+        let error = (bodies[3].position - reference[3].position).length();
+        assert!(error < 1.0, "Neptune error {} m", error);
+    }
+
+    #[test]
+    fn test_zonal_forces_enter_block_steps() {
+        // An inclined Io around an oblate Jupiter: J2 shifts it by kilometers a day
+        let r = 4.2e8;
+        let speed = (G * M_JUPITER / r).sqrt();
+        let mut bodies = vec![
+            Body::new(0, "Planet", BodyType::Planet, M_JUPITER, R_JUPITER, Vec3::ZERO, Vec3::ZERO),
+            Body::new(1, "Moon", BodyType::Moon, 1.0e20, 1.0e5, Vec3::new(r, 0.0, 0.0), Vec3::new(0.0, 0.6 * speed, 0.8 * speed)),
+        ];
+        for body in bodies.iter_mut() {
+            body.softening_length = 1.0;
+        }
+        let mut spherical = bodies.clone();
+        bodies[0].j2 = 0.0147;
+        let config = ForceConfig::default();
+        compute_accelerations_direct(&mut bodies, &config);
+        compute_accelerations_direct(&mut spherical, &config);
+
+        let dt = 0.5 * SECONDS_PER_DAY;
+        let mut reference = bodies.clone();
+        step_ias15_with(&mut reference, dt, DEFAULT_IAS15_EPSILON, &config, compute_accelerations_direct);
+        let mut classical = spherical.clone();
+        step_ias15_with(&mut classical, dt, DEFAULT_IAS15_EPSILON, &config, compute_accelerations_direct);
+        integrate(&mut spherical, dt, &config);
+        integrate(&mut bodies, dt, &config);
+
+        // Compare the J2 shift itself, free of truncation error
+        let expected = reference[1].position - classical[1].position;
+        let shift = expected.length();
+        let error = (bodies[1].position - spherical[1].position - expected).length();
+        assert!(shift > 1000.0, "J2 shift {} m", shift);
+        assert!(error < 0.05 * shift, "Hermite shift off by {} m of {} m", error, shift);
+    }
+
+    #[test]
+    fn test_velocity_dependent_forces_enter_block_steps() {
+        // Mercury at perihelion: the 1PN term shifts it by tens of meters a day
+        let r = 4.6e10;
+        let mut bodies = vec![
+            Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO),
+            Body::new(1, "Mercury", BodyType::Planet, 3.301e23, 2.44e6, Vec3::new(r, 0.0, 0.0), Vec3::new(0.0, 5.898e4, 0.0)),
+        ];
+        for body in bodies.iter_mut() {
+            body.softening_length = 1.0;
+        }
+        let newtonian = ForceConfig::default();
+        let config = ForceConfig { relativity: RelativityMode::Schwarzschild, ..newtonian };
+        compute_accelerations_direct(&mut bodies, &config);
+
+        let dt = 2.0 * SECONDS_PER_DAY;
+        let mut reference = bodies.clone();
+        step_ias15_with(&mut reference, dt, DEFAULT_IAS15_EPSILON, &config, compute_accelerations_direct);
+        let mut classical = bodies.clone();
+        step_ias15_with(&mut classical, dt, DEFAULT_IAS15_EPSILON, &newtonian, compute_accelerations_direct);
+        let mut plain = bodies.clone();
+        integrate(&mut plain, dt, &newtonian);
+        integrate(&mut bodies, dt, &config);
+
+        // Compare the relativistic shift itself, free of truncation error
+        let expected = reference[1].position - classical[1].position;
+        let shift = expected.length();
+        let error = (bodies[1].position - plain[1].position - expected).length();
+        assert!(shift > 10.0, "relativistic shift {} m", shift);
+        assert!(error < 0.05 * shift, "Hermite shift off by {} m of {} m", error, shift);
+    }
+}
@@ -0,0 +1,413 @@
+//! IAS15: adaptive 15th-order Gauss-Radau integrator for the whole system
+//!
+//! Each step fits the acceleration over [t, t+dt] with a 7th-degree
+//! polynomial sampled at the 8 Gauss-Radau spacings and integrates it
+//! analytically, giving a 15th-order scheme. The polynomial coefficients are
+//! found by predictor-corrector iteration, seeded from the previous step's
+//! polynomial. The next step size is a fixed fraction of the shortest
+//! per-body timescale of that polynomial (Pham, Rein & Spiegel 2024), which
+//! keeps the error per step below machine precision for the default
+//! tolerance, so long-term energy errors grow like a random walk. Unlike the
+//! original criterion on the last coefficient, it does not hit a round-off
+//! floor for tight moons at large heliocentric distances.
+//!
+//! Notation (h = fraction of the step, a₀ = acceleration at its start):
+//! - a(h) = a₀ + b₀h + b₁h² + … + b₆h⁷
+//! - a(h) = a₀ + g₀h + g₁h(h−h₁) + … + g₆h(h−h₁)…(h−h₆)
+//!
+//! The g's are divided differences of the samples; `c` and `d` convert
+//! between the two bases.
+//!
+//! Reference: H. Rein & D. S. Spiegel, "IAS15: a fast, adaptive, high-order
+//! integrator for gravitational dynamics", MNRAS 446, 1424 (2015);
+//! D. Pham, H. Rein & D. S. Spiegel, "A new timestep criterion for N-body
+//! simulations", Open J. Astrophys. 7 (2024)
+
+use crate::body::Body;
+use crate::force::ForceConfig;
+use crate::integrator::AccelerationFn;
+use crate::vector::Vec3;
+
+/// Gauss-Radau spacings on [0, 1]
+const H: [f64; 8] = [
+    0.0,
+    0.05626256053692215,
+    0.18024069173689236,
+    0.3526247171131696,
+    0.5471536263305554,
+    0.7342101772154105,
+    0.8853209468390958,
+    0.9775206135612875,
+];
+
+/// Predictor-corrector iteration limit per step
+const MAX_ITERATIONS: usize = 12;
+
+/// Predictor-corrector convergence threshold (relative to |a|)
+const CORRECTOR_TOLERANCE: f64 = 1e-16;
+
+/// Steps shrinking by more than this factor are rejected and redone
+const SAFETY_FACTOR: f64 = 0.25;
+
+/// Consecutive rejections after which a step is accepted anyway
+const MAX_REJECTIONS: usize = 16;
+
+/// Basis conversion tables between b and g coefficients
+struct Tables {
+    /// rr[n][m] = h_n − h_m
+    rr: [[f64; 8]; 8],
+    /// c[j][k]: coefficient of h^k in (h−h₁)…(h−h_j); b_k = Σ_j c[j][k]·g_j
+    c: [[f64; 7]; 7],
+    /// d = c⁻¹; g_j = Σ_k d[k][j]·b_k
+    d: [[f64; 7]; 7],
+}
+
+impl Tables {
+    fn new() -> Self {
+        let mut rr = [[0.0; 8]; 8];
+        for n in 0..8 {
+            for m in 0..8 {
+                rr[n][m] = H[n] - H[m];
+            }
+        }
+
+        let mut c = [[0.0; 7]; 7];
+        c[0][0] = 1.0;
+        for j in 1..7 {
+            // Multiply the previous product by (h − h_j)
+            for k in 0..=j {
+                let shifted = if k > 0 { c[j - 1][k - 1] } else { 0.0 };
+                c[j][k] = shifted - H[j] * c[j - 1][k];
+            }
+        }
+
+        // c is unit lower-triangular in (j, k); invert by forward substitution
+        let mut d = [[0.0; 7]; 7];
+        for (k, row) in d.iter_mut().enumerate() {
+            row[k] = 1.0;
+            for j in (0..k).rev() {
+                let sum: f64 = ((j + 1)..=k).map(|m| row[m] * c[m][j]).sum();
+                row[j] = -sum;
+            }
+        }
+
+        Self { rr, c, d }
+    }
+}
+
+/// Per-call integrator state, one entry per active-body component
+struct State {
+    x0: Vec<Vec3>,
+    v0: Vec<Vec3>,
+    a0: Vec<Vec3>,
+    /// Kahan compensation for x0 and v0
+    csx: Vec<Vec3>,
+    csv: Vec<Vec3>,
+    b: [Vec<Vec3>; 7],
+    g: [Vec<Vec3>; 7],
+    /// Predicted b for the current step (to correct the next prediction)
+    e: [Vec<Vec3>; 7],
+    /// b and e of the last accepted step (to re-predict after a rejection)
+    br: [Vec<Vec3>; 7],
+    er: [Vec<Vec3>; 7],
+}
+
+impl State {
+    fn new(n: usize) -> Self {
+        let zeros = || vec![Vec3::ZERO; n];
+        let set = || std::array::from_fn(|_| zeros());
+        Self {
+            x0: zeros(),
+            v0: zeros(),
+            a0: zeros(),
+            csx: zeros(),
+            csv: zeros(),
+            b: set(),
+            g: set(),
+            e: set(),
+            br: set(),
+            er: set(),
+        }
+    }
+}
+
+/// Largest absolute component over a set of vectors
+fn max_component(values: impl Iterator<Item = Vec3>) -> f64 {
+    values.fold(0.0, |m, v| m.max(v.x.abs()).max(v.y.abs()).max(v.z.abs()))
+}
+
+/// Kahan-compensated `sum += add`
+#[inline]
+fn add_compensated(sum: &mut Vec3, compensation: &mut Vec3, add: Vec3) {
+    let y = add - *compensation;
+    let t = *sum + y;
+    *compensation = (t - *sum) - y;
+    *sum = t;
+}
+
+/// Extrapolate the acceleration polynomial to a step `ratio` times as long.
+/// `b` and `e` are the converged and predicted coefficients of the step
+/// just taken; the prediction is corrected by their difference.
+fn predict_next_step(ratio: f64, b_prev: &[Vec<Vec3>; 7], e_prev: &[Vec<Vec3>; 7], b: &mut [Vec<Vec3>; 7], e: &mut [Vec<Vec3>; 7]) {
+    if ratio > 20.0 {
+        // Extrapolating this far is worse than starting from scratch
+        for k in 0..7 {
+            b[k].fill(Vec3::ZERO);
+            e[k].fill(Vec3::ZERO);
+        }
+        return;
+    }
+
+    let q1 = ratio;
+    let q2 = q1 * q1;
+    let q3 = q1 * q2;
+    let q4 = q2 * q2;
+    let q5 = q2 * q3;
+    let q6 = q3 * q3;
+    let q7 = q3 * q4;
+
+    for i in 0..b[0].len() {
+        let p: [Vec3; 7] = std::array::from_fn(|k| b_prev[k][i]);
+        let be: [Vec3; 7] = std::array::from_fn(|k| b_prev[k][i] - e_prev[k][i]);
+        let predicted = [
+            (p[6] * 7.0 + p[5] * 6.0 + p[4] * 5.0 + p[3] * 4.0 + p[2] * 3.0 + p[1] * 2.0 + p[0]) * q1,
+            (p[6] * 21.0 + p[5] * 15.0 + p[4] * 10.0 + p[3] * 6.0 + p[2] * 3.0 + p[1]) * q2,
+            (p[6] * 35.0 + p[5] * 20.0 + p[4] * 10.0 + p[3] * 4.0 + p[2]) * q3,
+            (p[6] * 35.0 + p[5] * 15.0 + p[4] * 5.0 + p[3]) * q4,
+            (p[6] * 21.0 + p[5] * 6.0 + p[4]) * q5,
+            (p[6] * 7.0 + p[5]) * q6,
+            p[6] * q7,
+        ];
+        for k in 0..7 {
+            e[k][i] = predicted[k];
+            b[k][i] = predicted[k] + be[k];
+        }
+    }
+}
+
+/// Position polynomial weights: x(h) − x₀ = h·dt·(v₀ + h·dt/2·(a₀ + Σ X_WEIGHTS[k]·b_k·h^(k+1)))
+const X_WEIGHTS: [f64; 7] = [1.0 / 3.0, 1.0 / 6.0, 1.0 / 10.0, 1.0 / 15.0, 1.0 / 21.0, 1.0 / 28.0, 1.0 / 36.0];
+
+/// Velocity polynomial weights: v(h) − v₀ = h·dt·(a₀ + Σ V_WEIGHTS[k]·b_k·h^(k+1))
+const V_WEIGHTS: [f64; 7] = [1.0 / 2.0, 1.0 / 3.0, 1.0 / 4.0, 1.0 / 5.0, 1.0 / 6.0, 1.0 / 7.0, 1.0 / 8.0];
+
+/// Σ weights[k]·b_k·h^(k+1) by Horner's rule
+#[inline]
+fn polynomial(b: &[Vec<Vec3>; 7], i: usize, weights: &[f64; 7], h: f64) -> Vec3 {
+    let mut sum = Vec3::ZERO;
+    for k in (0..7).rev() {
+        sum = (sum + b[k][i] * weights[k]) * h;
+    }
+    sum
+}
+
+/// Position and velocity increments over fraction `h` of a step of length `dt`
+#[inline]
+fn increments(state: &State, i: usize, dt: f64, h: f64) -> (Vec3, Vec3) {
+    let a0 = state.a0[i];
+    let dx = (state.v0[i] + (a0 + polynomial(&state.b, i, &X_WEIGHTS, h)) * (0.5 * h * dt)) * (h * dt);
+    let dv = (a0 + polynomial(&state.b, i, &V_WEIGHTS, h)) * (h * dt);
+    (dx, dv)
+}
+
+/// Set body positions and velocities to the polynomial prediction at
+/// fraction `h` of a step of length `dt`
+fn predict_state(bodies: &mut [Body], active: &[usize], state: &State, dt: f64, h: f64) {
+    for (i, &index) in active.iter().enumerate() {
+        let (dx, dv) = increments(state, i, dt, h);
+        let body = &mut bodies[index];
+        body.position = state.x0[i] + (dx - state.csx[i]);
+        body.velocity = state.v0[i] + (dv - state.csv[i]);
+    }
+}
+
+/// Integrate all active bodies over `dt` with IAS15, taking as many
+/// adaptive steps as needed to land exactly on `dt`.
+///
+/// Steps are `(5040·epsilon)^(1/7)` of the shortest acceleration timescale;
+/// the default epsilon of 1e-9 keeps the per-step error below double precision.
+/// Accelerations must be current on entry and are current on return.
+/// Returns the potential energy from the last force evaluation.
+pub fn step_ias15_with(
+    bodies: &mut [Body],
+    dt: f64,
+    epsilon: f64,
+    force_config: &ForceConfig,
+    accel_fn: AccelerationFn,
+) -> f64 {
+    let active: Vec<usize> = (0..bodies.len()).filter(|&i| bodies[i].is_active).collect();
+    if active.is_empty() || dt == 0.0 {
+        return accel_fn(bodies, force_config);
+    }
+
+    let tables = Tables::new();
+    let mut state = State::new(active.len());
+    for (i, &index) in active.iter().enumerate() {
+        state.x0[i] = bodies[index].position;
+        state.v0[i] = bodies[index].velocity;
+        state.a0[i] = bodies[index].acceleration;
+        bodies[index].prev_acceleration = bodies[index].acceleration;
+    }
+
+    let mut pe = 0.0;
+    let mut remaining = dt;
+    let mut trial_dt = dt;
+    let mut last_success_dt = 0.0;
+    let mut rejections = 0;
+
+    while remaining.abs() > 0.0 {
+        let step_dt = if trial_dt.abs() >= remaining.abs() { remaining } else { trial_dt };
+
+        // g from the predicted b
+        for j in 0..7 {
+            for i in 0..active.len() {
+                let mut g = Vec3::ZERO;
+                for k in j..7 {
+                    g += state.b[k][i] * tables.d[k][j];
+                }
+                state.g[j][i] = g;
+            }
+        }
+
+        let mut corrector_error = f64::INFINITY;
+        let mut corrector_error_last;
+        let mut iterations = 0;
+        loop {
+            if corrector_error < CORRECTOR_TOLERANCE || iterations >= MAX_ITERATIONS {
+                break;
+            }
+            corrector_error_last = corrector_error;
+            iterations += 1;
+
+            let mut max_delta = 0.0;
+            for (n, &h) in H.iter().enumerate().skip(1) {
+                predict_state(bodies, &active, &state, step_dt, h);
+                pe = accel_fn(bodies, force_config);
+
+                let j = n - 1;
+                for (i, &index) in active.iter().enumerate() {
+                    let at = bodies[index].acceleration;
+                    // Newton divided difference for g_j
+                    let mut gk = (at - state.a0[i]) / tables.rr[n][0];
+                    for m in 0..j {
+                        gk = (gk - state.g[m][i]) / tables.rr[n][m + 1];
+                    }
+                    let delta = gk - state.g[j][i];
+                    state.g[j][i] = gk;
+                    for k in 0..j {
+                        state.b[k][i] += delta * tables.c[j][k];
+                    }
+                    state.b[j][i] += delta;
+
+                    if n == 7 {
+                        max_delta = max_component(std::iter::once(delta)).max(max_delta);
+                    }
+                }
+            }
+
+            let max_accel = max_component(active.iter().map(|&index| bodies[index].acceleration));
+            corrector_error = if max_accel > 0.0 { max_delta / max_accel } else { 0.0 };
+            // Stop once round-off makes further iterations useless
+            if iterations > 2 && corrector_error >= corrector_error_last {
+                break;
+            }
+        }
+
+        // Step-size control: the shortest per-body timescale of the
+        // acceleration polynomial at the end of the step
+        let mut min_timescale2 = f64::INFINITY;
+        for i in 0..active.len() {
+            if state.a0[i].length_squared() == 0.0 {
+                continue;
+            }
+            let b = |k: usize| state.b[k][i];
+            let end = state.a0[i] + b(0) + b(1) + b(2) + b(3) + b(4) + b(5) + b(6);
+            let jerk = b(0) + b(1) * 2.0 + b(2) * 3.0 + b(3) * 4.0 + b(4) * 5.0 + b(5) * 6.0 + b(6) * 7.0;
+            let snap = b(1) * 2.0 + b(2) * 6.0 + b(3) * 12.0 + b(4) * 20.0 + b(5) * 30.0 + b(6) * 42.0;
+            let (y2, y3, y4) = (end.length_squared(), jerk.length_squared(), snap.length_squared());
+            let timescale2 = 2.0 * y2 / (y3 + (y4 * y2).sqrt());
+            if timescale2 < min_timescale2 {
+                min_timescale2 = timescale2;
+            }
+        }
+        let mut next_dt = if min_timescale2.is_normal() {
+            min_timescale2.sqrt() * step_dt * (epsilon * 5040.0).powf(1.0 / 7.0)
+        } else {
+            step_dt / SAFETY_FACTOR
+        };
+
+        if (next_dt / step_dt).abs() < SAFETY_FACTOR && rejections < MAX_REJECTIONS {
+            // Reject: redo with the smaller step, re-predicting from the last success
+            rejections += 1;
+                trial_dt = next_dt;
+            if last_success_dt != 0.0 {
+                predict_next_step(next_dt / last_success_dt, &state.br, &state.er, &mut state.b, &mut state.e);
+            } else {
+                for k in 0..7 {
+                    state.b[k].fill(Vec3::ZERO);
+                    state.e[k].fill(Vec3::ZERO);
+                }
+            }
+            continue;
+        }
+        rejections = 0;
+        if (next_dt / step_dt).abs() > 1.0 / SAFETY_FACTOR {
+            next_dt = step_dt / SAFETY_FACTOR;
+        }
+
+        // Accept: advance the start-of-step state with the full-step polynomial
+        for i in 0..active.len() {
+            let (dx, dv) = increments(&state, i, step_dt, 1.0);
+            add_compensated(&mut state.x0[i], &mut state.csx[i], dx);
+            add_compensated(&mut state.v0[i], &mut state.csv[i], dv);
+        }
+
+        for (i, &index) in active.iter().enumerate() {
+            bodies[index].position = state.x0[i];
+            bodies[index].velocity = state.v0[i];
+        }
+        pe = accel_fn(bodies, force_config);
+        for (i, &index) in active.iter().enumerate() {
+            state.a0[i] = bodies[index].acceleration;
+        }
+
+        remaining -= step_dt;
+        // Guard against round-off leaving a sliver of the interval
+        if remaining.abs() <= 1e-15 * dt.abs() {
+            remaining = 0.0;
+        }
+        last_success_dt = step_dt;
+        trial_dt = next_dt;
+
+        let State { b, e, br, er, .. } = &mut state;
+        for k in 0..7 {
+            br[k].clone_from(&b[k]);
+            er[k].clone_from(&e[k]);
+        }
+        let ratio = if remaining.abs() > 0.0 { trial_dt.abs().min(remaining.abs()) / step_dt.abs() } else { 1.0 };
+        predict_next_step(ratio, br, er, b, e);
+    }
+
+    pe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basis_tables_invert() {
+        let tables = Tables::new();
+        for k in 0..7 {
+            for j in 0..7 {
+                let product: f64 = (0..7).map(|m| tables.d[k][m] * tables.c[m][j]).sum();
+                let expected = if j == k { 1.0 } else { 0.0 };
+                assert!((product - expected).abs() < 1e-12, "d·c[{}][{}] = {}", k, j, product);
+            }
+        }
+        // Spot-check against the published constants
+        assert!((tables.c[1][0] + 0.056_262_560_536_922_15).abs() < 1e-15);
+        assert!((tables.d[1][0] - 0.056_262_560_536_922_15).abs() < 1e-15);
+        assert!((tables.c[6][0] - 0.001_271_790_309_026_868).abs() < 1e-15);
+    }
+}
@@ -8,8 +8,19 @@
 //! https://gafferongames.com/post/integration_basics/
 
 use crate::body::Body;
-use crate::force::{compute_accelerations_direct, ForceConfig};
+use crate::constants::G;
+use crate::constants::{DEFAULT_ADAPTIVE_ETA, DEFAULT_IAS15_EPSILON, DEFAULT_MAX_SUBSTEPS};
+use crate::force::{
+    compute_accelerations_direct, gravitational_acceleration, gravitational_acceleration_and_jerk, ForceConfig,
+};
+use crate::hermite::step_hermite_with;
+use crate::ias15::step_ias15_with;
+use crate::kepler::kepler_drift;
+use crate::rotation::advance_rotation;
+use crate::thrust::{advance_burns, schedule_thrust};
+use crate::tides::apply_tidal_spin;
 use crate::vector::Vec3;
+use crate::yarkovsky::apply_yorp;
 
 pub type AccelerationFn = fn(&mut [Body], &ForceConfig) -> f64;
 
@@ -27,6 +38,69 @@ pub enum IntegratorType {
     /// Leapfrog (symplectic, 2nd order)
     /// Equivalent to Velocity-Verlet, different formulation
     Leapfrog,
+
+    /// Yoshida triple-jump composition of Velocity-Verlet (symplectic, 4th order)
+    /// 3 force evaluations per step
+    Yoshida4,
+
+    /// Yoshida solution A composition of Velocity-Verlet (symplectic, 6th order)
+    /// 7 force evaluations per step
+    Yoshida6,
+
+    /// Forest-Ruth in drift-first form (symplectic, 4th order)
+    /// 4 force evaluations per step
+    ForestRuth,
+
+    /// Omelyan-Mryglod-Folk position-extended Forest-Ruth-like (symplectic, 4th order)
+    /// 4 force evaluations per step, ~100x smaller error constant than Forest-Ruth
+    Pefrl,
+
+    /// Wisdom-Holman mixed-variable map in democratic heliocentric coordinates
+    /// (symplectic, 2nd order in dt, errors scale with planet/star mass ratio)
+    /// Kepler drift around the dominant body plus interaction kicks; for
+    /// star-dominated systems with timesteps of a fraction of the inner period
+    WisdomHolman,
+
+    /// IAS15 adaptive Gauss-Radau (15th order, machine-precision energy)
+    /// Picks its own internal steps; dt only sets how often control returns
+    Ias15,
+
+    /// Hermite predictor-corrector with per-body block timesteps (4th order)
+    /// Bodies step at dt/2^k by their own timescales and resynchronize each tick;
+    /// point-mass gravity and jerk are direct-summed, additional forces are
+    /// added without a jerk (second order)
+    Hermite,
+}
+
+impl IntegratorType {
+    /// Parse a method name ("verlet", "leapfrog", "euler", "yoshida4",
+    /// "yoshida6", "forest-ruth", "pefrl", "wisdom-holman", "ias15", "hermite")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "verlet" => Some(Self::VelocityVerlet),
+            "leapfrog" => Some(Self::Leapfrog),
+            "euler" => Some(Self::Euler),
+            "yoshida4" => Some(Self::Yoshida4),
+            "yoshida6" => Some(Self::Yoshida6),
+            "forest-ruth" => Some(Self::ForestRuth),
+            "pefrl" => Some(Self::Pefrl),
+            "wisdom-holman" | "whfast" => Some(Self::WisdomHolman),
+            "ias15" => Some(Self::Ias15),
+            "hermite" => Some(Self::Hermite),
+            _ => None,
+        }
+    }
+
+    /// Order of accuracy of the method
+    pub fn order(&self) -> u32 {
+        match self {
+            Self::Euler => 1,
+            Self::VelocityVerlet | Self::Leapfrog | Self::WisdomHolman => 2,
+            Self::Yoshida4 | Self::ForestRuth | Self::Pefrl | Self::Hermite => 4,
+            Self::Yoshida6 => 6,
+            Self::Ias15 => 15,
+        }
+    }
 }
 
 /// Close-encounter integrator selection (subset-scoped).
@@ -105,12 +179,18 @@ pub struct IntegratorConfig {
     
     /// Integrator method
     pub method: IntegratorType,
+
+    /// IAS15 step-size tolerance (see `DEFAULT_IAS15_EPSILON`)
+    pub ias15_epsilon: f64,
     
     /// Force calculation settings
     pub force_config: ForceConfig,
 
     /// Close-encounter switching settings
     pub close_encounter: CloseEncounterConfig,
+
+    /// Per-tick substep selection (overrides `substeps` when enabled)
+    pub adaptive: AdaptiveSubstepConfig,
 }
 
 impl Default for IntegratorConfig {
@@ -119,12 +199,101 @@ impl Default for IntegratorConfig {
             dt: 1.0 / 60.0, // 60 Hz default
             substeps: 4,
             method: IntegratorType::VelocityVerlet,
+            ias15_epsilon: DEFAULT_IAS15_EPSILON,
             force_config: ForceConfig::default(),
             close_encounter: CloseEncounterConfig::default(),
+            adaptive: AdaptiveSubstepConfig::default(),
+        }
+    }
+}
+
+/// Adaptive substep configuration.
+///
+/// Each tick the substep length is set to `eta` times the shortest dynamical
+/// timescale in the system, so the substep count follows close approaches
+/// and tight binaries instead of a hand-tuned constant.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveSubstepConfig {
+    /// Pick the substep count each tick instead of using `substeps`.
+    pub enabled: bool,
+    /// Substep length as a fraction of the shortest timescale.
+    pub eta: f64,
+    /// Upper bound on substeps per tick.
+    pub max_substeps: u32,
+}
+
+impl Default for AdaptiveSubstepConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            eta: DEFAULT_ADAPTIVE_ETA,
+            max_substeps: DEFAULT_MAX_SUBSTEPS,
         }
     }
 }
 
+/// Shortest dynamical timescale among active bodies, in seconds.
+///
+/// Per body this is |a| / |jerk|, with `a` read from the (current)
+/// `acceleration` field and the jerk summed analytically from relative
+/// positions and velocities; per gravitating pair it is the free-fall time
+/// sqrt(r³ / G(m_i + m_j)), which covers bodies momentarily at rest.
+/// Both depend only on the current state, so the result is reproducible.
+/// Returns infinity when nothing interacts.
+pub fn min_dynamical_timescale(bodies: &[Body], force_config: &ForceConfig) -> f64 {
+    let mut tau = f64::INFINITY;
+
+    for (i, body) in bodies.iter().enumerate() {
+        if !body.is_active || !body.feels_gravity {
+            continue;
+        }
+        let mut jerk = Vec3::ZERO;
+        for (j, other) in bodies.iter().enumerate() {
+            if i == j || !other.is_active || !other.contributes_gravity || other.mass <= 0.0 {
+                continue;
+            }
+            let eps = body.effective_softening(force_config.softening)
+                .max(other.effective_softening(force_config.softening));
+            let r2 = body.position.distance_squared(other.position) + eps * eps;
+            if r2 <= 0.0 {
+                continue;
+            }
+            jerk += gravitational_acceleration_and_jerk(
+                body.position,
+                body.velocity,
+                other.position,
+                other.velocity,
+                other.mass,
+                eps * eps,
+            )
+            .1;
+
+            let free_fall = (r2 * r2.sqrt() / (G * (body.mass + other.mass))).sqrt();
+            tau = tau.min(free_fall);
+        }
+
+        let accel = body.acceleration.length();
+        let jerk = jerk.length();
+        if accel > 0.0 && jerk > 0.0 {
+            tau = tau.min(accel / jerk);
+        }
+    }
+
+    tau
+}
+
+/// Substeps needed to cover `dt` in steps of at most `eta` times the
+/// shortest dynamical timescale, clamped to `[1, max_substeps]`.
+pub fn adaptive_substep_count(bodies: &[Body], dt: f64, config: &AdaptiveSubstepConfig, force_config: &ForceConfig) -> u32 {
+    let max_substeps = config.max_substeps.max(1);
+    let tau = min_dynamical_timescale(bodies, force_config);
+    let count = (dt.abs() / (config.eta * tau)).ceil();
+    if count.is_nan() {
+        return max_substeps;
+    }
+    (count as u32).clamp(1, max_substeps)
+}
+
 /// Velocity-Verlet integration step.
 /// 
 /// This is a symplectic integrator that conserves energy well over long periods.
@@ -144,6 +313,7 @@ fn step_velocity_verlet_with(
 ) -> f64 {
     let half_dt_squared = 0.5 * dt * dt;
     let half_dt = 0.5 * dt;
+    let predict_velocity = force_config.velocity_dependent();
 
     // Step 1: Update positions using current velocities and accelerations
     // x(t+dt) = x(t) + v(t)*dt + 0.5*a(t)*dt²
@@ -157,6 +327,11 @@ fn step_velocity_verlet_with(
         
         // Update position
         body.position += body.velocity * dt + body.acceleration * half_dt_squared;
+
+        // Velocity-dependent forces see the predicted v(t+dt) = v(t) + a(t)*dt
+        if predict_velocity {
+            body.velocity += body.acceleration * dt;
+        }
     }
 
     // Step 2: Compute new accelerations from new positions
@@ -169,7 +344,12 @@ fn step_velocity_verlet_with(
             continue;
         }
         
-        body.velocity += (body.prev_acceleration + body.acceleration) * half_dt;
+        if predict_velocity {
+            // Same update, starting from the predicted velocity
+            body.velocity += (body.acceleration - body.prev_acceleration) * half_dt;
+        } else {
+            body.velocity += (body.prev_acceleration + body.acceleration) * half_dt;
+        }
     }
     
     pe
@@ -215,6 +395,7 @@ fn step_leapfrog_with(
     accel_fn: AccelerationFn,
 ) -> f64 {
     let half_dt = 0.5 * dt;
+    let predict_velocity = force_config.velocity_dependent();
 
     // Kick: v(t+dt/2) = v(t) + a(t) * dt/2
     for body in bodies.iter_mut() {
@@ -230,6 +411,12 @@ fn step_leapfrog_with(
             continue;
         }
         body.position += body.velocity * dt;
+
+        // Velocity-dependent forces see the predicted v(t+dt) = v(t+dt/2) + a(t) * dt/2
+        if predict_velocity {
+            body.prev_acceleration = body.acceleration;
+            body.velocity += body.acceleration * half_dt;
+        }
     }
 
     // Compute new accelerations
@@ -240,12 +427,281 @@ fn step_leapfrog_with(
         if !body.is_active {
             continue;
         }
-        body.velocity += body.acceleration * half_dt;
+        if predict_velocity {
+            body.velocity += (body.acceleration - body.prev_acceleration) * half_dt;
+        } else {
+            body.velocity += body.acceleration * half_dt;
+        }
     }
     
     pe
 }
 
+// === Higher-order symplectic compositions ===
+
+/// Yoshida (1990) 6th-order solution A weights w1..w3; w0 = 1 - 2(w1 + w2 + w3)
+const YOSHIDA6_WEIGHTS: [f64; 3] = [-1.177_679_984_178_87, 0.235_573_213_359_357, 0.784_513_610_477_560];
+
+/// Omelyan, Mryglod & Folk (2002) PEFRL coefficients
+const PEFRL_XI: f64 = 0.178_617_895_844_809_1;
+const PEFRL_LAMBDA: f64 = -0.212_341_831_062_605_4;
+const PEFRL_CHI: f64 = -0.066_264_582_669_818_5;
+
+/// Triple-jump weights (outer, inner) for 4th-order composition of a
+/// symmetric 2nd-order step
+fn triple_jump_weights() -> (f64, f64) {
+    let cbrt2 = 2.0_f64.cbrt();
+    let outer = 1.0 / (2.0 - cbrt2);
+    (outer, 1.0 - 2.0 * outer)
+}
+
+/// Compose Velocity-Verlet substeps of `weights[i] * dt`.
+/// 
+/// Each substep is symmetric and ends with accelerations at its new positions,
+/// so the composition reuses them and costs one force evaluation per weight.
+fn step_verlet_composition_with(
+    bodies: &mut [Body],
+    dt: f64,
+    weights: &[f64],
+    force_config: &ForceConfig,
+    accel_fn: AccelerationFn,
+) -> f64 {
+    let mut pe = 0.0;
+    for &w in weights {
+        pe = step_velocity_verlet_with(bodies, w * dt, force_config, accel_fn);
+    }
+    pe
+}
+
+/// Alternating kick/drift sequence: kick(kicks[0]), drift(drifts[0]),
+/// kick(kicks[1]), ..., kick(kicks[n]), with coefficients as fractions of `dt`.
+/// 
+/// Accelerations are recomputed after every drift, so a zero leading kick
+/// gives a drift-first scheme and a zero trailing kick still leaves
+/// accelerations consistent with the final positions. Velocity-dependent
+/// forces see the velocities of the current stage, which is accurate to
+/// first order in those (small) terms.
+fn step_kick_drift_with(
+    bodies: &mut [Body],
+    dt: f64,
+    kicks: &[f64],
+    drifts: &[f64],
+    force_config: &ForceConfig,
+    accel_fn: AccelerationFn,
+) -> f64 {
+    debug_assert_eq!(kicks.len(), drifts.len() + 1);
+    let kick = |bodies: &mut [Body], h: f64| {
+        if h == 0.0 {
+            return;
+        }
+        for body in bodies.iter_mut().filter(|b| b.is_active) {
+            body.velocity += body.acceleration * h;
+        }
+    };
+
+    for body in bodies.iter_mut() {
+        body.prev_acceleration = body.acceleration;
+    }
+
+    let mut pe = 0.0;
+    kick(bodies, kicks[0] * dt);
+    for (&d, &k) in drifts.iter().zip(&kicks[1..]) {
+        for body in bodies.iter_mut().filter(|b| b.is_active) {
+            body.position += body.velocity * (d * dt);
+        }
+        pe = accel_fn(bodies, force_config);
+        kick(bodies, k * dt);
+    }
+    pe
+}
+
+/// Yoshida 4th-order step: Velocity-Verlet substeps of w1, w0, w1 times dt
+pub fn step_yoshida4(bodies: &mut [Body], dt: f64, force_config: &ForceConfig) -> f64 {
+    step_yoshida4_with(bodies, dt, force_config, compute_accelerations_direct)
+}
+
+fn step_yoshida4_with(bodies: &mut [Body], dt: f64, force_config: &ForceConfig, accel_fn: AccelerationFn) -> f64 {
+    let (w1, w0) = triple_jump_weights();
+    step_verlet_composition_with(bodies, dt, &[w1, w0, w1], force_config, accel_fn)
+}
+
+/// Yoshida 6th-order step: seven Velocity-Verlet substeps (w3 w2 w1 w0 w1 w2 w3)
+pub fn step_yoshida6(bodies: &mut [Body], dt: f64, force_config: &ForceConfig) -> f64 {
+    step_yoshida6_with(bodies, dt, force_config, compute_accelerations_direct)
+}
+
+fn step_yoshida6_with(bodies: &mut [Body], dt: f64, force_config: &ForceConfig, accel_fn: AccelerationFn) -> f64 {
+    let [w1, w2, w3] = YOSHIDA6_WEIGHTS;
+    let w0 = 1.0 - 2.0 * (w1 + w2 + w3);
+    step_verlet_composition_with(bodies, dt, &[w3, w2, w1, w0, w1, w2, w3], force_config, accel_fn)
+}
+
+/// Forest-Ruth 4th-order step in its original drift-first form.
+/// 
+/// The kick-first form is algebraically identical to Yoshida4; this one
+/// samples forces at different points and needs a final force evaluation.
+pub fn step_forest_ruth(bodies: &mut [Body], dt: f64, force_config: &ForceConfig) -> f64 {
+    step_forest_ruth_with(bodies, dt, force_config, compute_accelerations_direct)
+}
+
+fn step_forest_ruth_with(bodies: &mut [Body], dt: f64, force_config: &ForceConfig, accel_fn: AccelerationFn) -> f64 {
+    let (theta, _) = triple_jump_weights();
+    let kicks = [0.0, theta, 1.0 - 2.0 * theta, theta, 0.0];
+    let drifts = [0.5 * theta, 0.5 * (1.0 - theta), 0.5 * (1.0 - theta), 0.5 * theta];
+    step_kick_drift_with(bodies, dt, &kicks, &drifts, force_config, accel_fn)
+}
+
+/// PEFRL 4th-order step (Omelyan et al. 2002) in kick-first form, which
+/// reuses the accelerations from the previous step.
+pub fn step_pefrl(bodies: &mut [Body], dt: f64, force_config: &ForceConfig) -> f64 {
+    step_pefrl_with(bodies, dt, force_config, compute_accelerations_direct)
+}
+
+fn step_pefrl_with(bodies: &mut [Body], dt: f64, force_config: &ForceConfig, accel_fn: AccelerationFn) -> f64 {
+    let middle = 1.0 - 2.0 * (PEFRL_CHI + PEFRL_XI);
+    let kicks = [PEFRL_XI, PEFRL_CHI, middle, PEFRL_CHI, PEFRL_XI];
+    let outer = 0.5 * (1.0 - 2.0 * PEFRL_LAMBDA);
+    let drifts = [outer, PEFRL_LAMBDA, PEFRL_LAMBDA, outer];
+    step_kick_drift_with(bodies, dt, &kicks, &drifts, force_config, accel_fn)
+}
+
+// === Wisdom-Holman (democratic heliocentric) ===
+
+/// The body Wisdom-Holman drifts everything around: the most massive active
+/// body that exerts and feels gravity
+fn central_body_index(bodies: &[Body]) -> Option<usize> {
+    bodies
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.is_active && b.contributes_gravity && b.feels_gravity && b.mass > 0.0)
+        .max_by(|(_, a), (_, b)| a.mass.total_cmp(&b.mass))
+        .map(|(i, _)| i)
+}
+
+/// Kick every non-central body by its acceleration minus the central body's
+/// pull, which the Kepler drift already accounts for. Pairwise interactions
+/// conserve momentum, so the central body's velocity is left alone.
+fn interaction_kick(bodies: &mut [Body], central: usize, h: f64, force_config: &ForceConfig) {
+    let (center_pos, center_mass) = (bodies[central].position, bodies[central].mass);
+    let center_softening = bodies[central].effective_softening(force_config.softening);
+    for (i, body) in bodies.iter_mut().enumerate() {
+        if i == central || !body.is_active || !body.feels_gravity {
+            continue;
+        }
+        let eps = body.effective_softening(force_config.softening).max(center_softening);
+        let central_pull = gravitational_acceleration(body.position, center_pos, center_mass, eps * eps);
+        body.velocity += (body.acceleration - central_pull) * h;
+    }
+}
+
+/// Drift under H_Kepler + H_jump: heliocentric positions follow Kepler
+/// orbits around the central body with barycentric velocities, sandwiched
+/// between half "jumps" by the total barycentric momentum over the central
+/// mass. The central body is then placed so the barycentre moves uniformly.
+fn democratic_heliocentric_drift(bodies: &mut [Body], central: usize, dt: f64) {
+    let participates = |b: &Body| b.is_active && b.feels_gravity;
+    let gravitating = |b: &Body| participates(b) && b.contributes_gravity;
+
+    let center_mass = bodies[central].mass;
+    let mu = G * center_mass;
+    let mut total_mass = 0.0;
+    let mut com_position = Vec3::ZERO;
+    let mut com_velocity = Vec3::ZERO;
+    for body in bodies.iter().filter(|b| gravitating(b)) {
+        total_mass += body.mass;
+        com_position += body.position * body.mass;
+        com_velocity += body.velocity * body.mass;
+    }
+    com_position /= total_mass;
+    com_velocity /= total_mass;
+
+    let center_position = bodies[central].position;
+    let jump_velocity = |bodies: &[Body]| {
+        let mut momentum = Vec3::ZERO;
+        for (i, body) in bodies.iter().enumerate() {
+            if i != central && gravitating(body) {
+                momentum += body.velocity * body.mass;
+            }
+        }
+        momentum / center_mass
+    };
+
+    // Heliocentric positions and barycentric velocities (stored in place)
+    for (i, body) in bodies.iter_mut().enumerate() {
+        if i == central || !body.is_active {
+            continue;
+        }
+        if participates(body) {
+            body.position -= center_position;
+            body.velocity -= com_velocity;
+        } else {
+            body.position += body.velocity * dt;
+        }
+    }
+
+    let jump = jump_velocity(bodies) * (0.5 * dt);
+    for (i, body) in bodies.iter_mut().enumerate() {
+        if i != central && participates(body) {
+            let (position, velocity) = kepler_drift(body.position + jump, body.velocity, mu, dt);
+            body.position = position;
+            body.velocity = velocity;
+        }
+    }
+    let jump = jump_velocity(bodies) * (0.5 * dt);
+
+    // Back to Cartesian: the barycentre drifts at constant velocity
+    let mut weighted_offset = Vec3::ZERO;
+    for (i, body) in bodies.iter_mut().enumerate() {
+        if i != central && participates(body) {
+            body.position += jump;
+            if body.contributes_gravity {
+                weighted_offset += body.position * body.mass;
+            }
+        }
+    }
+    let new_center = com_position + com_velocity * dt - weighted_offset / total_mass;
+    let center_velocity = com_velocity - jump_velocity(bodies);
+    for (i, body) in bodies.iter_mut().enumerate() {
+        if i != central && participates(body) {
+            body.position += new_center;
+            body.velocity += com_velocity;
+        }
+    }
+    bodies[central].position = new_center;
+    bodies[central].velocity = center_velocity;
+}
+
+/// Wisdom-Holman step (kick-drift-kick) in democratic heliocentric coordinates.
+/// 
+/// Errors scale with the planet/central mass ratio, so test particles around
+/// a lone star follow exact Kepler orbits at any timestep. Bodies that do
+/// not feel gravity drift in straight lines. Falls back to Velocity-Verlet when
+/// there is no central body.
+pub fn step_wisdom_holman(bodies: &mut [Body], dt: f64, force_config: &ForceConfig) -> f64 {
+    step_wisdom_holman_with(bodies, dt, force_config, compute_accelerations_direct)
+}
+
+fn step_wisdom_holman_with(
+    bodies: &mut [Body],
+    dt: f64,
+    force_config: &ForceConfig,
+    accel_fn: AccelerationFn,
+) -> f64 {
+    let Some(central) = central_body_index(bodies) else {
+        return step_velocity_verlet_with(bodies, dt, force_config, accel_fn);
+    };
+
+    for body in bodies.iter_mut() {
+        body.prev_acceleration = body.acceleration;
+    }
+
+    interaction_kick(bodies, central, 0.5 * dt, force_config);
+    democratic_heliocentric_drift(bodies, central, dt);
+    let pe = accel_fn(bodies, force_config);
+    interaction_kick(bodies, central, 0.5 * dt, force_config);
+    pe
+}
+
 /// Perform one integration step with the specified method.
 pub fn step(bodies: &mut [Body], config: &IntegratorConfig) -> f64 {
     step_with_accel(bodies, config, compute_accelerations_direct)
@@ -256,6 +712,11 @@ pub fn step_with_accel(bodies: &mut [Body], config: &IntegratorConfig, accel_fn:
     let mut pe = 0.0;
 
     for _ in 0..config.substeps {
+        // Engines switching on, off or turning with the orbit: start the
+        // substep from accelerations that include the new thrust
+        if schedule_thrust(bodies, substep_dt) {
+            accel_fn(bodies, &config.force_config);
+        }
         match config.method {
             IntegratorType::VelocityVerlet => {
                 pe = step_velocity_verlet_with(bodies, substep_dt, &config.force_config, accel_fn);
@@ -266,7 +727,36 @@ pub fn step_with_accel(bodies: &mut [Body], config: &IntegratorConfig, accel_fn:
             IntegratorType::Leapfrog => {
                 pe = step_leapfrog_with(bodies, substep_dt, &config.force_config, accel_fn);
             }
+            IntegratorType::Yoshida4 => {
+                pe = step_yoshida4_with(bodies, substep_dt, &config.force_config, accel_fn);
+            }
+            IntegratorType::Yoshida6 => {
+                pe = step_yoshida6_with(bodies, substep_dt, &config.force_config, accel_fn);
+            }
+            IntegratorType::ForestRuth => {
+                pe = step_forest_ruth_with(bodies, substep_dt, &config.force_config, accel_fn);
+            }
+            IntegratorType::Pefrl => {
+                pe = step_pefrl_with(bodies, substep_dt, &config.force_config, accel_fn);
+            }
+            IntegratorType::WisdomHolman => {
+                pe = step_wisdom_holman_with(bodies, substep_dt, &config.force_config, accel_fn);
+            }
+            IntegratorType::Ias15 => {
+                pe = step_ias15_with(bodies, substep_dt, config.ias15_epsilon, &config.force_config, accel_fn);
+            }
+            IntegratorType::Hermite => {
+                pe = step_hermite_with(bodies, substep_dt, &config.force_config, accel_fn);
+            }
+        }
+        advance_burns(bodies, substep_dt);
+        if config.force_config.tides {
+            apply_tidal_spin(bodies, substep_dt);
+        }
+        if config.force_config.yorp {
+            apply_yorp(bodies, substep_dt);
         }
+        advance_rotation(bodies, substep_dt, config.force_config.precession);
     }
     
     pe
@@ -975,4 +1465,27 @@ mod tests {
         // Spec requires < 0.1% error
         assert!(error_percent < 0.1, "Orbital period error {}% exceeds 0.1%", error_percent);
     }
+
+    #[test]
+    fn test_circular_orbit_timescale() {
+        // |a|/|jerk| and the free-fall time both equal 1/ω on a circular orbit
+        let mut bodies = vec![
+            Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO),
+            Body::new(
+                1, "Earth", BodyType::Planet, 0.0, R_EARTH,
+                Vec3::new(AU, 0.0, 0.0),
+                Vec3::new(0.0, (G * M_SUN / AU).sqrt(), 0.0),
+            ),
+        ];
+        let config = IntegratorConfig::default();
+        initialize_accelerations(&mut bodies, &config.force_config);
+
+        let inverse_omega = (AU.powi(3) / (G * M_SUN)).sqrt();
+        let tau = min_dynamical_timescale(&bodies, &config.force_config);
+        assert!((tau / inverse_omega - 1.0).abs() < 1e-9, "tau {} vs {}", tau, inverse_omega);
+
+        let adaptive = AdaptiveSubstepConfig { enabled: true, eta: 0.01, max_substeps: 1_000_000 };
+        let count = adaptive_substep_count(&bodies, SECONDS_PER_YEAR, &adaptive, &config.force_config);
+        assert_eq!(count, (SECONDS_PER_YEAR / (0.01 * tau)).ceil() as u32);
+    }
 }
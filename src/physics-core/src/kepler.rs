@@ -0,0 +1,209 @@
+//! Universal-variable Kepler propagation
+//!
+//! Advances a two-body relative state (position, velocity) by a time step
+//! using Stumpff functions and Lagrange f/g coefficients. One formulation
+//! covers elliptic, parabolic and hyperbolic orbits, so bodies can cross
+//! between regimes without special cases. Used by the Wisdom-Holman drift.
+
+use crate::vector::Vec3;
+
+/// Laguerre-Conway iteration limit
+const MAX_ITERATIONS: usize = 64;
+
+/// Laguerre-Conway degree parameter (5 is the usual choice)
+const LAGUERRE_N: f64 = 5.0;
+
+/// Relative convergence tolerance on the universal anomaly
+const TOLERANCE: f64 = 1e-15;
+
+/// Stumpff functions (c2(ψ), c3(ψ)).
+///
+/// c2 = (1 - cos √ψ) / ψ and c3 = (√ψ - sin √ψ) / ψ^(3/2), continued
+/// analytically to ψ ≤ 0. Near zero a power series avoids cancellation.
+pub fn stumpff(psi: f64) -> (f64, f64) {
+    if psi.abs() < 1.0 {
+        // c2 = Σ (-ψ)^k / (2k+2)!, c3 = Σ (-ψ)^k / (2k+3)!
+        let (mut c2, mut c3) = (0.0, 0.0);
+        let (mut term2, mut term3) = (0.5, 1.0 / 6.0);
+        for k in 0..16 {
+            c2 += term2;
+            c3 += term3;
+            let k = k as f64;
+            term2 *= -psi / ((2.0 * k + 3.0) * (2.0 * k + 4.0));
+            term3 *= -psi / ((2.0 * k + 4.0) * (2.0 * k + 5.0));
+        }
+        (c2, c3)
+    } else if psi > 0.0 {
+        let x = psi.sqrt();
+        let half = (0.5 * x).sin();
+        (2.0 * half * half / psi, (x - x.sin()) / (psi * x))
+    } else {
+        let x = (-psi).sqrt();
+        let half = (0.5 * x).sinh();
+        (2.0 * half * half / -psi, (x.sinh() - x) / (-psi * x))
+    }
+}
+
+/// Propagate a relative two-body state by `dt` around gravitational
+/// parameter `mu` (= G·M). Returns the new (position, velocity).
+///
+/// With no attractor (mu ≤ 0) or a state at the origin the body drifts in
+/// a straight line.
+pub fn kepler_drift(position: Vec3, velocity: Vec3, mu: f64, dt: f64) -> (Vec3, Vec3) {
+    let r0 = position.length();
+    if dt == 0.0 || mu <= 0.0 || r0 == 0.0 {
+        return (position + velocity * dt, velocity);
+    }
+
+    let sqrt_mu = mu.sqrt();
+    let sigma0 = position.dot(velocity) / sqrt_mu;
+    // α = 1/a (positive for bound orbits)
+    let alpha = 2.0 / r0 - velocity.length_squared() / mu;
+    let target = sqrt_mu * dt;
+    let chi = solve_universal_anomaly(r0, sigma0, alpha, target, sqrt_mu, dt);
+
+    let psi = alpha * chi * chi;
+    let (c2, c3) = stumpff(psi);
+    let chi2 = chi * chi;
+    let r = chi2 * c2 + sigma0 * chi * (1.0 - psi * c3) + r0 * (1.0 - psi * c2);
+
+    let f = 1.0 - chi2 * c2 / r0;
+    let g = dt - chi2 * chi * c3 / sqrt_mu;
+    let f_dot = sqrt_mu / (r * r0) * chi * (psi * c3 - 1.0);
+    let g_dot = 1.0 - chi2 * c2 / r;
+
+    (position * f + velocity * g, position * f_dot + velocity * g_dot)
+}
+
+/// Solve the universal Kepler equation
+/// √μ·dt = χ³c3 + σ0·χ²c2 + r0·χ(1 - ψc3), ψ = αχ², for χ
+fn solve_universal_anomaly(r0: f64, sigma0: f64, alpha: f64, target: f64, sqrt_mu: f64, dt: f64) -> f64 {
+    let mut chi = initial_guess(r0, sigma0, alpha, target, sqrt_mu, dt);
+
+    for _ in 0..MAX_ITERATIONS {
+        let chi2 = chi * chi;
+        let psi = alpha * chi2;
+        let (c2, c3) = stumpff(psi);
+
+        let f = chi2 * chi * c3 + sigma0 * chi2 * c2 + r0 * chi * (1.0 - psi * c3) - target;
+        // dF/dχ = r(χ)
+        let df = chi2 * c2 + sigma0 * chi * (1.0 - psi * c3) + r0 * (1.0 - psi * c2);
+        let ddf = sigma0 * (1.0 - psi * c2) + (1.0 - alpha * r0) * chi * (1.0 - psi * c3);
+
+        let n = LAGUERRE_N;
+        let disc = ((n - 1.0) * (n - 1.0) * df * df - n * (n - 1.0) * f * ddf).abs().sqrt();
+        let delta = n * f / (df + disc.copysign(df));
+        if !delta.is_finite() {
+            break;
+        }
+        chi -= delta;
+        if delta.abs() <= TOLERANCE * chi.abs().max(f64::MIN_POSITIVE) {
+            break;
+        }
+    }
+
+    chi
+}
+
+/// Starting χ: the first-order estimate for short steps, Vallado's
+/// estimates for steps that are long compared to the orbit
+fn initial_guess(r0: f64, sigma0: f64, alpha: f64, target: f64, sqrt_mu: f64, dt: f64) -> f64 {
+    let short = target / r0;
+    if alpha > 0.0 {
+        let per_orbit = std::f64::consts::TAU / alpha.sqrt();
+        if short.abs() < per_orbit {
+            short
+        } else {
+            target * alpha
+        }
+    } else if alpha < 0.0 {
+        let a = 1.0 / alpha;
+        let sign = dt.signum();
+        let guess = sign
+            * (-a).sqrt()
+            * ((-2.0 * sqrt_mu * sqrt_mu * alpha * dt)
+                / (sigma0 * sqrt_mu + sign * (-sqrt_mu * sqrt_mu * a).sqrt() * (1.0 - r0 * alpha)))
+                .ln();
+        if guess.is_finite() && guess.abs() < short.abs() {
+            guess
+        } else {
+            short
+        }
+    } else {
+        short
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+    use crate::presets::OrbitalElements;
+
+    fn assert_close(a: Vec3, b: Vec3, tol: f64) {
+        let scale = a.length().max(b.length());
+        assert!((a - b).length() <= tol * scale, "{:?} vs {:?}", a, b);
+    }
+
+    #[test]
+    fn test_stumpff_continuity() {
+        for psi in [1.0, -1.0] {
+            let below = stumpff(psi * (1.0 - 1e-12));
+            let above = stumpff(psi * (1.0 + 1e-12));
+            assert!((below.0 - above.0).abs() < 1e-12);
+            assert!((below.1 - above.1).abs() < 1e-12);
+        }
+        assert_eq!(stumpff(0.0), (0.5, 1.0 / 6.0));
+    }
+
+    #[test]
+    fn test_matches_mean_anomaly_propagation() {
+        let mu = G * M_SUN;
+        // Elliptic, very eccentric and hyperbolic orbits
+        for (a, e) in [(AU, 0.7), (0.3 * AU, 0.95), (-AU, 1.5)] {
+            let mut elements = OrbitalElements::from_degrees(a, e, 12.0, 40.0, 70.0, -20.0);
+            let (r0, v0) = elements.to_cartesian(mu);
+            let dt = 30.0 * 86_400.0;
+            let n = (mu / a.abs().powi(3)).sqrt();
+            elements.mean_anomaly += n * dt;
+            let (r1, v1) = elements.to_cartesian(mu);
+
+            let (r, v) = kepler_drift(r0, v0, mu, dt);
+            assert_close(r, r1, 1e-9);
+            assert_close(v, v1, 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_near_parabolic_composition() {
+        // One long drift equals many short ones through periapsis
+        let mu = G * M_SUN;
+        let elements = OrbitalElements::from_degrees(0.3 * AU, 0.999, 12.0, 40.0, 70.0, -20.0);
+        let (r0, v0) = elements.to_cartesian(mu);
+        let dt = 30.0 * 86_400.0;
+
+        let (r, v) = kepler_drift(r0, v0, mu, dt);
+        let (mut rs, mut vs) = (r0, v0);
+        for _ in 0..1000 {
+            (rs, vs) = kepler_drift(rs, vs, mu, dt / 1000.0);
+        }
+        assert_close(r, rs, 1e-11);
+        assert_close(v, vs, 1e-11);
+    }
+
+    #[test]
+    fn test_time_reversible() {
+        let mu = G * M_EARTH;
+        let r0 = Vec3::new(7.0e6, 1.0e5, -2.0e5);
+        let v0 = Vec3::new(100.0, 8.5e3, 1.0e3);
+        // Many orbits forward and back again
+        let (r, v) = kepler_drift(r0, v0, mu, 1.0e6);
+        let (r, v) = kepler_drift(r, v, mu, -1.0e6);
+        assert_close(r, r0, 1e-9);
+        assert_close(v, v0, 1e-9);
+
+        // Straight-line drift without an attractor
+        let (r, _) = kepler_drift(r0, v0, 0.0, 2.0);
+        assert_close(r, r0 + v0 * 2.0, 1e-15);
+    }
+}
@@ -4,7 +4,7 @@
 //! Uses SI units (meters, kilograms, seconds) throughout for scientific accuracy.
 //!
 //! # Features
-//! - Direct O(N²) and Barnes-Hut O(N log N) gravity solvers
+//! - Direct O(N²), Barnes-Hut O(N log N) and Fast Multipole O(N) gravity solvers
 //! - Symplectic Velocity-Verlet integrator for energy conservation  
 //! - Deterministic PRNG for reproducible simulations
 //! - Snapshot serialization for save/load and networking
@@ -26,29 +26,53 @@
 use wasm_bindgen::prelude::*;
 
 pub mod body;
+pub mod codec;
+pub mod collision;
 pub mod constants;
+pub mod disruption;
+pub mod drag;
+pub mod fmm;
 pub mod force;
+pub mod fragmentation;
+pub mod harmonics;
+pub mod hermite;
+pub mod ias15;
 pub mod integrator;
+pub mod kepler;
+pub mod mass_loss;
 pub mod octree;
+pub mod outgassing;
 pub mod planet;
 pub mod presets;
 pub mod prng;
+pub mod radiation;
+pub mod relativity;
+pub mod rings;
+pub mod rotation;
 pub mod simulation;
 pub mod snapshot;
 pub mod star;
+pub mod thrust;
+pub mod tides;
 pub mod vector;
+pub mod yarkovsky;
 
 // Re-exports for convenience
 pub mod prelude {
     pub use crate::body::{Atmosphere, Body, BodyId, BodyType, PlanetComposition};
+    pub use crate::collision::{CollisionConfig, CollisionOutcome};
     pub use crate::constants::*;
+    pub use crate::disruption::DisruptionConfig;
     pub use crate::force::ForceConfig;
     pub use crate::integrator::{CloseEncounterConfig, CloseEncounterIntegrator, IntegratorConfig, IntegratorType};
+    pub use crate::mass_loss::MassLossMode;
     pub use crate::presets::Preset;
     pub use crate::prng::Pcg32;
+    pub use crate::relativity::RelativityMode;
     pub use crate::simulation::{ForceMethod, Simulation, SimulationConfig};
     pub use crate::snapshot::Snapshot;
-    pub use crate::vector::Vec3;
+    pub use crate::thrust::{Burn, ThrustFrame};
+    pub use crate::vector::{Mat3, Quat, Vec3};
 }
 
 // WASM Bindings
@@ -107,21 +131,10 @@ impl WasmSimulation {
         px: f64, py: f64, pz: f64,
         vx: f64, vy: f64, vz: f64,
     ) -> u32 {
-        let bt = match body_type {
-            0 => body::BodyType::Star,
-            1 => body::BodyType::Planet,
-            2 => body::BodyType::Moon,
-            3 => body::BodyType::Asteroid,
-            4 => body::BodyType::Comet,
-            5 => body::BodyType::Spacecraft,
-            6 => body::BodyType::TestParticle,
-            7 => body::BodyType::Player,
-            _ => body::BodyType::Asteroid,
-        };
         let mut b = body::Body::new(
             0,
             name,
-            bt,
+            body_type_from_u8(body_type),
             mass,
             radius,
             vector::Vec3::new(px, py, pz),
@@ -144,12 +157,54 @@ impl WasmSimulation {
         }
     }
 
+    /// Fill a body's rings with `count` massless test particles on circular
+    /// equatorial orbits; returns their IDs (empty if it has no rings)
+    #[wasm_bindgen(js_name = addRingParticles)]
+    pub fn add_ring_particles(&mut self, id: u32, count: u32) -> Vec<u32> {
+        self.inner.add_ring_particles(id, count)
+    }
+
     /// Remove a body by ID
     #[wasm_bindgen(js_name = removeBody)]
     pub fn remove_body(&mut self, id: u32) -> bool {
         self.inner.remove_body(id)
     }
 
+    /// Give a body propellant (kg, counted in its mass) and an engine
+    /// exhaust velocity (m/s)
+    #[wasm_bindgen(js_name = setPropulsion)]
+    pub fn set_propulsion(&mut self, id: u32, propellant_mass: f64, exhaust_velocity: f64) -> bool {
+        self.inner.set_propulsion(id, propellant_mass, exhaust_velocity)
+    }
+
+    /// Queue a constant-thrust burn from simulation time `start` for
+    /// `duration` seconds. `thrust` is [x, y, z] in N, in frame "inertial",
+    /// "prograde" or "radial".
+    #[wasm_bindgen(js_name = scheduleBurn)]
+    pub fn schedule_burn(&mut self, id: u32, start: f64, duration: f64, thrust: &[f64], frame: &str) -> Result<bool, JsValue> {
+        let frame = thrust::ThrustFrame::from_name(frame).ok_or_else(|| JsValue::from_str("unknown thrust frame"))?;
+        let [x, y, z] = thrust else {
+            return Err(JsValue::from_str("thrust must have 3 components"));
+        };
+        Ok(self.inner.schedule_burn(id, start, duration, vector::Vec3::new(*x, *y, *z), frame))
+    }
+
+    /// Drop every burn queued on a body
+    #[wasm_bindgen(js_name = cancelBurns)]
+    pub fn cancel_burns(&mut self, id: u32) -> bool {
+        self.inner.cancel_burns(id)
+    }
+
+    /// Apply an impulsive Δv (m/s) in frame "inertial", "prograde" or
+    /// "radial". Returns the Δv applied in simulation axes as [x, y, z],
+    /// or an empty array for an unknown body.
+    #[wasm_bindgen(js_name = applyDeltaV)]
+    pub fn apply_delta_v(&mut self, id: u32, dvx: f64, dvy: f64, dvz: f64, frame: &str) -> Result<Vec<f64>, JsValue> {
+        let frame = thrust::ThrustFrame::from_name(frame).ok_or_else(|| JsValue::from_str("unknown thrust frame"))?;
+        let applied = self.inner.apply_delta_v(id, vector::Vec3::new(dvx, dvy, dvz), frame);
+        Ok(applied.map(|v| v.to_array().to_vec()).unwrap_or_default())
+    }
+
     /// Advance simulation by one tick
     pub fn step(&mut self) {
         self.inner.step();
@@ -171,6 +226,12 @@ impl WasmSimulation {
         self.inner.tick()
     }
 
+    /// Get the substep count used by the most recent tick
+    #[wasm_bindgen(js_name = lastSubsteps)]
+    pub fn last_substeps(&self) -> u32 {
+        self.inner.last_substeps()
+    }
+
     /// Get number of active bodies
     #[wasm_bindgen(js_name = bodyCount)]
     pub fn body_count(&self) -> usize {
@@ -189,6 +250,18 @@ impl WasmSimulation {
         self.inner.velocities_flat()
     }
 
+    /// Get all orientation quaternions as Float64Array [w0, x0, y0, z0, w1, ...]
+    #[wasm_bindgen(js_name = getOrientations)]
+    pub fn get_orientations(&self) -> Vec<f64> {
+        self.inner.orientations_flat()
+    }
+
+    /// Get all angular velocity vectors as Float64Array (rad/s)
+    #[wasm_bindgen(js_name = getAngularVelocities)]
+    pub fn get_angular_velocities(&self) -> Vec<f64> {
+        self.inner.angular_velocities_flat()
+    }
+
     /// Get body data as JSON (only active bodies)
     #[wasm_bindgen(js_name = getBodiesJson)]
     pub fn get_bodies_json(&self) -> String {
@@ -211,6 +284,74 @@ impl WasmSimulation {
         }
     }
 
+    /// Export full state as a binary snapshot (lossless)
+    #[wasm_bindgen(js_name = toBinary)]
+    pub fn to_binary(&self) -> Result<Vec<u8>, JsValue> {
+        self.inner.snapshot().to_binary().map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Export full state as a binary snapshot with positions/velocities
+    /// quantized to the given steps (m, m/s)
+    #[wasm_bindgen(js_name = toBinaryQuantized)]
+    pub fn to_binary_quantized(&self, position_step: f64, velocity_step: f64) -> Result<Vec<u8>, JsValue> {
+        let quantization = codec::Quantization::new(position_step, velocity_step);
+        self.inner
+            .snapshot()
+            .to_binary_quantized(quantization)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Import state from a binary snapshot
+    #[wasm_bindgen(js_name = fromBinary)]
+    pub fn from_binary(&mut self, bytes: &[u8]) -> bool {
+        match snapshot::Snapshot::from_binary(bytes) {
+            Ok(snapshot) => self.inner.restore(snapshot).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// Export a binary delta from a base binary snapshot to the current state.
+    /// A positive position_step quantizes the delta (velocity_step likewise).
+    #[wasm_bindgen(js_name = toBinaryDelta)]
+    pub fn to_binary_delta(&self, base: &[u8], position_step: f64, velocity_step: f64) -> Result<Vec<u8>, JsValue> {
+        let to_js = |e: codec::CodecError| JsValue::from_str(&e.to_string());
+        let base = snapshot::Snapshot::from_binary(base).map_err(to_js)?;
+        let delta = snapshot::DeltaSnapshot::from_diff(&base, &self.inner.snapshot());
+        let quantization = (position_step > 0.0).then(|| codec::Quantization::new(position_step, velocity_step));
+        delta.to_binary(&base, quantization).map_err(to_js)
+    }
+
+    /// Export a JSON delta from a base JSON snapshot to the current state,
+    /// sending bodies that moved more than the given thresholds (m, m/s)
+    #[wasm_bindgen(js_name = toJsonDelta)]
+    pub fn to_json_delta(&self, base: &str, position_threshold: f64, velocity_threshold: f64) -> Result<String, JsValue> {
+        let to_js = |e: serde_json::Error| JsValue::from_str(&e.to_string());
+        let base = snapshot::Snapshot::from_json(base).map_err(to_js)?;
+        let thresholds = snapshot::DeltaThresholds {
+            position: position_threshold,
+            velocity: velocity_threshold,
+            ..Default::default()
+        };
+        snapshot::DeltaSnapshot::from_diff_with_thresholds(&base, &self.inner.snapshot(), &thresholds)
+            .to_json()
+            .map_err(to_js)
+    }
+
+    /// Apply a JSON delta whose base sequence matches the current state
+    #[wasm_bindgen(js_name = applyDelta)]
+    pub fn apply_delta(&mut self, json: &str) -> Result<(), JsValue> {
+        let delta = snapshot::DeltaSnapshot::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.inner.apply_delta(delta).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Apply a binary delta whose base sequence matches the current state
+    #[wasm_bindgen(js_name = applyBinaryDelta)]
+    pub fn apply_binary_delta(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let base = self.inner.snapshot();
+        let delta = snapshot::DeltaSnapshot::from_binary(bytes, &base).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.inner.apply_delta(delta).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     /// Get total energy of the system
     #[wasm_bindgen(js_name = totalEnergy)]
     pub fn total_energy(&self) -> f64 {
@@ -229,6 +370,18 @@ impl WasmSimulation {
         self.inner.potential_energy()
     }
 
+    /// Get the energy carried away by stellar winds so far
+    #[wasm_bindgen(js_name = massLossEnergy)]
+    pub fn mass_loss_energy(&self) -> f64 {
+        self.inner.mass_loss_energy()
+    }
+
+    /// Get the total mass lost to stellar winds so far
+    #[wasm_bindgen(js_name = massLost)]
+    pub fn mass_lost(&self) -> f64 {
+        self.inner.mass_lost()
+    }
+
     /// Get total linear momentum as [px, py, pz]
     #[wasm_bindgen(js_name = totalMomentum)]
     pub fn total_momentum(&self) -> Vec<f64> {
@@ -262,6 +415,91 @@ impl WasmSimulation {
         self.inner.set_substeps(substeps);
     }
 
+    /// Set integration method ("verlet", "leapfrog", "euler", "yoshida4",
+    /// "yoshida6", "forest-ruth", "pefrl", "wisdom-holman", "ias15", "hermite")
+    #[wasm_bindgen(js_name = setIntegrator)]
+    pub fn set_integrator(&mut self, name: &str) -> Result<(), JsValue> {
+        let method = integrator::IntegratorType::from_name(name)
+            .ok_or_else(|| JsValue::from_str("unknown integrator"))?;
+        self.inner.set_integrator(method);
+        Ok(())
+    }
+
+    /// Set post-Newtonian corrections ("none", "schwarzschild", "eih")
+    #[wasm_bindgen(js_name = setRelativity)]
+    pub fn set_relativity(&mut self, name: &str) -> Result<(), JsValue> {
+        let mode = relativity::RelativityMode::from_name(name)
+            .ok_or_else(|| JsValue::from_str("unknown relativity mode"))?;
+        self.inner.set_relativity(mode);
+        Ok(())
+    }
+
+    /// Set gravitational-wave radiation reaction strength (0 = off, 1 = physical)
+    #[wasm_bindgen(js_name = setRadiationReaction)]
+    pub fn set_radiation_reaction(&mut self, scale: f64) {
+        self.inner.set_radiation_reaction(scale);
+    }
+
+    /// Enable or disable tidal dissipation and spin-orbit coupling
+    #[wasm_bindgen(js_name = setTides)]
+    pub fn set_tides(&mut self, enabled: bool) {
+        self.inner.set_tides(enabled);
+    }
+
+    /// Enable or disable radiation pressure and Poynting-Robertson drag
+    #[wasm_bindgen(js_name = setRadiation)]
+    pub fn set_radiation(&mut self, enabled: bool) {
+        self.inner.set_radiation(enabled);
+    }
+
+    /// Enable or disable drag inside planetary atmospheres
+    #[wasm_bindgen(js_name = setAtmosphericDrag)]
+    pub fn set_atmospheric_drag(&mut self, enabled: bool) {
+        self.inner.set_atmospheric_drag(enabled);
+    }
+
+    /// Enable or disable non-gravitational forces on outgassing comets
+    #[wasm_bindgen(js_name = setOutgassing)]
+    pub fn set_outgassing(&mut self, enabled: bool) {
+        self.inner.set_outgassing(enabled);
+    }
+
+    /// Enable or disable Yarkovsky drift of asteroids
+    #[wasm_bindgen(js_name = setYarkovsky)]
+    pub fn set_yarkovsky(&mut self, enabled: bool) {
+        self.inner.set_yarkovsky(enabled);
+    }
+
+    /// Enable or disable YORP spin evolution of asteroids
+    #[wasm_bindgen(js_name = setYorp)]
+    pub fn set_yorp(&mut self, enabled: bool) {
+        self.inner.set_yorp(enabled);
+    }
+
+    /// Enable or disable torque-driven spin-axis precession of oblate bodies
+    #[wasm_bindgen(js_name = setPrecession)]
+    pub fn set_precession(&mut self, enabled: bool) {
+        self.inner.set_precession(enabled);
+    }
+
+    /// Enable or disable per-tick adaptive substeps
+    #[wasm_bindgen(js_name = setAdaptiveSubsteps)]
+    pub fn set_adaptive_substeps(&mut self, enabled: bool) {
+        self.inner.set_adaptive_substeps(enabled);
+    }
+
+    /// Set adaptive substep fraction (eta) and per-tick cap
+    #[wasm_bindgen(js_name = setAdaptiveSubstepParams)]
+    pub fn set_adaptive_substep_params(&mut self, eta: f64, max_substeps: u32) {
+        self.inner.set_adaptive_substep_params(eta, max_substeps);
+    }
+
+    /// Set IAS15 step-size tolerance
+    #[wasm_bindgen(js_name = setIas15Epsilon)]
+    pub fn set_ias15_epsilon(&mut self, epsilon: f64) {
+        self.inner.set_ias15_epsilon(epsilon);
+    }
+
     /// Set Barnes-Hut theta parameter
     #[wasm_bindgen(js_name = setTheta)]
     pub fn set_theta(&mut self, theta: f64) {
@@ -280,6 +518,24 @@ impl WasmSimulation {
         self.inner.set_force_method(simulation::ForceMethod::BarnesHut);
     }
 
+    /// Use Fast Multipole Method O(N) force calculation
+    #[wasm_bindgen(js_name = useFmm)]
+    pub fn use_fmm(&mut self) {
+        self.inner.set_force_method(simulation::ForceMethod::Fmm);
+    }
+
+    /// Set FMM expansion order (1-12, higher is more accurate)
+    #[wasm_bindgen(js_name = setFmmOrder)]
+    pub fn set_fmm_order(&mut self, order: u32) {
+        self.inner.set_fmm_order(order);
+    }
+
+    /// Set FMM opening angle
+    #[wasm_bindgen(js_name = setFmmTheta)]
+    pub fn set_fmm_theta(&mut self, theta: f64) {
+        self.inner.set_fmm_theta(theta);
+    }
+
     /// Set close-encounter integrator ("none", "rk45", "gauss-radau")
     #[wasm_bindgen(js_name = setCloseEncounterIntegrator)]
     pub fn set_close_encounter_integrator(&mut self, name: &str) {
@@ -323,12 +579,105 @@ impl WasmSimulation {
         serde_json::to_string(&events).unwrap_or_default()
     }
 
+    /// Get current osculating elements of a body as JSON
+    /// ({"primary_id", "elements"}), or "null" if it orbits nothing
+    #[wasm_bindgen(js_name = getOsculatingElements)]
+    pub fn get_osculating_elements(&self, id: u32) -> String {
+        let result = self.inner.osculating_elements(id).map(|(primary_id, elements)| {
+            serde_json::json!({ "primary_id": primary_id, "elements": elements })
+        });
+        serde_json::to_string(&result).unwrap_or_default()
+    }
+
+    /// Refresh the orbital element fields stored on each body
+    #[wasm_bindgen(js_name = updateOrbitalElements)]
+    pub fn update_orbital_elements(&mut self) {
+        self.inner.update_orbital_elements();
+    }
+
+    /// Set how bodies with a mass-loss rate lose mass ("none", "isotropic")
+    #[wasm_bindgen(js_name = setMassLoss)]
+    pub fn set_mass_loss(&mut self, name: &str) -> Result<(), JsValue> {
+        let mode = mass_loss::MassLossMode::from_name(name)
+            .ok_or_else(|| JsValue::from_str("unknown mass-loss mode"))?;
+        self.inner.set_mass_loss(mode);
+        Ok(())
+    }
+
+    /// Enable or disable collision detection
+    #[wasm_bindgen(js_name = setCollisionsEnabled)]
+    pub fn set_collisions_enabled(&mut self, enabled: bool) {
+        self.inner.set_collisions_enabled(enabled);
+    }
+
+    /// Set collision outcome for a body type ("merge", "fragment", "bounce", "ignore")
+    #[wasm_bindgen(js_name = setCollisionOutcome)]
+    pub fn set_collision_outcome(&mut self, body_type: u8, name: &str) -> Result<(), JsValue> {
+        let outcome = collision::CollisionOutcome::from_name(name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown collision outcome: {}", name)))?;
+        self.inner.set_collision_outcome(body_type_from_u8(body_type), outcome);
+        Ok(())
+    }
+
+    /// Set coefficient of restitution for bouncing collisions
+    #[wasm_bindgen(js_name = setCollisionRestitution)]
+    pub fn set_collision_restitution(&mut self, restitution: f64) {
+        self.inner.set_collision_restitution(restitution);
+    }
+
+    /// Set the number of debris bodies per fragmenting impact
+    #[wasm_bindgen(js_name = setCollisionDebris)]
+    pub fn set_collision_debris(&mut self, count: u32) {
+        self.inner.set_collision_debris(count);
+    }
+
+    /// Drain collision events as JSON
+    #[wasm_bindgen(js_name = takeCollisionEvents)]
+    pub fn take_collision_events(&mut self) -> String {
+        let events = self.inner.take_collision_events();
+        serde_json::to_string(&events).unwrap_or_default()
+    }
+
+    /// Enable or disable tidal disruption at the Roche limit
+    #[wasm_bindgen(js_name = setDisruptionEnabled)]
+    pub fn set_disruption_enabled(&mut self, enabled: bool) {
+        self.inner.set_disruption_enabled(enabled);
+    }
+
+    /// Set how many fragments a disrupted body breaks into (at least 2)
+    #[wasm_bindgen(js_name = setDisruptionFragments)]
+    pub fn set_disruption_fragments(&mut self, fragments: u32) {
+        self.inner.set_disruption_fragments(fragments);
+    }
+
+    /// Drain tidal disruption events as JSON
+    #[wasm_bindgen(js_name = takeDisruptionEvents)]
+    pub fn take_disruption_events(&mut self) -> String {
+        let events = self.inner.take_disruption_events();
+        serde_json::to_string(&events).unwrap_or_default()
+    }
+
     /// Get a random number from the deterministic PRNG
     pub fn random(&mut self) -> f64 {
         self.inner.random()
     }
 }
 
+/// Map the numeric body type used by the JS API to `BodyType`
+fn body_type_from_u8(body_type: u8) -> body::BodyType {
+    match body_type {
+        0 => body::BodyType::Star,
+        1 => body::BodyType::Planet,
+        2 => body::BodyType::Moon,
+        3 => body::BodyType::Asteroid,
+        4 => body::BodyType::Comet,
+        5 => body::BodyType::Spacecraft,
+        6 => body::BodyType::TestParticle,
+        7 => body::BodyType::Player,
+        _ => body::BodyType::Asteroid,
+    }
+}
+
 /// Create a Sun-Earth-Moon preset
 #[wasm_bindgen(js_name = createSunEarthMoon)]
 pub fn create_sun_earth_moon(seed: u64) -> WasmSimulation {
@@ -0,0 +1,109 @@
+//! Stellar mass loss
+//!
+//! Bodies with a positive `mass_loss_rate` lose that many kg/s, applied
+//! once per tick after integration. In `Isotropic` mode the wind leaves
+//! symmetrically in the body's rest frame, so the body keeps its velocity
+//! and the wind carries off its share of momentum and energy. When the loss
+//! is slow compared with the orbital periods, orbits around the body expand
+//! adiabatically with a·M constant (Hadjidemetriou 1963).
+//!
+//! Bulk density, surface gravity and surface escape velocity are re-derived
+//! for the new mass; radius and luminosity are left to the caller.
+
+use crate::body::Body;
+use crate::constants::G;
+use serde::{Deserialize, Serialize};
+
+/// How bodies with a `mass_loss_rate` lose mass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MassLossMode {
+    /// Masses are held fixed
+    None,
+    /// Isotropic wind in the body's rest frame
+    #[default]
+    Isotropic,
+}
+
+impl MassLossMode {
+    /// Parse a mode name ("none", "isotropic")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Self::None),
+            "isotropic" => Some(Self::Isotropic),
+            _ => None,
+        }
+    }
+}
+
+/// Gravitational potential at body `index` due to all other bodies
+fn potential_at(bodies: &[Body], index: usize, softening: f64) -> f64 {
+    let body = &bodies[index];
+    bodies
+        .iter()
+        .enumerate()
+        .filter(|&(j, other)| j != index && other.is_active && other.contributes_gravity && other.mass > 0.0)
+        .map(|(_, other)| {
+            let eps = body.effective_softening(softening).max(other.effective_softening(softening));
+            let r = (body.position.distance_squared(other.position) + eps * eps).sqrt();
+            if r > 0.0 { -G * other.mass / r } else { 0.0 }
+        })
+        .sum()
+}
+
+/// Remove `dt` worth of wind from every mass-losing body.
+/// Returns the (mass, mechanical energy) carried away, so that the system
+/// energy plus the energy carried away is unchanged.
+pub fn apply_mass_loss(bodies: &mut [Body], dt: f64, mode: MassLossMode, softening: f64) -> (f64, f64) {
+    if mode == MassLossMode::None {
+        return (0.0, 0.0);
+    }
+    let (mut lost, mut energy) = (0.0, 0.0);
+    for i in 0..bodies.len() {
+        let body = &bodies[i];
+        if !body.is_active || body.mass <= 0.0 || body.mass_loss_rate <= 0.0 {
+            continue;
+        }
+        let dm = (body.mass_loss_rate * dt).min(body.mass);
+        if body.contributes_gravity {
+            energy += dm * (0.5 * body.velocity.length_squared() + potential_at(bodies, i, softening));
+        }
+        lost += dm;
+
+        let body = &mut bodies[i];
+        body.mass -= dm;
+        body.bulk_density = 0.0;
+        body.surface_gravity = 0.0;
+        body.escape_velocity_surface = 0.0;
+        body.compute_derived();
+    }
+    (lost, energy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BodyType;
+    use crate::constants::*;
+    use crate::force::compute_total_energy;
+    use crate::vector::Vec3;
+
+    #[test]
+    fn test_wind_carries_off_energy_and_rederives_gravity() {
+        let mut star = Body::new(0, "Star", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::new(0.0, -30.0, 0.0));
+        star.mass_loss_rate = 1.0e20;
+        star.compute_derived();
+        let planet = Body::new(1, "Planet", BodyType::Planet, M_JUPITER, R_JUPITER, Vec3::new(5.2 * AU, 0.0, 0.0), Vec3::new(0.0, 13_000.0, 0.0));
+        let mut bodies = vec![star, planet];
+        let gravity = bodies[0].surface_gravity;
+        let before = compute_total_energy(&bodies, 0.0);
+
+        let (lost, carried) = apply_mass_loss(&mut bodies, SECONDS_PER_YEAR, MassLossMode::Isotropic, 0.0);
+        assert_eq!(lost, 1.0e20 * SECONDS_PER_YEAR);
+        assert_eq!(bodies[0].velocity, Vec3::new(0.0, -30.0, 0.0));
+        let after = compute_total_energy(&bodies, 0.0);
+        assert!((before - after - carried).abs() < 1e-12 * before.abs());
+        assert!((bodies[0].surface_gravity / gravity - bodies[0].mass / M_SUN).abs() < 1e-12);
+
+        assert_eq!(apply_mass_loss(&mut bodies, SECONDS_PER_YEAR, MassLossMode::None, 0.0), (0.0, 0.0));
+    }
+}
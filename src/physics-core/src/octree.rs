@@ -8,7 +8,7 @@
 
 use crate::body::Body;
 use crate::constants::G;
-use crate::force::ForceConfig;
+use crate::force::{apply_additional_forces, ForceConfig};
 use crate::vector::Vec3;
 
 /// Default maximum octree depth to prevent infinite recursion
@@ -343,7 +343,7 @@ pub fn compute_accelerations_barnes_hut(bodies: &mut [Body], config: &ForceConfi
         total_pe += pe;
     }
     
-    total_pe / 2.0
+    total_pe / 2.0 + apply_additional_forces(bodies, config)
 }
 
 /// Compare Barnes-Hut accuracy against direct sum
@@ -357,6 +357,7 @@ pub fn compare_accuracy(bodies: &[Body], theta: f64, softening: f64) -> (f64, f6
     let config = ForceConfig {
         softening,
         barnes_hut_theta: theta,
+        ..ForceConfig::default()
     };
     
     compute_accelerations_direct(&mut direct_bodies, &config);
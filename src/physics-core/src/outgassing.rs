@@ -0,0 +1,107 @@
+//! Cometary non-gravitational forces
+//!
+//! Outgassing comets feel the Marsden-Sekanina acceleration (Marsden,
+//! Sekanina & Yeomans 1973)
+//!
+//!   a = g(r) (A1 r̂ + A2 t̂ + A3 n̂)
+//!
+//! in the comet's orbital frame: r̂ radial away from the star, n̂ along the
+//! orbital angular momentum and t̂ = n̂ × r̂ transverse in the direction of
+//! motion. The water-ice sublimation law
+//!
+//!   g(r) = α (r/r₀)^−m (1 + (r/r₀)^n)^−k
+//!
+//! is normalised to g(1 AU) = 1, so A1..A3 are the accelerations at 1 AU.
+//! Published values are in AU/day² (`AU_PER_DAY_SQUARED`); `nongrav_params`
+//! holds them in m/s².
+//!
+//! Each comet is driven by the star that lights it most. Around a star of
+//! luminosity L, r is scaled to the distance with the same sunlight,
+//! r·√(L☉/L). The reaction goes into the escaping gas, so momentum is not
+//! conserved.
+
+use crate::body::{Body, BodyType};
+use crate::constants::{AU, L_SUN, SECONDS_PER_DAY};
+use crate::radiation::brightest_source;
+
+/// Conversion from AU/day², the unit of published A1..A3, to m/s²
+pub const AU_PER_DAY_SQUARED: f64 = AU / (SECONDS_PER_DAY * SECONDS_PER_DAY);
+
+/// Normalisation α of the sublimation law, so that g(1 AU) = 1
+pub const SUBLIMATION_ALPHA: f64 = 0.111_262;
+/// Scale distance r₀ of the sublimation law in AU
+pub const SUBLIMATION_R0: f64 = 2.808;
+/// Exponents (m, n, k) of the sublimation law for water ice
+pub const SUBLIMATION_EXPONENTS: (f64, f64, f64) = (2.15, 5.093, 4.6142);
+
+/// Sublimation law g(r) for a distance in AU
+pub fn sublimation_g(r_au: f64) -> f64 {
+    let (m, n, k) = SUBLIMATION_EXPONENTS;
+    let x = r_au / SUBLIMATION_R0;
+    SUBLIMATION_ALPHA * x.powf(-m) * (1.0 + x.powf(n)).powf(-k)
+}
+
+/// Whether `body` is an outgassing comet
+pub fn is_outgassing(body: &Body) -> bool {
+    body.is_active && body.body_type == BodyType::Comet && body.nongrav_params != [0.0; 3]
+}
+
+/// Add the non-gravitational acceleration of every outgassing comet
+pub fn apply_outgassing(bodies: &mut [Body]) {
+    let sources: Vec<usize> = (0..bodies.len())
+        .filter(|&i| bodies[i].is_active && bodies[i].luminosity > 0.0)
+        .collect();
+    if sources.is_empty() {
+        return;
+    }
+
+    for i in 0..bodies.len() {
+        if !is_outgassing(&bodies[i]) || !bodies[i].feels_gravity {
+            continue;
+        }
+        let Some(s) = brightest_source(bodies, &sources, i) else {
+            continue;
+        };
+        let (comet, star) = (&bodies[i], &bodies[s]);
+        let r = comet.position - star.position;
+        let v = comet.velocity - star.velocity;
+        let distance = r.length();
+        if distance == 0.0 {
+            continue;
+        }
+        let radial = r / distance;
+        let normal = r.cross(v).normalize();
+        let transverse = normal.cross(radial);
+
+        let g = sublimation_g(distance / AU * (L_SUN / star.luminosity).sqrt());
+        let [a1, a2, a3] = comet.nongrav_params;
+        bodies[i].acceleration += (radial * a1 + transverse * a2 + normal * a3) * g;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+    use crate::vector::Vec3;
+
+    #[test]
+    fn test_sublimation_law_and_frame() {
+        assert!((sublimation_g(1.0) - 1.0).abs() < 1e-4);
+        // Sublimation shuts off beyond ~3 AU
+        assert!(sublimation_g(5.0) < 1e-3 * sublimation_g(2.0));
+
+        let mut sun = Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO);
+        sun.luminosity = L_SUN;
+        let mut comet = Body::new(1, "Comet", BodyType::Comet, 1.0e14, 5.0e3, Vec3::new(AU, 0.0, 0.0), Vec3::new(-1.0e4, 3.0e4, 0.0));
+        comet.nongrav_params = [1.0e-8, 2.0e-9, 3.0e-10];
+        let mut bodies = vec![sun, comet];
+        apply_outgassing(&mut bodies);
+        let acc = bodies[1].acceleration;
+        let g = sublimation_g(1.0);
+        assert!((acc.x - 1.0e-8 * g).abs() < 1e-20);
+        assert!((acc.y - 2.0e-9 * g).abs() < 1e-20);
+        assert!((acc.z - 3.0e-10 * g).abs() < 1e-20);
+        assert_eq!(bodies[0].acceleration, Vec3::ZERO);
+    }
+}
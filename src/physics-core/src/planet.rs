@@ -82,6 +82,20 @@ pub fn derive_planet_properties(body: &mut Body, parent: Option<&Body>) {
         }
     }
 
+    // ── Zonal harmonics (Darwin-Radau, homogeneous-spheroid J4) ──
+    if body.j2 == 0.0 && body.oblateness > 0.0 {
+        // Uniform sphere unless a moment of inertia factor is given
+        let k = if body.moment_of_inertia_factor > 0.0 { body.moment_of_inertia_factor } else { 0.4 };
+        let f = body.oblateness;
+        // Rotational parameter q = ω²R³/GM implied by f and C/MR²
+        let radau = 2.5 * (1.0 - 1.5 * k);
+        let q = 0.4 * f * (1.0 + radau * radau);
+        body.j2 = ((2.0 * f - q) / 3.0).max(0.0);
+    }
+    if body.j4 == 0.0 && body.j2 > 0.0 {
+        body.j4 = -15.0 / 7.0 * body.j2 * body.j2;
+    }
+
     // ── Atmosphere scale height (sync back to Atmosphere struct if present) ──
     if let Some(ref mut atm) = body.atmosphere {
         if atm.scale_height == 0.0 && body.scale_height > 0.0 {
@@ -8,6 +8,7 @@ use crate::simulation::Simulation;
 use crate::vector::Vec3;
 use crate::constants::*;
 use crate::prng::Pcg32;
+use crate::outgassing::AU_PER_DAY_SQUARED;
 
 /// Convert hex color to RGB array
 fn hex_to_rgb(hex: u32) -> [f64; 3] {
@@ -19,11 +20,21 @@ fn hex_to_rgb(hex: u32) -> [f64; 3] {
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-// ORBITAL MECHANICS: Kepler → Cartesian Conversion (J2000)
+// ORBITAL MECHANICS: Kepler ↔ Cartesian Conversion (J2000)
 // ═══════════════════════════════════════════════════════════════════════════
 
+use serde::{Deserialize, Serialize};
 use std::f64::consts::PI;
 
+/// Eccentricity below which an orbit is treated as circular
+const CIRCULAR_TOLERANCE: f64 = 1e-10;
+
+/// |e - 1| below which an orbit is treated as parabolic
+const PARABOLIC_TOLERANCE: f64 = 1e-10;
+
+/// sin(i) below which an orbit is treated as equatorial
+const EQUATORIAL_TOLERANCE: f64 = 1e-10;
+
 // ═══════════════════════════════════════════════════════════════════════════
 // STATISTICAL DISTRIBUTIONS FOR PROCEDURAL GENERATION
 // ═══════════════════════════════════════════════════════════════════════════
@@ -81,9 +92,18 @@ fn sample_plummer_radius(rng: &mut Pcg32, scale_radius: f64) -> f64 {
 
 /// Orbital elements for a body at J2000 epoch
 /// All angles in radians, distances in meters
-#[derive(Debug, Clone, Copy)]
+///
+/// Conic conventions:
+/// - Ellipse (e < 1): a > 0, M = E - e·sin(E)
+/// - Hyperbola (e > 1): a < 0, M = e·sinh(H) - H
+/// - Parabola (e = 1): a is undefined, so `semi_major_axis` holds the
+///   periapsis distance q and M = D + D³/3 with D = tan(ν/2) (Barker)
+///
+/// Circular orbits use ω = 0 and equatorial orbits use Ω = 0, so the
+/// mean anomaly carries the argument of latitude / true longitude.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct OrbitalElements {
-    /// Semi-major axis (meters)
+    /// Semi-major axis (meters; negative for hyperbolic, q for parabolic)
     pub semi_major_axis: f64,
     /// Eccentricity (dimensionless, 0 ≤ e < 1 for ellipses)
     pub eccentricity: f64,
@@ -154,6 +174,10 @@ impl OrbitalElements {
     /// Convert orbital elements to Cartesian state vectors (position, velocity)
     /// relative to the central body with gravitational parameter mu = G * M_central
     pub fn to_cartesian(&self, mu: f64) -> (Vec3, Vec3) {
+        if self.eccentricity >= 1.0 - PARABOLIC_TOLERANCE {
+            return self.open_orbit_to_cartesian(mu);
+        }
+
         let a = self.semi_major_axis;
         let e = self.eccentricity;
         let i = self.inclination;
@@ -210,12 +234,147 @@ impl OrbitalElements {
         (Vec3::new(x, y, z), Vec3::new(vx, vy, vz))
     }
 
+    /// Hyperbolic / parabolic branch of `to_cartesian`
+    fn open_orbit_to_cartesian(&self, mu: f64) -> (Vec3, Vec3) {
+        let e = self.eccentricity;
+        let m = self.mean_anomaly;
+
+        let (true_anomaly, p) = if (e - 1.0).abs() < PARABOLIC_TOLERANCE {
+            // Barker's equation D + D³/3 = M, solved in closed form
+            let b = 1.5 * m;
+            let w = (b + (b * b + 1.0).sqrt()).cbrt();
+            let d = w - 1.0 / w;
+            (2.0 * d.atan(), 2.0 * self.semi_major_axis)
+        } else {
+            let h = self.solve_kepler_hyperbolic();
+            let nu = 2.0 * ((e + 1.0).sqrt() * (h / 2.0).sinh())
+                .atan2((e - 1.0).sqrt() * (h / 2.0).cosh());
+            (nu, self.semi_major_axis * (1.0 - e * e))
+        };
+
+        let r = p / (1.0 + e * true_anomaly.cos());
+        let v_scale = (mu / p).sqrt();
+        let pos = Vec3::new(r * true_anomaly.cos(), r * true_anomaly.sin(), 0.0);
+        let vel = Vec3::new(-v_scale * true_anomaly.sin(), v_scale * (e + true_anomaly.cos()), 0.0);
+        (self.perifocal_to_reference(pos), self.perifocal_to_reference(vel))
+    }
+
+    /// Solve the hyperbolic Kepler equation M = e·sinh(H) - H for H
+    fn solve_kepler_hyperbolic(&self) -> f64 {
+        let m = self.mean_anomaly;
+        let e = self.eccentricity;
+
+        let mut h = (m / e).asinh();
+        for _ in 0..50 {
+            let f = e * h.sinh() - h - m;
+            let f_prime = e * h.cosh() - 1.0;
+            let delta = f / f_prime;
+            h -= delta;
+            if delta.abs() < 1e-12 * h.abs().max(1.0) {
+                break;
+            }
+        }
+        h
+    }
+
+    /// Rotate a perifocal vector into the reference frame: Rz(Ω)·Rx(i)·Rz(ω)
+    fn perifocal_to_reference(&self, v: Vec3) -> Vec3 {
+        let (sin_o, cos_o) = self.longitude_asc_node.sin_cos();
+        let (sin_w, cos_w) = self.arg_periapsis.sin_cos();
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+
+        let x1 = cos_w * v.x - sin_w * v.y;
+        let y1 = sin_w * v.x + cos_w * v.y;
+        let y2 = cos_i * y1;
+        let z2 = sin_i * y1;
+        Vec3::new(cos_o * x1 - sin_o * y2, sin_o * x1 + cos_o * y2, z2)
+    }
+
+    /// Convert Cartesian state vectors relative to a central body with
+    /// gravitational parameter mu = G * M_central into osculating elements.
+    ///
+    /// Inverse of `to_cartesian` for elliptic, parabolic and hyperbolic
+    /// orbits. Angles are wrapped to [0, 2π) except the (unbounded) mean
+    /// anomaly of open orbits. Radial trajectories (h = 0) are given the
+    /// reference plane as their orbital plane.
+    pub fn from_cartesian(position: Vec3, velocity: Vec3, mu: f64) -> Self {
+        let r = position.length();
+        let v2 = velocity.length_squared();
+        let rv = position.dot(velocity);
+
+        let h_vec = position.cross(velocity);
+        let h = h_vec.length();
+        let h_hat = if h > 0.0 { h_vec / h } else { Vec3::Z };
+
+        let e_vec = (position * (v2 - mu / r) - velocity * rv) / mu;
+        let e = e_vec.length();
+
+        let inclination = h_hat.z.clamp(-1.0, 1.0).acos();
+
+        // Line of nodes, or the reference x axis for equatorial orbits
+        let node = Vec3::Z.cross(h_hat);
+        let equatorial = node.length() < EQUATORIAL_TOLERANCE;
+        let node_hat = if equatorial { Vec3::X } else { node.normalize() };
+        let longitude_asc_node = if equatorial { 0.0 } else { wrap_two_pi(node_hat.y.atan2(node_hat.x)) };
+
+        // Periapsis direction, or the node for circular orbits
+        let circular = e < CIRCULAR_TOLERANCE;
+        let peri_hat = if circular { node_hat } else { e_vec / e };
+        let arg_periapsis = if circular {
+            0.0
+        } else {
+            wrap_two_pi(peri_hat.dot(h_hat.cross(node_hat)).atan2(peri_hat.dot(node_hat)))
+        };
+        let true_anomaly = position.dot(h_hat.cross(peri_hat)).atan2(position.dot(peri_hat));
+
+        let (semi_major_axis, mean_anomaly) = if (e - 1.0).abs() < PARABOLIC_TOLERANCE {
+            let d = (true_anomaly / 2.0).tan();
+            (h * h / (2.0 * mu), d + d * d * d / 3.0)
+        } else if e > 1.0 {
+            let hyp = 2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * (true_anomaly / 2.0).tan()).atanh();
+            (-mu / (v2 - 2.0 * mu / r), e * hyp.sinh() - hyp)
+        } else {
+            let ecc = 2.0 * ((1.0 - e).sqrt() * (true_anomaly / 2.0).sin())
+                .atan2((1.0 + e).sqrt() * (true_anomaly / 2.0).cos());
+            (-mu / (v2 - 2.0 * mu / r), wrap_two_pi(ecc - e * ecc.sin()))
+        };
+
+        Self {
+            semi_major_axis,
+            eccentricity: e,
+            inclination,
+            longitude_asc_node,
+            arg_periapsis,
+            mean_anomaly,
+        }
+    }
+
+    /// Periapsis distance q (meters), valid for every conic
+    pub fn periapsis_distance(&self) -> f64 {
+        if (self.eccentricity - 1.0).abs() < PARABOLIC_TOLERANCE {
+            self.semi_major_axis
+        } else {
+            self.semi_major_axis * (1.0 - self.eccentricity)
+        }
+    }
+
+    /// Whether the orbit is closed (elliptic)
+    pub fn is_bound(&self) -> bool {
+        self.eccentricity < 1.0 - PARABOLIC_TOLERANCE
+    }
+
     /// Compute orbital period from semi-major axis and central mass
     pub fn orbital_period(&self, mu: f64) -> f64 {
         2.0 * PI * (self.semi_major_axis.powi(3) / mu).sqrt()
     }
 }
 
+/// Wrap an angle to [0, 2π)
+fn wrap_two_pi(angle: f64) -> f64 {
+    let wrapped = angle.rem_euclid(2.0 * PI);
+    if wrapped >= 2.0 * PI { 0.0 } else { wrapped }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // J2000 CANONICAL ORBITAL DATA (JPL Horizons)
 // ═══════════════════════════════════════════════════════════════════════════
@@ -947,6 +1106,14 @@ pub fn create_jupiter_system(seed: u64) -> Simulation {
     jupiter.axial_tilt = 0.0546;
     jupiter.mean_surface_temperature = 165.0;
     jupiter.seed = seed.wrapping_add(0);
+    // Juno gravity field (Iess et al. 2018), rescaled from the 71,492 km
+    // reference radius to the mean radius used here
+    jupiter.j2 = 14_696.5e-6 * (7.1492e7_f64 / 6.9911e7).powi(2);
+    jupiter.j4 = -586.6e-6 * (7.1492e7_f64 / 6.9911e7).powi(4);
+    // Juno k2 (Durante et al. 2020); lag from k2/Q = 1.1e-5 at the Io
+    // tidal frequency 2(Ω − n) (Lainey et al. 2009)
+    jupiter.k2 = 0.565;
+    jupiter.tidal_time_lag = 0.072;
     jupiter.composition = PlanetComposition::GasGiant;
     jupiter.albedo = 0.503;
     jupiter.softening_length = compute_softening(6.9911e7);
@@ -972,6 +1139,10 @@ pub fn create_jupiter_system(seed: u64) -> Simulation {
     io.softening_length = compute_softening(1.8216e6);
     io.pole_ra = Some(268.05_f64.to_radians());
     io.pole_dec = Some(64.5_f64.to_radians());
+    // Juno k2 (Park et al. 2024); lag from k2/Q = 0.015 at the orbital
+    // frequency (Lainey et al. 2009), giving ~1e14 W of heating
+    io.k2 = 0.125;
+    io.tidal_time_lag = 2.9e3;
     io.compute_derived();
     sim.add_body(io);
     
@@ -1063,6 +1234,10 @@ pub fn create_saturn_system(seed: u64) -> Simulation {
     saturn.axial_tilt = 0.4665;
     saturn.mean_surface_temperature = 134.0;
     saturn.seed = seed.wrapping_add(0);
+    // Cassini Grand Finale gravity field (Iess et al. 2019), rescaled from
+    // the 60,330 km reference radius to the mean radius used here
+    saturn.j2 = 16_290.7e-6 * (6.033e7_f64 / 5.8232e7).powi(2);
+    saturn.j4 = -935.8e-6 * (6.033e7_f64 / 5.8232e7).powi(4);
     saturn.composition = PlanetComposition::GasGiant;
     saturn.albedo = 0.342;
     saturn.softening_length = compute_softening(5.8232e7);
@@ -1093,6 +1268,7 @@ pub fn create_saturn_system(seed: u64) -> Simulation {
         mie_direction: 0.76,
         height: 600_000.0,                            // ~600 km effective atmosphere
         mie_color: [0.85, 0.55, 0.2],                 // Tholin haze — deep orange-brown
+        surface_density: 5.4,                         // Huygens (Fulchignoni et al. 2005)
     });
     titan.pole_ra = Some(39.4827_f64.to_radians());
     titan.pole_dec = Some(83.4279_f64.to_radians());
@@ -1248,6 +1424,9 @@ pub fn create_trappist1(seed: u64) -> Simulation {
             p.color = hex_to_rgb(col);
             p.composition = PlanetComposition::Rocky;
             p.albedo = 0.3; // Earth-like estimate
+            // Earth's k2 and time lag (Neron de Surgy & Laskar 1997)
+            p.k2 = 0.305;
+            p.tidal_time_lag = 698.0;
             p.compute_derived();
         }
     }
@@ -1901,6 +2080,10 @@ pub fn create_full_solar_system_iii(seed: u64, barycentric: bool) -> Simulation
     io.softening_length = compute_softening(1.82149e6);
     io.pole_ra = Some(268.05_f64.to_radians());
     io.pole_dec = Some(64.5_f64.to_radians());
+    // Juno k2 (Park et al. 2024); lag from k2/Q = 0.015 at the orbital
+    // frequency (Lainey et al. 2009), giving ~1e14 W of heating
+    io.k2 = 0.125;
+    io.tidal_time_lag = 2.9e3;
     io.compute_derived();
     sim.add_body(io);
 
@@ -2130,6 +2313,7 @@ pub fn create_full_solar_system_iii(seed: u64, barycentric: bool) -> Simulation
         mie_direction: 0.76,
         height: 600_000.0,
         mie_color: [0.9, 0.7, 0.3],
+        surface_density: 5.4,
     });
     titan.softening_length = compute_softening(2.5755e6);
     titan.pole_ra = Some(39.4827_f64.to_radians());
@@ -2525,6 +2709,8 @@ pub fn create_full_solar_system_iii(seed: u64, barycentric: bool) -> Simulation
     halley.albedo = 0.04;
     halley.rotation_rate = 3.305e-5;
     halley.axial_tilt = 0.0 * deg;
+    // Non-gravitational parameters A1, A2 (Yeomans & Kiang 1981)
+    halley.nongrav_params = [0.027e-8 * AU_PER_DAY_SQUARED, 0.0155e-8 * AU_PER_DAY_SQUARED, 0.0];
     halley.softening_length = compute_softening(5.5e3);
     halley.compute_derived();
     sim.add_body(halley);
@@ -2990,6 +3176,9 @@ pub fn create_asteroid_belt(seed: u64, asteroid_count: usize) -> Simulation {
     
     // Procedurally generate asteroid belt
     let mut rng = Pcg32::new(seed.wrapping_add(1000));
+    // Spin states come from their own stream so the orbits do not change
+    let mut spin_rng = Pcg32::new(seed.wrapping_add(2000));
+    let (sun_position, sun_velocity) = (sim.bodies()[0].position, sim.bodies()[0].velocity);
     
     // Asteroid belt parameters
     let a_min = 2.1 * AU;  // Inner edge (Mars crossers excluded)
@@ -3074,6 +3263,17 @@ pub fn create_asteroid_belt(seed: u64, asteroid_count: usize) -> Simulation {
         asteroid.color = hex_to_rgb((gray << 16) | (gray << 8) | gray);
         asteroid.composition = PlanetComposition::Rocky;
         asteroid.albedo = 0.05 + rng.next_f64() * 0.15; // Low albedo (0.05-0.2)
+        // Spin periods log-uniform in 2-20 h, isotropic spin axes (for
+        // Yarkovsky and YORP); the tilt is the obliquity to the orbit normal
+        let spin_period = 2.0 * 10f64.powf(spin_rng.next_f64()) * 3600.0;
+        asteroid.rotation_rate = 2.0 * PI / spin_period;
+        let cos_theta = spin_rng.next_f64_range(-1.0, 1.0);
+        let phi = spin_rng.next_f64() * 2.0 * PI;
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let axis = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+        asteroid.set_spin_axis(axis);
+        let normal = (pos - sun_position).cross(vel - sun_velocity).normalize();
+        asteroid.axial_tilt = axis.dot(normal).clamp(-1.0, 1.0).acos();
         asteroid.softening_length = compute_softening(radius);
         asteroid.compute_derived();
         
@@ -3558,7 +3758,101 @@ mod tests {
         println!("  Earth velocity: {:.2} m/s", v);
         println!("  Earth period: {:.4} days", period_days);
     }
-    
+
+    fn assert_state_roundtrip(elements: OrbitalElements, mu: f64) {
+        let (pos, vel) = elements.to_cartesian(mu);
+        let recovered = OrbitalElements::from_cartesian(pos, vel, mu);
+        let (pos2, vel2) = recovered.to_cartesian(mu);
+
+        let pos_err = (pos2 - pos).length() / pos.length();
+        let vel_err = (vel2 - vel).length() / vel.length();
+        assert!(pos_err < 1e-9, "Position roundtrip error {:.3e} for {:?}", pos_err, elements);
+        assert!(vel_err < 1e-9, "Velocity roundtrip error {:.3e} for {:?}", vel_err, elements);
+    }
+
+    #[test]
+    fn test_cartesian_to_kepler_elliptic() {
+        let mu_sun = G * M_SUN;
+        for elements in [j2000::MERCURY, j2000::EARTH, j2000::PLUTO] {
+            let (pos, vel) = elements.to_cartesian(mu_sun);
+            let recovered = OrbitalElements::from_cartesian(pos, vel, mu_sun);
+
+            assert!((recovered.semi_major_axis / elements.semi_major_axis - 1.0).abs() < 1e-10);
+            assert!((recovered.eccentricity - elements.eccentricity).abs() < 1e-10);
+            assert!((recovered.inclination - elements.inclination).abs() < 1e-10);
+            assert!(wrap_two_pi(recovered.longitude_asc_node - elements.longitude_asc_node + 1e-6) < 2e-6);
+            assert!(wrap_two_pi(recovered.arg_periapsis - elements.arg_periapsis + 1e-6) < 2e-6);
+            assert!(recovered.is_bound());
+        }
+    }
+
+    #[test]
+    fn test_cartesian_to_kepler_open_orbits() {
+        let mu_sun = G * M_SUN;
+
+        // 'Oumuamua-like hyperbola, both before and after periapsis
+        for mean_anomaly in [-3.0, 0.0, 0.5, 20.0] {
+            let hyperbola = OrbitalElements {
+                semi_major_axis: -1.27 * AU,
+                eccentricity: 1.2011,
+                inclination: 2.1,
+                longitude_asc_node: 0.42,
+                arg_periapsis: 4.2,
+                mean_anomaly,
+            };
+            assert_state_roundtrip(hyperbola, mu_sun);
+            let (pos, vel) = hyperbola.to_cartesian(mu_sun);
+            let recovered = OrbitalElements::from_cartesian(pos, vel, mu_sun);
+            assert!(!recovered.is_bound());
+            assert!((recovered.semi_major_axis / hyperbola.semi_major_axis - 1.0).abs() < 1e-10);
+            assert!((recovered.mean_anomaly - mean_anomaly).abs() < 1e-8);
+        }
+
+        // Parabola from escape speed at perihelion
+        let q = 0.3 * AU;
+        let pos = Vec3::new(q, 0.0, 0.0);
+        let vel = Vec3::new(0.0, (2.0 * mu_sun / q).sqrt(), 0.0);
+        let parabola = OrbitalElements::from_cartesian(pos, vel, mu_sun);
+        assert!((parabola.eccentricity - 1.0).abs() < 1e-10);
+        assert!((parabola.periapsis_distance() / q - 1.0).abs() < 1e-12);
+        assert!(parabola.mean_anomaly.abs() < 1e-12);
+
+        let later = OrbitalElements { mean_anomaly: 2.5, ..parabola };
+        assert_state_roundtrip(later, mu_sun);
+    }
+
+    #[test]
+    fn test_cartesian_to_kepler_degenerate_geometry() {
+        let mu_earth = G * M_EARTH;
+        let r = R_EARTH + 4.0e5;
+        let v = (mu_earth / r).sqrt();
+        let angle: f64 = 1.1;
+        let pos = Vec3::new(r * angle.cos(), r * angle.sin(), 0.0);
+
+        // Circular, equatorial, prograde: everything lives in the mean anomaly
+        let prograde = OrbitalElements::from_cartesian(pos, Vec3::new(-v * angle.sin(), v * angle.cos(), 0.0), mu_earth);
+        assert!(prograde.eccentricity < 1e-10);
+        assert_eq!(prograde.longitude_asc_node, 0.0);
+        assert_eq!(prograde.arg_periapsis, 0.0);
+        assert!(prograde.inclination.abs() < 1e-12);
+        assert!((prograde.mean_anomaly - angle).abs() < 1e-9);
+        assert_state_roundtrip(prograde, mu_earth);
+
+        // Retrograde equatorial
+        let retrograde = OrbitalElements::from_cartesian(pos, Vec3::new(v * angle.sin(), -v * angle.cos(), 0.0), mu_earth);
+        assert!((retrograde.inclination - PI).abs() < 1e-12);
+        assert_state_roundtrip(retrograde, mu_earth);
+
+        // Eccentric but equatorial, and circular but inclined
+        let eccentric = OrbitalElements::from_degrees(2.0 * r, 0.3, 0.0, 0.0, 75.0, 200.0);
+        assert_state_roundtrip(eccentric, mu_earth);
+        let recovered = OrbitalElements::from_cartesian(eccentric.to_cartesian(mu_earth).0, eccentric.to_cartesian(mu_earth).1, mu_earth);
+        assert!((recovered.arg_periapsis - eccentric.arg_periapsis).abs() < 1e-9);
+
+        let inclined = OrbitalElements::from_degrees(r, 0.0, 51.6, 120.0, 0.0, 33.0);
+        assert_state_roundtrip(inclined, mu_earth);
+    }
+
     #[test]
     fn test_sun_earth_moon_positions() {
         let sim = create_sun_earth_moon(42);
@@ -0,0 +1,248 @@
+//! First post-Newtonian (1PN) corrections
+//!
+//! Two opt-in modes, selected by `ForceConfig::relativity`:
+//!
+//! - `Schwarzschild`: the test-particle correction around the dominant body,
+//!   a = GM/(c²r³) [(4GM/r − v²) r + 4 (r·v) v] in relative coordinates, with
+//!   the reaction applied to the dominant body. One pass over N bodies; right
+//!   for planets around a star.
+//! - `Eih`: the full Einstein-Infeld-Hoffmann pairwise terms in the form used
+//!   for the JPL ephemerides (Newhall, Standish & Williams 1983). Needed when
+//!   the masses are comparable, e.g. binary pulsars. O(N·M) for M massive
+//!   bodies.
+//!
+//! Both depend on velocities, and EIH also on the Newtonian accelerations,
+//! so they must run after the point-mass pass.
+//!
+//! Independently, `ForceConfig::radiation_reaction` enables the 2.5PN
+//! gravitational-wave radiation reaction between pairs of compact stars
+//! (neutron stars, black holes), scaled by the given factor so time-warped
+//! sessions can show an inspiral. It uses the harmonic-gauge relative
+//! acceleration (Iyer & Will 1995, α = 4, β = 5), whose orbit-averaged
+//! energy loss is the Peters-Mathews quadrupole formula.
+
+use crate::body::{Body, BodyType};
+use crate::constants::{C, G};
+use crate::vector::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Minimum compactness GM/(Rc²) of a star that radiates gravitational waves
+/// (neutron stars are ~0.2, white dwarfs ~1e-4)
+pub const COMPACTNESS_THRESHOLD: f64 = 0.05;
+
+/// Relativistic correction mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RelativityMode {
+    /// Newtonian gravity only
+    #[default]
+    None,
+    /// 1PN correction from the most massive body only
+    Schwarzschild,
+    /// Full 1PN Einstein-Infeld-Hoffmann pairwise terms
+    Eih,
+}
+
+impl RelativityMode {
+    /// Parse a mode name ("none", "schwarzschild", "eih")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Self::None),
+            "schwarzschild" => Some(Self::Schwarzschild),
+            "eih" => Some(Self::Eih),
+            _ => None,
+        }
+    }
+}
+
+/// Add the 1PN accelerations for `mode` on top of the Newtonian ones
+/// already stored in `bodies`
+pub fn apply_relativity(bodies: &mut [Body], mode: RelativityMode) {
+    match mode {
+        RelativityMode::None => {}
+        RelativityMode::Schwarzschild => apply_schwarzschild(bodies),
+        RelativityMode::Eih => apply_eih(bodies),
+    }
+}
+
+/// Test-particle 1PN acceleration at relative state (r, v) around GM = `mu`
+pub fn schwarzschild_acceleration(r: Vec3, v: Vec3, mu: f64) -> Vec3 {
+    let r2 = r.length_squared();
+    if r2 == 0.0 {
+        return Vec3::ZERO;
+    }
+    let dist = r2.sqrt();
+    let scale = mu / (C * C * r2 * dist);
+    (r * (4.0 * mu / dist - v.length_squared()) + v * (4.0 * r.dot(v))) * scale
+}
+
+fn apply_schwarzschild(bodies: &mut [Body]) {
+    let Some(central) = bodies
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.is_active && b.contributes_gravity && b.mass > 0.0)
+        .max_by(|(_, a), (_, b)| a.mass.total_cmp(&b.mass))
+        .map(|(i, _)| i)
+    else {
+        return;
+    };
+
+    let (center_pos, center_vel, center_mass) = (bodies[central].position, bodies[central].velocity, bodies[central].mass);
+    let mu = G * center_mass;
+    let mut reaction = Vec3::ZERO;
+    for (i, body) in bodies.iter_mut().enumerate() {
+        if i == central || !body.is_active || !body.feels_gravity {
+            continue;
+        }
+        let acc = schwarzschild_acceleration(body.position - center_pos, body.velocity - center_vel, mu);
+        body.acceleration += acc;
+        if body.contributes_gravity {
+            reaction -= acc * (body.mass / center_mass);
+        }
+    }
+    if bodies[central].feels_gravity {
+        bodies[central].acceleration += reaction;
+    }
+}
+
+fn apply_eih(bodies: &mut [Body]) {
+    let sources: Vec<usize> = (0..bodies.len())
+        .filter(|&j| bodies[j].is_active && bodies[j].contributes_gravity && bodies[j].mass > 0.0)
+        .collect();
+    if sources.is_empty() {
+        return;
+    }
+    let c2 = C * C;
+
+    // Newtonian potential Σ_k μ_k / r_ik at every body
+    let potential: Vec<f64> = bodies
+        .iter()
+        .enumerate()
+        .map(|(i, body)| {
+            sources
+                .iter()
+                .filter(|&&k| k != i)
+                .map(|&k| {
+                    let r = body.position.distance(bodies[k].position);
+                    if r > 0.0 { G * bodies[k].mass / r } else { 0.0 }
+                })
+                .sum()
+        })
+        .collect();
+
+    let corrections: Vec<Vec3> = bodies
+        .iter()
+        .enumerate()
+        .map(|(i, body)| {
+            if !body.is_active || !body.feels_gravity {
+                return Vec3::ZERO;
+            }
+            let (xi, vi) = (body.position, body.velocity);
+            let mut acc = Vec3::ZERO;
+            for &j in &sources {
+                if j == i {
+                    continue;
+                }
+                let source = &bodies[j];
+                let (xj, vj, aj) = (source.position, source.velocity, source.acceleration);
+                let rij = xj - xi;
+                let r = rij.length();
+                if r == 0.0 {
+                    continue;
+                }
+                let mu = G * source.mass;
+                let mu_r3 = mu / (r * r * r);
+                let radial_vj = (xi - xj).dot(vj) / r;
+
+                let bracket = -4.0 * potential[i] - potential[j]
+                    + vi.length_squared()
+                    + 2.0 * vj.length_squared()
+                    - 4.0 * vi.dot(vj)
+                    - 1.5 * radial_vj * radial_vj
+                    + 0.5 * rij.dot(aj);
+                acc += rij * (mu_r3 * bracket / c2);
+                acc += (vi - vj) * (mu_r3 * (xi - xj).dot(vi * 4.0 - vj * 3.0) / c2);
+                acc += aj * (3.5 * mu / (r * c2));
+            }
+            acc
+        })
+        .collect();
+
+    for (body, correction) in bodies.iter_mut().zip(corrections) {
+        body.acceleration += correction;
+    }
+}
+
+/// Whether `body` is a compact star that takes part in radiation reaction
+pub fn is_compact(body: &Body) -> bool {
+    body.is_active
+        && body.body_type == BodyType::Star
+        && body.mass > 0.0
+        && body.radius > 0.0
+        && G * body.mass / (body.radius * C * C) >= COMPACTNESS_THRESHOLD
+}
+
+/// 2.5PN radiation-reaction acceleration of the relative orbit r = x1 − x2,
+/// v = v1 − v2, for total GM = `mu` and symmetric mass ratio `eta`:
+/// a = 8/5 η μ²/(c⁵r³) [(3v² + 17/3 μ/r) ṙ r̂ − (v² + 3μ/r) v]
+pub fn radiation_reaction_acceleration(r: Vec3, v: Vec3, mu: f64, eta: f64) -> Vec3 {
+    let dist = r.length();
+    if dist == 0.0 {
+        return Vec3::ZERO;
+    }
+    let n = r / dist;
+    let v2 = v.length_squared();
+    let radial = n.dot(v);
+    let scale = 1.6 * eta * mu * mu / (C.powi(5) * dist.powi(3));
+    (n * ((3.0 * v2 + 17.0 / 3.0 * mu / dist) * radial) - v * (v2 + 3.0 * mu / dist)) * scale
+}
+
+/// Add radiation reaction, multiplied by `scale`, between every pair of
+/// compact stars
+pub fn apply_radiation_reaction(bodies: &mut [Body], scale: f64) {
+    if scale == 0.0 {
+        return;
+    }
+    let compact: Vec<usize> = (0..bodies.len()).filter(|&i| is_compact(&bodies[i])).collect();
+    for (k, &i) in compact.iter().enumerate() {
+        for &j in &compact[k + 1..] {
+            let (m1, m2) = (bodies[i].mass, bodies[j].mass);
+            let total = m1 + m2;
+            let acc = radiation_reaction_acceleration(
+                bodies[i].position - bodies[j].position,
+                bodies[i].velocity - bodies[j].velocity,
+                G * total,
+                m1 * m2 / (total * total),
+            ) * scale;
+            // Split the relative acceleration so momentum is conserved
+            if bodies[i].feels_gravity {
+                bodies[i].acceleration += acc * (m2 / total);
+            }
+            if bodies[j].feels_gravity {
+                bodies[j].acceleration -= acc * (m1 / total);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+
+    #[test]
+    fn test_eih_reduces_to_schwarzschild() {
+        // A massless particle around a lone star at rest
+        let star = Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO);
+        let particle = Body::new(
+            1, "Probe", BodyType::TestParticle, 0.0, 1.0,
+            Vec3::new(0.4 * AU, 0.1 * AU, 0.0),
+            Vec3::new(-1.0e4, 5.0e4, 3.0e3),
+        );
+        let mut bodies = vec![star, particle];
+        apply_relativity(&mut bodies, RelativityMode::Eih);
+
+        let expected = schwarzschild_acceleration(bodies[1].position, bodies[1].velocity, G * M_SUN);
+        assert!((bodies[1].acceleration - expected).length() < 1e-12 * expected.length());
+        assert_eq!(bodies[0].acceleration, Vec3::ZERO);
+    }
+}
@@ -0,0 +1,144 @@
+//! Ring particle systems
+//!
+//! `RingParameters` on its own only describes how a ring is drawn.
+//! `generate_ring_particles` fills the annulus between
+//! `inner_radius_mult` and `outer_radius_mult` planet radii with massless
+//! test particles on circular prograde orbits in the planet's equatorial
+//! plane (normal to `Body::spin_axis`, i.e. `pole_ra`/`pole_dec`). Radii are
+//! drawn for a uniform surface density and azimuths uniformly, from a
+//! `Pcg32` seeded with the planet's `seed`, so every peer builds the same
+//! ring.
+//!
+//! Orbital speeds include the planet's J2/J4 field, which speeds up the
+//! inner ring by a few percent for Saturn; without it the particles would
+//! start on eccentric orbits and the ring would blur radially. Structure
+//! then comes only from the moons: resonances and shepherding show up as
+//! gaps and edges as the ring evolves.
+//!
+//! Test particles feel gravity but exert none, so the direct solver only
+//! sums over the gravity sources and thousands of particles stay cheap.
+
+use crate::body::{Body, BodyType};
+use crate::constants::G;
+use crate::harmonics::ZonalField;
+use crate::prng::Pcg32;
+use crate::vector::Vec3;
+
+/// PCG stream for ring generation, independent of other uses of the seed
+const RING_STREAM: u64 = 0x5249_4e47;
+
+/// Unit vector along the ascending node of the equator of a body with
+/// spin axis `pole` (+x when the equator is the xy plane)
+fn equatorial_node(pole: Vec3) -> Vec3 {
+    let node = Vec3::Z.cross(pole);
+    if node.length_squared() > 1e-12 { node.normalize() } else { Vec3::X }
+}
+
+/// Speed of a circular orbit of `radius` in the equatorial plane of `planet`
+pub fn circular_speed(planet: &Body, radius: f64) -> f64 {
+    let mut pull = G * planet.mass / (radius * radius);
+    if let Some(field) = ZonalField::of(planet) {
+        // In the equatorial plane the J2/J4 terms are purely radial
+        let offset = equatorial_node(planet.spin_axis()) * radius;
+        pull -= field.acceleration(offset).dot(offset) / radius;
+    }
+    (pull * radius).sqrt()
+}
+
+/// Ring particles around `planet`, or none when it has no rings. The
+/// particles have id 0 and `parent_id` set to the planet, ready for
+/// `Simulation::add_body`.
+pub fn generate_ring_particles(planet: &Body, count: u32) -> Vec<Body> {
+    let Some(rings) = &planet.rings else {
+        return Vec::new();
+    };
+    let inner = rings.inner_radius_mult * planet.radius;
+    let outer = rings.outer_radius_mult * planet.radius;
+    if planet.mass <= 0.0 || !(inner > planet.radius && outer >= inner) {
+        return Vec::new();
+    }
+
+    let pole = planet.spin_axis();
+    let x = equatorial_node(pole);
+    let y = pole.cross(x);
+    let mut rng = Pcg32::with_stream(planet.seed, RING_STREAM);
+    (0..count)
+        .map(|k| {
+            let radius = rng.next_f64_range(inner * inner, outer * outer).sqrt();
+            let (sin, cos) = rng.next_f64_range(0.0, std::f64::consts::TAU).sin_cos();
+            let radial = x * cos + y * sin;
+            let speed = circular_speed(planet, radius);
+            let mut particle = Body::new(
+                0,
+                format!("{} ring {}", planet.name, k + 1),
+                BodyType::TestParticle,
+                0.0,
+                0.0,
+                planet.position + radial * radius,
+                planet.velocity + pole.cross(radial) * speed,
+            );
+            particle.parent_id = Some(planet.id);
+            particle.seed = rng.next_u64();
+            particle
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::RingParameters;
+
+    fn saturn() -> Body {
+        let mut saturn = Body::new(4, "Saturn", BodyType::Planet, 5.6834e26, 5.8232e7, Vec3::new(1.4e12, 0.0, 0.0), Vec3::new(0.0, 9.6e3, 0.0));
+        saturn.j2 = 0.0175;
+        saturn.j4 = -0.001;
+        saturn.pole_ra = Some(40.59_f64.to_radians());
+        saturn.pole_dec = Some(83.54_f64.to_radians());
+        saturn.rings = Some(RingParameters {
+            inner_radius_mult: 1.11,
+            outer_radius_mult: 2.3,
+            texture_preset: "saturn".to_string(),
+            base_opacity: 0.9,
+        });
+        saturn
+    }
+
+    #[test]
+    fn test_particles_fill_equatorial_annulus() {
+        let saturn = saturn();
+        let pole = saturn.spin_axis();
+        let particles = generate_ring_particles(&saturn, 500);
+        assert_eq!(particles.len(), 500);
+        for p in &particles {
+            let r = p.position - saturn.position;
+            let v = p.velocity - saturn.velocity;
+            assert!(r.length() >= 1.11 * saturn.radius && r.length() <= 2.3 * saturn.radius);
+            assert!(r.dot(pole).abs() < 1e-6 * r.length());
+            assert!(v.dot(r).abs() < 1e-9 * v.length() * r.length());
+            assert!(r.cross(v).normalize().dot(pole) > 1.0 - 1e-12, "orbits must be prograde");
+            assert!(!p.contributes_gravity && p.parent_id == Some(4));
+        }
+
+        let again = generate_ring_particles(&saturn, 500);
+        assert!(particles.iter().zip(&again).all(|(a, b)| a.position == b.position));
+
+        let mut bare = saturn.clone();
+        bare.rings = None;
+        assert!(generate_ring_particles(&bare, 500).is_empty());
+    }
+
+    #[test]
+    fn test_oblateness_speeds_up_inner_ring() {
+        let mut saturn = saturn();
+        let r = 1.2 * saturn.radius;
+        let kepler = (G * saturn.mass / r).sqrt();
+        // n² = GM/r³ [1 + 3/2 J2 (R/r)² − 15/8 J4 (R/r)⁴]
+        let s2 = (saturn.radius / r).powi(2);
+        let expected = kepler * (1.0 + 1.5 * saturn.j2 * s2 - 15.0 / 8.0 * saturn.j4 * s2 * s2).sqrt();
+        assert!((circular_speed(&saturn, r) / expected - 1.0).abs() < 1e-12);
+        saturn.j2 = 0.0;
+        saturn.j4 = 0.0;
+        assert_eq!(circular_speed(&saturn, r), kepler);
+    }
+}
@@ -0,0 +1,122 @@
+//! Rotation state: orientation quaternions and spin-axis precession
+//!
+//! Every body carries an `orientation` quaternion (body frame to simulation
+//! frame, body z along the spin axis). Each step turns it by
+//! `rotation_rate · dt` about `spin_axis()`, so every client that steps the
+//! same simulation sees the same rotation phase. The spin state itself
+//! stays in `rotation_rate` and `pole_ra`/`pole_dec`, which tides and YORP
+//! also evolve. `orientation` follows any change of axis with the shortest
+//! turn.
+//!
+//! With `ForceConfig::precession`, oblate bodies (J2 > 0) feel the torque
+//! of every other mass m on their equatorial bulge (MacCullagh's formula)
+//!
+//!   τ = 3 G m / r⁵ · r × (I r)
+//!
+//! with inertia tensor I = diag(A, A, C) in the body frame,
+//! C = `tides::moment_of_inertia` and C − A = J2 M R². The spin is treated
+//! as gyroscopic (angular momentum C ω along the axis), so the torque turns
+//! the axis and the axis is written back to `pole_ra`/`pole_dec`. J4 and
+//! the body's own spin-orbit nutation terms are ignored. The matching
+//! orbital reaction is the J2 force from `harmonics`, which is only applied
+//! within `ZONAL_RANGE_RADII`; beyond that the reaction on the orbit is
+//! negligible and the torque alone is kept.
+
+use crate::body::Body;
+use crate::constants::G;
+use crate::tides::moment_of_inertia;
+use crate::vector::{Mat3, Quat, Vec3};
+
+/// Inertia tensor of an axisymmetric oblate body in the simulation frame
+pub fn inertia_tensor(body: &Body) -> Mat3 {
+    let polar = moment_of_inertia(body);
+    let equatorial = polar - body.j2 * body.mass * body.radius * body.radius;
+    let rotation = body.orientation.to_mat3();
+    rotation * Mat3::from_diagonal(Vec3::new(equatorial, equatorial, polar)) * rotation.transpose()
+}
+
+/// Whether `body` feels a precessional torque
+pub fn precesses(body: &Body) -> bool {
+    body.is_active && body.j2 > 0.0 && body.mass > 0.0 && body.radius > 0.0 && body.rotation_rate != 0.0
+}
+
+/// Torque on the bulge of body `index` from all other massive bodies
+pub fn oblateness_torque(bodies: &[Body], index: usize) -> Vec3 {
+    let body = &bodies[index];
+    let inertia = inertia_tensor(body);
+    let mut torque = Vec3::ZERO;
+    for (j, other) in bodies.iter().enumerate() {
+        if j == index || !other.is_active || !other.contributes_gravity || other.mass <= 0.0 {
+            continue;
+        }
+        let r = other.position - body.position;
+        let r2 = r.length_squared();
+        if r2 <= body.radius * body.radius {
+            continue;
+        }
+        torque += r.cross(inertia * r) * (3.0 * G * other.mass / (r2 * r2 * r2.sqrt()));
+    }
+    torque
+}
+
+/// Advance every body's orientation by `dt`, first turning the spin axes of
+/// oblate bodies under their torques when `precession` is set
+pub fn advance_rotation(bodies: &mut [Body], dt: f64, precession: bool) {
+    if precession {
+        let torques: Vec<(usize, Vec3)> = (0..bodies.len())
+            .filter(|&i| precesses(&bodies[i]))
+            .map(|i| (i, oblateness_torque(bodies, i)))
+            .collect();
+        for (i, torque) in torques {
+            let body = &mut bodies[i];
+            let momentum = body.spin_axis() * (moment_of_inertia(body) * body.rotation_rate) + torque * dt;
+            // A retrograde spin keeps its axis and negative rate
+            body.set_spin_axis(momentum * body.rotation_rate.signum());
+        }
+    }
+
+    for body in bodies.iter_mut().filter(|b| b.is_active) {
+        body.align_orientation();
+        if body.rotation_rate != 0.0 {
+            let turn = Quat::from_axis_angle(body.spin_axis(), body.rotation_rate * dt);
+            body.orientation = (turn * body.orientation).normalize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BodyType;
+    use crate::constants::*;
+
+    #[test]
+    fn test_orientation_accumulates_rotation_phase() {
+        let mut planet = Body::new(0, "Planet", BodyType::Planet, M_EARTH, R_EARTH, Vec3::ZERO, Vec3::ZERO);
+        planet.rotation_rate = -OMEGA_EARTH;
+        planet.pole_ra = Some(0.0);
+        planet.pole_dec = Some(std::f64::consts::FRAC_PI_2);
+        planet.compute_derived();
+        let axis = planet.spin_axis();
+        assert!((planet.orientation.rotate(Vec3::Z) - axis).length() < 1e-12);
+        let start = planet.orientation.rotate(Vec3::X);
+
+        // A quarter turn, retrograde about the axis
+        let mut bodies = vec![planet];
+        let quarter = std::f64::consts::FRAC_PI_2 / OMEGA_EARTH;
+        for _ in 0..100 {
+            advance_rotation(&mut bodies, quarter / 100.0, false);
+        }
+        let end = bodies[0].orientation.rotate(Vec3::X);
+        assert!((end - axis.cross(start) * -1.0).length() < 1e-9);
+        assert!((bodies[0].angular_velocity - axis * -OMEGA_EARTH).length() < 1e-18);
+    }
+
+    #[test]
+    fn test_spin_axis_round_trip() {
+        let mut body = Body::new(0, "Body", BodyType::Planet, 1.0, 1.0, Vec3::ZERO, Vec3::ZERO);
+        let axis = Vec3::new(0.3, -0.5, 0.8).normalize();
+        body.set_spin_axis(axis);
+        assert!((body.spin_axis() - axis).length() < 1e-14);
+    }
+}
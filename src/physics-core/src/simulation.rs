@@ -3,27 +3,41 @@
 //! Combines all physics components into a single simulation that can be
 //! advanced by step, checkpointed, and serialized.
 
-use crate::body::{Body, BodyId};
+use crate::body::{Body, BodyId, BodyType};
+use crate::collision::{
+    bounce_bodies, detect_collisions, merge_bodies, survives, CollisionConfig, CollisionEvent,
+    CollisionOutcome,
+};
 use crate::constants::G;
+use crate::fragmentation::fragment_collision;
 use crate::force::{
     compute_accelerations_direct, compute_angular_momentum, compute_center_of_mass,
     compute_kinetic_energy, compute_potential_energy,
     compute_total_momentum,
 };
 use crate::integrator::{
+    adaptive_substep_count,
     step_with_accel,
     AccelerationFn,
     CloseEncounterConfig,
     CloseEncounterIntegrator,
     CloseEncounterTrialResult,
     IntegratorConfig,
+    IntegratorType,
     initialize_accelerations_with,
     trial_integrate_subset_gauss_radau,
     trial_integrate_subset_rk45,
 };
+use crate::fmm::{compute_accelerations_fmm, MAX_FMM_ORDER};
+use crate::disruption::{find_disruptions, fragment_body, DisruptionConfig, DisruptionEvent};
+use crate::mass_loss::{apply_mass_loss, MassLossMode};
 use crate::octree::compute_accelerations_barnes_hut;
+use crate::presets::OrbitalElements;
 use crate::prng::Pcg32;
-use crate::snapshot::{CloseEncounterEvent, Snapshot, SnapshotMetadata};
+use crate::relativity::RelativityMode;
+use crate::rings::generate_ring_particles;
+use crate::thrust::{apply_delta_v, Burn, ThrustFrame};
+use crate::snapshot::{CloseEncounterEvent, DeltaError, DeltaSnapshot, Snapshot, SnapshotMetadata};
 use crate::vector::Vec3;
 
 /// Force calculation method
@@ -33,6 +47,8 @@ pub enum ForceMethod {
     Direct,
     /// Barnes-Hut O(N log N) approximation - faster for large N
     BarnesHut,
+    /// Fast Multipole Method O(N) - fastest for very large N
+    Fmm,
 }
 
 impl Default for ForceMethod {
@@ -51,8 +67,20 @@ pub struct SimulationConfig {
     /// Force calculation method
     pub force_method: ForceMethod,
     
-    /// Threshold for auto-switching to Barnes-Hut
+    /// Number of gravity sources above which Direct auto-switches to
+    /// Barnes-Hut (an explicit BarnesHut or Fmm choice is kept as-is).
+    /// Massless test particles don't count: direct summation over a few
+    /// sources stays cheaper and exact however many particles feel them.
     pub barnes_hut_threshold: usize,
+
+    /// Collision detection and response
+    pub collision: CollisionConfig,
+
+    /// Mass loss of bodies with a `mass_loss_rate`
+    pub mass_loss: MassLossMode,
+
+    /// Tidal disruption of bodies inside a Roche limit
+    pub disruption: DisruptionConfig,
 }
 
 impl Default for SimulationConfig {
@@ -61,6 +89,9 @@ impl Default for SimulationConfig {
             integrator: IntegratorConfig::default(),
             force_method: ForceMethod::Direct,
             barnes_hut_threshold: 10000,
+            collision: CollisionConfig::default(),
+            mass_loss: MassLossMode::default(),
+            disruption: DisruptionConfig::default(),
         }
     }
 }
@@ -97,6 +128,18 @@ pub struct Simulation {
 
     /// Last close-encounter body ids (for exit logging)
     close_encounter_last_body_ids: Vec<u32>,
+
+    /// Collision events (recent)
+    collision_events: Vec<CollisionEvent>,
+
+    /// Collision event ID counter
+    collision_event_id: u64,
+
+    /// Tidal disruption events (recent)
+    disruption_events: Vec<DisruptionEvent>,
+
+    /// Disruption event ID counter
+    disruption_event_id: u64,
     
     /// Next body ID to assign
     next_id: BodyId,
@@ -106,6 +149,15 @@ pub struct Simulation {
     
     /// Cached potential energy from the last force evaluation
     cached_potential_energy: Option<f64>,
+
+    /// Substeps used by the most recent tick
+    last_substeps: u32,
+
+    /// Mass lost to stellar winds since creation or the last restore
+    mass_lost: f64,
+
+    /// Mechanical energy carried away with that mass
+    mass_loss_energy: f64,
 }
 
 impl Simulation {
@@ -122,9 +174,16 @@ impl Simulation {
             close_encounter_event_id: 1,
             close_encounter_active: false,
             close_encounter_last_body_ids: Vec::new(),
+            collision_events: Vec::new(),
+            collision_event_id: 1,
+            disruption_events: Vec::new(),
+            disruption_event_id: 1,
             next_id: 0,
             needs_init: true,
             cached_potential_energy: None,
+            last_substeps: 0,
+            mass_lost: 0.0,
+            mass_loss_energy: 0.0,
         }
     }
 
@@ -181,6 +240,17 @@ impl Simulation {
         Some(self.add_body(body))
     }
 
+    /// Fill the rings of body `id` with `count` massless test particles on
+    /// circular equatorial orbits. Returns the particle ids (none when the
+    /// body has no rings).
+    pub fn add_ring_particles(&mut self, id: BodyId, count: u32) -> Vec<BodyId> {
+        let Some(planet) = self.get_body(id).filter(|b| b.is_active) else {
+            return Vec::new();
+        };
+        let particles = generate_ring_particles(planet, count);
+        particles.into_iter().map(|p| self.add_body(p)).collect()
+    }
+
     /// Get a reference to a body by ID
     pub fn get_body(&self, id: BodyId) -> Option<&Body> {
         self.bodies.iter().find(|b| b.id == id)
@@ -201,6 +271,54 @@ impl Simulation {
         }
     }
 
+    /// Give a body propellant (kg, counted in its mass) and an engine
+    /// exhaust velocity (m/s) for `thrust`'s rocket equation
+    pub fn set_propulsion(&mut self, id: BodyId, propellant_mass: f64, exhaust_velocity: f64) -> bool {
+        let Some(body) = self.get_body_mut(id) else {
+            return false;
+        };
+        body.propellant_mass = propellant_mass.clamp(0.0, body.mass.max(0.0));
+        body.exhaust_velocity = exhaust_velocity.max(0.0);
+        true
+    }
+
+    /// Queue a constant-thrust burn (N, in `frame`) from simulation time
+    /// `start` for `duration` seconds. A start in the past fires the rest of
+    /// the window from now. Returns false for an unknown body or a window
+    /// that is already over.
+    pub fn schedule_burn(&mut self, id: BodyId, start: f64, duration: f64, thrust: Vec3, frame: ThrustFrame) -> bool {
+        let delay = start - self.time;
+        let duration = if delay < 0.0 { duration + delay } else { duration };
+        if duration.is_nan() || duration <= 0.0 {
+            return false;
+        }
+        let Some(body) = self.get_body_mut(id) else {
+            return false;
+        };
+        body.burns.push(Burn { delay: delay.max(0.0), duration, thrust, frame });
+        true
+    }
+
+    /// Drop every burn queued on a body
+    pub fn cancel_burns(&mut self, id: BodyId) -> bool {
+        let Some(body) = self.get_body_mut(id) else {
+            return false;
+        };
+        body.burns.clear();
+        true
+    }
+
+    /// Apply an impulsive Δv (m/s, in `frame`) to a body now.
+    /// Returns the Δv applied in simulation axes, which falls short of the
+    /// request when the propellant runs out.
+    pub fn apply_delta_v(&mut self, id: BodyId, delta_v: Vec3, frame: ThrustFrame) -> Option<Vec3> {
+        let index = self.bodies.iter().position(|b| b.id == id && b.is_active)?;
+        let applied = apply_delta_v(&mut self.bodies, index, delta_v, frame);
+        // Velocity-dependent forces and the Verlet bookkeeping see the new velocity
+        self.needs_init = true;
+        Some(applied)
+    }
+
     /// Get all bodies
     pub fn bodies(&self) -> &[Body] {
         &self.bodies
@@ -242,8 +360,71 @@ impl Simulation {
         self.tick
     }
 
+    /// Substeps used by the most recent tick (0 before the first step)
+    pub fn last_substeps(&self) -> u32 {
+        self.last_substeps
+    }
+
     /// Advance simulation by one tick
     pub fn step(&mut self) {
+        let start_positions: Option<Vec<Vec3>> = self
+            .config
+            .collision
+            .enabled
+            .then(|| self.bodies.iter().map(|b| b.position).collect());
+
+        self.advance();
+
+        if let Some(start_positions) = start_positions {
+            self.resolve_collisions(&start_positions);
+        }
+
+        if self.config.disruption.enabled {
+            self.disrupt_bodies();
+        }
+
+        self.lose_mass();
+    }
+
+    /// Replace every body inside a Roche limit with its fragments
+    fn disrupt_bodies(&mut self) {
+        for (index, primary, distance, roche_limit) in find_disruptions(&self.bodies) {
+            let fragments = fragment_body(&self.bodies[index], self.config.disruption.fragments);
+            self.bodies[index].is_active = false;
+            let fragment_ids = fragments.into_iter().map(|f| self.add_body(f)).collect();
+            let event = DisruptionEvent {
+                id: self.disruption_event_id,
+                time: self.time,
+                body_id: self.bodies[index].id,
+                primary_id: self.bodies[primary].id,
+                fragment_ids,
+                distance,
+                roche_limit,
+            };
+            self.disruption_event_id += 1;
+            self.disruption_events.push(event);
+            if self.disruption_events.len() > 256 {
+                self.disruption_events.remove(0);
+            }
+            self.cached_potential_energy = None;
+        }
+    }
+
+    /// Remove one tick of stellar wind; accelerations and the potential
+    /// energy are recomputed for the new masses
+    fn lose_mass(&mut self) {
+        let softening = self.config.integrator.force_config.softening;
+        let (lost, energy) = apply_mass_loss(&mut self.bodies, self.config.integrator.dt, self.config.mass_loss, softening);
+        if lost > 0.0 {
+            self.mass_lost += lost;
+            self.mass_loss_energy += energy;
+            self.needs_init = true;
+            self.cached_potential_energy = None;
+        }
+    }
+
+    /// Integrate one tick, switching to the close-encounter integrator when needed
+    fn advance(&mut self) {
         let accel_fn = self.resolve_accel_fn();
         let dt = self.config.integrator.dt;
         let close_cfg = self.config.integrator.close_encounter;
@@ -253,6 +434,17 @@ impl Simulation {
             self.needs_init = false;
         }
 
+        let mut integrator = self.config.integrator;
+        if integrator.adaptive.enabled {
+            integrator.substeps = adaptive_substep_count(&self.bodies, dt, &integrator.adaptive, &integrator.force_config);
+        }
+        self.last_substeps = integrator.substeps;
+
+        if close_cfg.enabled && close_cfg.integrator != CloseEncounterIntegrator::None {
+            // The Hill-radius estimate reads semi_major_axis; keep it current
+            self.update_orbital_elements();
+        }
+
         let (subset, reason) = self.detect_close_encounter_subset(&close_cfg);
 
         if subset.is_empty() || !close_cfg.enabled || close_cfg.integrator == CloseEncounterIntegrator::None {
@@ -270,7 +462,7 @@ impl Simulation {
                 self.close_encounter_last_body_ids.clear();
             }
             // Advance physics normally
-            self.cached_potential_energy = Some(step_with_accel(&mut self.bodies, &self.config.integrator, accel_fn));
+            self.cached_potential_energy = Some(step_with_accel(&mut self.bodies, &integrator, accel_fn));
             self.time += dt;
             self.tick += 1;
             self.sequence += 1;
@@ -282,7 +474,7 @@ impl Simulation {
         let pre_velocities: Vec<Vec3> = self.bodies.iter().map(|b| b.velocity).collect();
 
         // Baseline step for all bodies (Velocity-Verlet / configured integrator)
-        self.cached_potential_energy = Some(step_with_accel(&mut self.bodies, &self.config.integrator, accel_fn));
+        self.cached_potential_energy = Some(step_with_accel(&mut self.bodies, &integrator, accel_fn));
         self.time += dt;
         self.tick += 1;
         self.sequence += 1;
@@ -386,6 +578,80 @@ impl Simulation {
         }
     }
 
+    /// Detect and resolve collisions swept over the tick just taken.
+    fn resolve_collisions(&mut self, start_positions: &[Vec3]) {
+        let config = self.config.collision;
+        let contacts = detect_collisions(&self.bodies, start_positions, &config);
+        if contacts.is_empty() {
+            return;
+        }
+
+        let dt = self.config.integrator.dt;
+        for contact in contacts {
+            let (head, tail) = self.bodies.split_at_mut(contact.j);
+            let (a, b) = (&mut head[contact.i], &mut tail[0]);
+            if !a.is_active || !b.is_active {
+                continue;
+            }
+
+            // Rewind both bodies to the moment of first contact
+            let remaining = (1.0 - contact.fraction) * dt;
+            a.position = start_positions[contact.i].lerp(a.position, contact.fraction);
+            b.position = start_positions[contact.j].lerp(b.position, contact.fraction);
+            let relative_speed = (b.velocity - a.velocity).length();
+
+            let outcome = config.pair_outcome(a.body_type, b.body_type);
+            let (body_ids, name, fragments) = match outcome {
+                CollisionOutcome::Merge => {
+                    let (survivor, absorbed) = if survives(a, b) { (a, b) } else { (b, a) };
+                    merge_bodies(survivor, absorbed);
+                    survivor.position += survivor.velocity * remaining;
+                    (vec![survivor.id, absorbed.id], outcome.name(), Vec::new())
+                }
+                CollisionOutcome::Fragment => {
+                    let (target, projectile) = if survives(a, b) { (a, b) } else { (b, a) };
+                    let body_ids = vec![target.id, projectile.id];
+                    let (regime, mut fragments) = fragment_collision(target, projectile, config.debris);
+                    for body in [target, projectile].into_iter().filter(|body| body.is_active) {
+                        body.position += body.velocity * remaining;
+                    }
+                    for fragment in &mut fragments {
+                        fragment.position += fragment.velocity * remaining;
+                    }
+                    (body_ids, regime.name(), fragments)
+                }
+                CollisionOutcome::Bounce => {
+                    bounce_bodies(a, b, config.restitution);
+                    a.position += a.velocity * remaining;
+                    b.position += b.velocity * remaining;
+                    (vec![a.id, b.id], outcome.name(), Vec::new())
+                }
+                CollisionOutcome::Ignore => continue,
+            };
+
+            let fragment_ids = fragments.into_iter().map(|f| self.add_body(f)).collect();
+            self.log_collision_event(self.time - remaining, body_ids, name, relative_speed, fragment_ids);
+            self.needs_init = true;
+        }
+    }
+
+    fn log_collision_event(&mut self, time: f64, body_ids: Vec<u32>, outcome: &str, relative_speed: f64, fragment_ids: Vec<u32>) {
+        let event = CollisionEvent {
+            id: self.collision_event_id,
+            time,
+            body_ids,
+            outcome: outcome.to_string(),
+            relative_speed,
+            fragment_ids,
+        };
+
+        self.collision_event_id += 1;
+        self.collision_events.push(event);
+        if self.collision_events.len() > 256 {
+            self.collision_events.remove(0);
+        }
+    }
+
     fn detect_close_encounter_subset(&self, cfg: &CloseEncounterConfig) -> (Vec<usize>, String) {
         if !cfg.enabled || cfg.integrator == CloseEncounterIntegrator::None {
             return (Vec::new(), String::new());
@@ -519,8 +785,15 @@ impl Simulation {
         }
     }
 
+    /// Active bodies that contribute gravity
+    fn gravity_source_count(&self) -> usize {
+        self.bodies.iter().filter(|b| b.is_active && b.contributes_gravity).count()
+    }
+
     fn resolve_force_method(&self) -> ForceMethod {
-        if self.bodies.len() > self.config.barnes_hut_threshold {
+        if self.config.force_method == ForceMethod::Direct
+            && self.gravity_source_count() > self.config.barnes_hut_threshold
+        {
             ForceMethod::BarnesHut
         } else {
             self.config.force_method
@@ -531,6 +804,108 @@ impl Simulation {
         match self.resolve_force_method() {
             ForceMethod::Direct => compute_accelerations_direct,
             ForceMethod::BarnesHut => compute_accelerations_barnes_hut,
+            ForceMethod::Fmm => compute_accelerations_fmm,
+        }
+    }
+
+    /// Index of the primary each body orbits, if any.
+    ///
+    /// An active `parent_id` wins. Otherwise the primary is the heavier body
+    /// with the smallest Hill sphere that contains this one, where each
+    /// candidate's Hill sphere is measured against the heavier body pulling
+    /// on it hardest (the top of the hierarchy has an unbounded sphere).
+    fn primary_indices(&self) -> Vec<Option<usize>> {
+        let attractors: Vec<usize> = (0..self.bodies.len())
+            .filter(|&k| {
+                let b = &self.bodies[k];
+                b.is_active && b.contributes_gravity && b.mass > 0.0
+            })
+            .collect();
+
+        let hill: Vec<f64> = attractors
+            .iter()
+            .map(|&c| {
+                let bc = &self.bodies[c];
+                let mut strongest: Option<(f64, f64, f64)> = None; // (pull, mass, distance)
+                for &p in &attractors {
+                    let bp = &self.bodies[p];
+                    if p == c || bp.mass <= bc.mass {
+                        continue;
+                    }
+                    let d = bc.position.distance(bp.position);
+                    let pull = bp.mass / (d * d);
+                    if strongest.is_none_or(|(best, _, _)| pull > best) {
+                        strongest = Some((pull, bp.mass, d));
+                    }
+                }
+                match strongest {
+                    Some((_, m, d)) => hill_radius_estimate(bc.mass, m, d),
+                    None => f64::INFINITY,
+                }
+            })
+            .collect();
+
+        self.bodies
+            .iter()
+            .enumerate()
+            .map(|(i, body)| {
+                if !body.is_active {
+                    return None;
+                }
+                if let Some(parent) = body.parent_id {
+                    if let Some(k) = self.bodies.iter().position(|b| b.id == parent && b.is_active) {
+                        if k != i {
+                            return Some(k);
+                        }
+                    }
+                }
+                attractors
+                    .iter()
+                    .zip(&hill)
+                    .filter(|&(&c, &radius)| {
+                        let bc = &self.bodies[c];
+                        c != i && bc.mass > body.mass && body.position.distance(bc.position) < radius
+                    })
+                    .min_by(|a, b| a.1.total_cmp(b.1))
+                    .map(|(&c, _)| c)
+            })
+            .collect()
+    }
+
+    /// Osculating two-body elements of `body` relative to `primary`
+    fn relative_elements(&self, body: usize, primary: usize) -> OrbitalElements {
+        let (b, p) = (&self.bodies[body], &self.bodies[primary]);
+        OrbitalElements::from_cartesian(
+            b.position - p.position,
+            b.velocity - p.velocity,
+            G * (p.mass + b.mass),
+        )
+    }
+
+    /// Current osculating elements of a body relative to its primary
+    /// (`parent_id`, or the dominant attractor by Hill-sphere hierarchy).
+    /// Returns the primary's id along with the elements, or None for
+    /// unknown/inactive bodies and the top of the hierarchy.
+    pub fn osculating_elements(&self, id: BodyId) -> Option<(BodyId, OrbitalElements)> {
+        let index = self.bodies.iter().position(|b| b.id == id)?;
+        let primary = self.primary_indices()[index]?;
+        Some((self.bodies[primary].id, self.relative_elements(index, primary)))
+    }
+
+    /// Refresh the orbital element fields stored on every body that has a
+    /// primary, so they describe the current osculating orbit.
+    pub fn update_orbital_elements(&mut self) {
+        let primaries = self.primary_indices();
+        for (i, primary) in primaries.into_iter().enumerate() {
+            let Some(p) = primary else { continue };
+            let elements = self.relative_elements(i, p);
+            let body = &mut self.bodies[i];
+            body.semi_major_axis = elements.semi_major_axis;
+            body.eccentricity = elements.eccentricity;
+            body.inclination = elements.inclination;
+            body.longitude_asc_node = elements.longitude_asc_node;
+            body.arg_periapsis = elements.arg_periapsis;
+            body.mean_anomaly = elements.mean_anomaly;
         }
     }
 
@@ -551,6 +926,17 @@ impl Simulation {
         })
     }
 
+    /// Get the mechanical energy carried away by stellar winds; with mass
+    /// loss the sum of this and `total_energy()` is what is conserved
+    pub fn mass_loss_energy(&self) -> f64 {
+        self.mass_loss_energy
+    }
+
+    /// Get the total mass lost to stellar winds
+    pub fn mass_lost(&self) -> f64 {
+        self.mass_lost
+    }
+
     /// Get total linear momentum as (px, py, pz)
     pub fn total_momentum(&self) -> crate::vector::Vec3 {
         compute_total_momentum(&self.bodies)
@@ -577,11 +963,20 @@ impl Simulation {
             self.bodies.clone(),
             &self.config.integrator.force_config,
             &self.config.integrator,
-        );
+        )
+        .with_simulation_config(&self.config);
 
-        if !self.close_encounter_events.is_empty() {
+        if !self.close_encounter_events.is_empty() || !self.collision_events.is_empty() || !self.disruption_events.is_empty() {
             let mut metadata = SnapshotMetadata::default();
-            metadata.close_encounter_events = Some(self.close_encounter_events.clone());
+            if !self.close_encounter_events.is_empty() {
+                metadata.close_encounter_events = Some(self.close_encounter_events.clone());
+            }
+            if !self.collision_events.is_empty() {
+                metadata.collision_events = Some(self.collision_events.clone());
+            }
+            if !self.disruption_events.is_empty() {
+                metadata.disruption_events = Some(self.disruption_events.clone());
+            }
             snapshot = snapshot.with_metadata(metadata);
         }
 
@@ -590,17 +985,20 @@ impl Simulation {
 
     /// Restore from a snapshot
     pub fn restore(&mut self, snapshot: Snapshot) -> Result<(), &'static str> {
+        let snapshot = snapshot.migrate()?;
         snapshot.validate()?;
+        let config = snapshot.simulation_config()?;
 
         self.sequence = snapshot.sequence;
         self.time = snapshot.time;
         self.tick = snapshot.tick;
         self.bodies = snapshot.bodies;
         self.rng = Pcg32::from_state(snapshot.rng_state.0, snapshot.rng_state.1);
-        self.config.integrator = (&snapshot.integrator_config).into();
-        self.config.integrator.force_config = (&snapshot.force_config).into();
+        self.config = config;
         self.needs_init = true;
         self.cached_potential_energy = None;
+        self.mass_lost = 0.0;
+        self.mass_loss_energy = 0.0;
         self.close_encounter_active = false;
         self.close_encounter_last_body_ids.clear();
 
@@ -610,6 +1008,42 @@ impl Simulation {
         Ok(())
     }
 
+    /// Apply a delta produced by `DeltaSnapshot::from_diff` against the
+    /// current state. Changed bodies replace their counterparts by ID, unknown
+    /// IDs are inserted, and removed IDs are deactivated.
+    pub fn apply_delta(&mut self, delta: DeltaSnapshot) -> Result<(), DeltaError> {
+        if delta.base_sequence != self.sequence {
+            return Err(DeltaError::SequenceMismatch {
+                expected: delta.base_sequence,
+                actual: self.sequence,
+            });
+        }
+        if let Some(body) = delta.changed_bodies.iter().find(|b| !b.is_valid()) {
+            return Err(DeltaError::InvalidBody(body.id));
+        }
+
+        for body in delta.changed_bodies {
+            self.next_id = self.next_id.max(body.id + 1);
+            match self.bodies.iter_mut().find(|b| b.id == body.id) {
+                Some(existing) => *existing = body,
+                None => self.bodies.push(body),
+            }
+        }
+        for id in &delta.removed_body_ids {
+            if let Some(body) = self.bodies.iter_mut().find(|b| b.id == *id) {
+                body.is_active = false;
+            }
+        }
+
+        self.sequence = delta.sequence;
+        self.time = delta.time;
+        self.tick = delta.tick;
+        self.needs_init = true;
+        self.cached_potential_energy = None;
+
+        Ok(())
+    }
+
     /// Export to JSON
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         self.snapshot().to_json()
@@ -649,6 +1083,28 @@ impl Simulation {
         result
     }
 
+    /// Get orientations as a flat array [w0, x0, y0, z0, w1, ...]
+    pub fn orientations_flat(&self) -> Vec<f64> {
+        let mut result = Vec::with_capacity(self.bodies.len() * 4);
+        for body in &self.bodies {
+            if body.is_active {
+                result.extend_from_slice(&body.orientation.to_array());
+            }
+        }
+        result
+    }
+
+    /// Get angular velocities as a flat array [wx0, wy0, wz0, wx1, ...]
+    pub fn angular_velocities_flat(&self) -> Vec<f64> {
+        let mut result = Vec::with_capacity(self.bodies.len() * 3);
+        for body in &self.bodies {
+            if body.is_active {
+                result.extend_from_slice(&body.angular_velocity.to_array());
+            }
+        }
+        result
+    }
+
     /// Set configuration
     pub fn set_config(&mut self, config: SimulationConfig) {
         self.config = config;
@@ -670,11 +1126,99 @@ impl Simulation {
         self.config.integrator.substeps = substeps;
     }
 
+    /// Set integration method
+    pub fn set_integrator(&mut self, method: IntegratorType) {
+        self.config.integrator.method = method;
+    }
+
+    /// Choose the substep count each tick from the shortest dynamical
+    /// timescale instead of the fixed `substeps`
+    pub fn set_adaptive_substeps(&mut self, enabled: bool) {
+        self.config.integrator.adaptive.enabled = enabled;
+    }
+
+    /// Set the adaptive substep fraction and cap (each ignored unless positive)
+    pub fn set_adaptive_substep_params(&mut self, eta: f64, max_substeps: u32) {
+        if eta > 0.0 {
+            self.config.integrator.adaptive.eta = eta;
+        }
+        if max_substeps > 0 {
+            self.config.integrator.adaptive.max_substeps = max_substeps;
+        }
+    }
+
+    /// Set IAS15 step-size tolerance (ignored unless positive)
+    pub fn set_ias15_epsilon(&mut self, epsilon: f64) {
+        if epsilon > 0.0 {
+            self.config.integrator.ias15_epsilon = epsilon;
+        }
+    }
+
     /// Set Barnes-Hut theta
     pub fn set_theta(&mut self, theta: f64) {
         self.config.integrator.force_config.barnes_hut_theta = theta;
     }
 
+    /// Set the post-Newtonian correction mode
+    pub fn set_relativity(&mut self, mode: RelativityMode) {
+        self.config.integrator.force_config.relativity = mode;
+    }
+
+    /// Set the radiation-reaction strength between compact stars
+    /// (0 = off, 1 = physical; larger values speed up inspirals)
+    pub fn set_radiation_reaction(&mut self, scale: f64) {
+        if scale >= 0.0 {
+            self.config.integrator.force_config.radiation_reaction = scale;
+        }
+    }
+
+    /// Enable or disable constant-time-lag tides
+    pub fn set_tides(&mut self, enabled: bool) {
+        self.config.integrator.force_config.tides = enabled;
+    }
+
+    /// Enable or disable radiation pressure and Poynting-Robertson drag
+    pub fn set_radiation(&mut self, enabled: bool) {
+        self.config.integrator.force_config.radiation = enabled;
+    }
+
+    /// Enable or disable atmospheric drag
+    pub fn set_atmospheric_drag(&mut self, enabled: bool) {
+        self.config.integrator.force_config.atmospheric_drag = enabled;
+    }
+
+    /// Enable or disable non-gravitational forces on outgassing comets
+    pub fn set_outgassing(&mut self, enabled: bool) {
+        self.config.integrator.force_config.outgassing = enabled;
+    }
+
+    /// Enable or disable Yarkovsky drift of asteroids
+    pub fn set_yarkovsky(&mut self, enabled: bool) {
+        self.config.integrator.force_config.yarkovsky = enabled;
+    }
+
+    /// Enable or disable YORP spin evolution of asteroids
+    pub fn set_yorp(&mut self, enabled: bool) {
+        self.config.integrator.force_config.yorp = enabled;
+    }
+
+    /// Enable or disable torque-driven spin-axis precession of oblate bodies
+    pub fn set_precession(&mut self, enabled: bool) {
+        self.config.integrator.force_config.precession = enabled;
+    }
+
+    /// Set FMM expansion order (clamped to 1..=MAX_FMM_ORDER)
+    pub fn set_fmm_order(&mut self, order: u32) {
+        self.config.integrator.force_config.fmm_order = order.clamp(1, MAX_FMM_ORDER);
+    }
+
+    /// Set FMM opening angle
+    pub fn set_fmm_theta(&mut self, theta: f64) {
+        if theta > 0.0 {
+            self.config.integrator.force_config.fmm_theta = theta;
+        }
+    }
+
     /// Set close-encounter integrator (subset-scoped)
     pub fn set_close_encounter_integrator(&mut self, integrator: CloseEncounterIntegrator) {
         self.config.integrator.close_encounter.integrator = integrator;
@@ -730,6 +1274,43 @@ impl Simulation {
         self.needs_init = true;
     }
 
+    /// Set how bodies with a mass-loss rate lose mass
+    pub fn set_mass_loss(&mut self, mode: MassLossMode) {
+        self.config.mass_loss = mode;
+    }
+
+    /// Enable or disable collision detection
+    pub fn set_collisions_enabled(&mut self, enabled: bool) {
+        self.config.collision.enabled = enabled;
+    }
+
+    /// Set the collision outcome for a body type
+    pub fn set_collision_outcome(&mut self, body_type: BodyType, outcome: CollisionOutcome) {
+        self.config.collision.set_outcome(body_type, outcome);
+    }
+
+    /// Set the coefficient of restitution for bounces (clamped to [0, 1])
+    pub fn set_collision_restitution(&mut self, restitution: f64) {
+        if restitution.is_finite() {
+            self.config.collision.restitution = restitution.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Set how many debris bodies a fragmenting impact sheds (at least 2)
+    pub fn set_collision_debris(&mut self, count: u32) {
+        self.config.collision.debris = count.max(2);
+    }
+
+    /// Enable or disable tidal disruption at the Roche limit
+    pub fn set_disruption_enabled(&mut self, enabled: bool) {
+        self.config.disruption.enabled = enabled;
+    }
+
+    /// Set how many fragments a disrupted body breaks into (at least 2)
+    pub fn set_disruption_fragments(&mut self, fragments: u32) {
+        self.config.disruption.fragments = fragments.max(2);
+    }
+
     /// Get a random number from the deterministic RNG
     pub fn random(&mut self) -> f64 {
         self.rng.next_f64()
@@ -749,6 +1330,16 @@ impl Simulation {
     pub fn take_close_encounter_events(&mut self) -> Vec<CloseEncounterEvent> {
         std::mem::take(&mut self.close_encounter_events)
     }
+
+    /// Drain collision events for logging
+    pub fn take_collision_events(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut self.collision_events)
+    }
+
+    /// Drain tidal disruption events for logging
+    pub fn take_disruption_events(&mut self) -> Vec<DisruptionEvent> {
+        std::mem::take(&mut self.disruption_events)
+    }
 }
 
 fn hill_radius_estimate(m1: f64, m2: f64, distance: f64) -> f64 {
@@ -840,4 +1431,41 @@ mod tests {
         assert!(moon_id.is_some());
         assert_eq!(sim.body_count(), 3);
     }
+
+    #[test]
+    fn test_osculating_elements_hierarchy() {
+        let mut sim = create_earth_sun_system();
+        let earth_id = 1;
+
+        // No parent_id: the Moon must still be assigned to Earth by Hill sphere
+        let earth = sim.get_body(earth_id).unwrap().clone();
+        let moon_id = sim.add_body(Body::new(
+            0,
+            "Moon",
+            crate::body::BodyType::Moon,
+            M_MOON,
+            R_MOON,
+            earth.position + Vec3::new(3.844e8, 0.0, 0.0),
+            earth.velocity + Vec3::new(0.0, 1022.0, 0.0),
+        ));
+
+        assert!(sim.osculating_elements(0).is_none());
+
+        let (primary, earth_elements) = sim.osculating_elements(earth_id).unwrap();
+        assert_eq!(primary, 0);
+        assert!((earth_elements.semi_major_axis / AU - 1.0).abs() < 0.01);
+
+        let (primary, moon_elements) = sim.osculating_elements(moon_id).unwrap();
+        assert_eq!(primary, earth_id);
+        assert!(moon_elements.is_bound());
+        assert!(moon_elements.eccentricity < 0.1);
+
+        sim.set_dt(3600.0);
+        sim.step_n(24 * 10);
+        sim.update_orbital_elements();
+        let moon = sim.get_body(moon_id).unwrap();
+        let (_, fresh) = sim.osculating_elements(moon_id).unwrap();
+        assert_eq!(moon.semi_major_axis, fresh.semi_major_axis);
+        assert!((moon.semi_major_axis / 3.844e8 - 1.0).abs() < 0.1);
+    }
 }
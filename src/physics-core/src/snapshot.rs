@@ -2,15 +2,31 @@
 //!
 //! Provides versioned JSON serialization of simulation state with
 //! support for checkpointing and network synchronization.
+//!
+//! Version history:
+//! - v1: bodies, force config, integrator config
+//! - v2: adds `simulation_config` (force method, Barnes-Hut threshold,
+//!   collisions). v1 files are migrated on load with v1 defaults.
 
 use crate::body::Body;
+use crate::codec::{self, CodecError, Quantization};
+use crate::collision::{CollisionConfig, CollisionEvent, CollisionOutcome};
+use crate::constants::{DEFAULT_FMM_ORDER, DEFAULT_FMM_THETA, DEFAULT_IAS15_EPSILON};
 use crate::force::ForceConfig;
-use crate::integrator::{CloseEncounterConfig, CloseEncounterIntegrator, IntegratorConfig, IntegratorType};
+use crate::integrator::{AdaptiveSubstepConfig, CloseEncounterConfig, CloseEncounterIntegrator, IntegratorConfig, IntegratorType};
+use crate::disruption::{DisruptionConfig, DisruptionEvent};
+use crate::fragmentation::DEFAULT_DEBRIS_FRAGMENTS;
+use crate::mass_loss::MassLossMode;
 use crate::prng::Pcg32;
+use crate::relativity::RelativityMode;
+use crate::simulation::{ForceMethod, SimulationConfig};
 use serde::{Deserialize, Serialize};
 
 /// Current snapshot format version
-pub const SNAPSHOT_VERSION: u32 = 1;
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Oldest snapshot format version that can still be loaded (via migration)
+pub const MIN_SNAPSHOT_VERSION: u32 = 1;
 
 /// Simulation snapshot for serialization
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +54,10 @@ pub struct Snapshot {
     
     /// Integrator configuration  
     pub integrator_config: SerializableIntegratorConfig,
+
+    /// Remaining simulation settings (v2+; v1 files get the v1 defaults)
+    #[serde(default)]
+    pub simulation_config: SerializableSimulationConfig,
     
     /// Optional metadata
     pub metadata: Option<SnapshotMetadata>,
@@ -48,6 +68,36 @@ pub struct Snapshot {
 pub struct SerializableForceConfig {
     pub softening: f64,
     pub barnes_hut_theta: f64,
+    #[serde(default = "default_fmm_order")]
+    pub fmm_order: u32,
+    #[serde(default = "default_fmm_theta")]
+    pub fmm_theta: f64,
+    #[serde(default)]
+    pub relativity: RelativityMode,
+    #[serde(default)]
+    pub radiation_reaction: f64,
+    #[serde(default)]
+    pub tides: bool,
+    #[serde(default)]
+    pub radiation: bool,
+    #[serde(default)]
+    pub atmospheric_drag: bool,
+    #[serde(default)]
+    pub outgassing: bool,
+    #[serde(default)]
+    pub yarkovsky: bool,
+    #[serde(default)]
+    pub yorp: bool,
+    #[serde(default)]
+    pub precession: bool,
+}
+
+fn default_fmm_order() -> u32 {
+    DEFAULT_FMM_ORDER
+}
+
+fn default_fmm_theta() -> f64 {
+    DEFAULT_FMM_THETA
 }
 
 impl From<&ForceConfig> for SerializableForceConfig {
@@ -55,6 +105,17 @@ impl From<&ForceConfig> for SerializableForceConfig {
         Self {
             softening: config.softening,
             barnes_hut_theta: config.barnes_hut_theta,
+            fmm_order: config.fmm_order,
+            fmm_theta: config.fmm_theta,
+            relativity: config.relativity,
+            radiation_reaction: config.radiation_reaction,
+            tides: config.tides,
+            radiation: config.radiation,
+            atmospheric_drag: config.atmospheric_drag,
+            outgassing: config.outgassing,
+            yarkovsky: config.yarkovsky,
+            yorp: config.yorp,
+            precession: config.precession,
         }
     }
 }
@@ -64,27 +125,48 @@ impl From<&SerializableForceConfig> for ForceConfig {
         Self {
             softening: config.softening,
             barnes_hut_theta: config.barnes_hut_theta,
+            fmm_order: config.fmm_order,
+            fmm_theta: config.fmm_theta,
+            relativity: config.relativity,
+            radiation_reaction: config.radiation_reaction,
+            tides: config.tides,
+            radiation: config.radiation,
+            atmospheric_drag: config.atmospheric_drag,
+            outgassing: config.outgassing,
+            yarkovsky: config.yarkovsky,
+            yorp: config.yorp,
+            precession: config.precession,
         }
     }
 }
 
-impl From<&SerializableIntegratorConfig> for IntegratorConfig {
-    fn from(config: &SerializableIntegratorConfig) -> Self {
+impl TryFrom<&SerializableIntegratorConfig> for IntegratorConfig {
+    type Error = &'static str;
+
+    fn try_from(config: &SerializableIntegratorConfig) -> Result<Self, Self::Error> {
         let mut result = IntegratorConfig::default();
         result.dt = config.dt;
         result.substeps = config.substeps;
+        result.ias15_epsilon = config.ias15_epsilon;
         result.method = match config.method.as_str() {
             "VelocityVerlet" => IntegratorType::VelocityVerlet,
             "Euler" => IntegratorType::Euler,
             "Leapfrog" => IntegratorType::Leapfrog,
-            _ => IntegratorType::VelocityVerlet,
+            "Yoshida4" => IntegratorType::Yoshida4,
+            "Yoshida6" => IntegratorType::Yoshida6,
+            "ForestRuth" => IntegratorType::ForestRuth,
+            "Pefrl" => IntegratorType::Pefrl,
+            "WisdomHolman" => IntegratorType::WisdomHolman,
+            "Ias15" => IntegratorType::Ias15,
+            "Hermite" => IntegratorType::Hermite,
+            _ => return Err("Unknown integrator method in snapshot"),
         };
 
         let integrator = match config.close_encounter.integrator.as_str() {
             "Rk45" => CloseEncounterIntegrator::Rk45,
             "GaussRadau5" => CloseEncounterIntegrator::GaussRadau5,
             "None" => CloseEncounterIntegrator::None,
-            _ => CloseEncounterIntegrator::GaussRadau5,
+            _ => return Err("Unknown close-encounter integrator in snapshot"),
         };
 
         result.close_encounter = CloseEncounterConfig {
@@ -101,7 +183,13 @@ impl From<&SerializableIntegratorConfig> for IntegratorConfig {
             gauss_radau_tol: config.close_encounter.gauss_radau_tol,
         };
 
-        result
+        result.adaptive = AdaptiveSubstepConfig {
+            enabled: config.adaptive.enabled,
+            eta: config.adaptive.eta,
+            max_substeps: config.adaptive.max_substeps,
+        };
+
+        Ok(result)
     }
 }
 
@@ -111,7 +199,15 @@ pub struct SerializableIntegratorConfig {
     pub dt: f64,
     pub substeps: u32,
     pub method: String,
+    #[serde(default = "default_ias15_epsilon")]
+    pub ias15_epsilon: f64,
     pub close_encounter: SerializableCloseEncounterConfig,
+    #[serde(default)]
+    pub adaptive: SerializableAdaptiveSubstepConfig,
+}
+
+fn default_ias15_epsilon() -> f64 {
+    DEFAULT_IAS15_EPSILON
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,12 +225,37 @@ pub struct SerializableCloseEncounterConfig {
     pub gauss_radau_tol: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableAdaptiveSubstepConfig {
+    pub enabled: bool,
+    pub eta: f64,
+    pub max_substeps: u32,
+}
+
+impl Default for SerializableAdaptiveSubstepConfig {
+    /// Fixed substeps, as implied by snapshots that predate adaptive mode
+    fn default() -> Self {
+        (&AdaptiveSubstepConfig::default()).into()
+    }
+}
+
+impl From<&AdaptiveSubstepConfig> for SerializableAdaptiveSubstepConfig {
+    fn from(config: &AdaptiveSubstepConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            eta: config.eta,
+            max_substeps: config.max_substeps,
+        }
+    }
+}
+
 impl From<&IntegratorConfig> for SerializableIntegratorConfig {
     fn from(config: &IntegratorConfig) -> Self {
         Self {
             dt: config.dt,
             substeps: config.substeps,
             method: format!("{:?}", config.method),
+            ias15_epsilon: config.ias15_epsilon,
             close_encounter: SerializableCloseEncounterConfig {
                 enabled: config.close_encounter.enabled,
                 integrator: format!("{:?}", config.close_encounter.integrator),
@@ -148,10 +269,94 @@ impl From<&IntegratorConfig> for SerializableIntegratorConfig {
                 gauss_radau_max_iters: config.close_encounter.gauss_radau_max_iters as u32,
                 gauss_radau_tol: config.close_encounter.gauss_radau_tol,
             },
+            adaptive: (&config.adaptive).into(),
         }
     }
 }
 
+/// Serializable simulation-level settings (everything in `SimulationConfig`
+/// outside the integrator and force configs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableSimulationConfig {
+    pub force_method: String,
+    pub barnes_hut_threshold: u64,
+    pub collision: SerializableCollisionConfig,
+    #[serde(default)]
+    pub mass_loss: MassLossMode,
+    #[serde(default)]
+    pub disruption: DisruptionConfig,
+}
+
+/// Serializable collision settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableCollisionConfig {
+    pub enabled: bool,
+    /// Outcome per body type, indexed by `BodyType as usize`
+    pub outcomes: Vec<CollisionOutcome>,
+    pub restitution: f64,
+    #[serde(default = "default_collision_debris")]
+    pub debris: u32,
+}
+
+fn default_collision_debris() -> u32 {
+    DEFAULT_DEBRIS_FRAGMENTS
+}
+
+impl Default for SerializableSimulationConfig {
+    /// Settings implied by a v1 snapshot (which did not store them)
+    fn default() -> Self {
+        (&SimulationConfig::default()).into()
+    }
+}
+
+impl From<&SimulationConfig> for SerializableSimulationConfig {
+    fn from(config: &SimulationConfig) -> Self {
+        Self {
+            force_method: format!("{:?}", config.force_method),
+            barnes_hut_threshold: config.barnes_hut_threshold as u64,
+            collision: SerializableCollisionConfig {
+                enabled: config.collision.enabled,
+                outcomes: config.collision.outcomes.to_vec(),
+                restitution: config.collision.restitution,
+                debris: config.collision.debris,
+            },
+            mass_loss: config.mass_loss,
+            disruption: config.disruption,
+        }
+    }
+}
+
+impl SerializableSimulationConfig {
+    /// Apply these settings onto a `SimulationConfig`, leaving its
+    /// integrator settings untouched
+    pub fn apply_to(&self, config: &mut SimulationConfig) -> Result<(), &'static str> {
+        let force_method = match self.force_method.as_str() {
+            "Direct" => ForceMethod::Direct,
+            "BarnesHut" => ForceMethod::BarnesHut,
+            "Fmm" => ForceMethod::Fmm,
+            _ => return Err("Unknown force method in snapshot"),
+        };
+
+        let mut collision = CollisionConfig {
+            enabled: self.collision.enabled,
+            restitution: self.collision.restitution,
+            debris: self.collision.debris,
+            ..CollisionConfig::default()
+        };
+        if self.collision.outcomes.len() != collision.outcomes.len() {
+            return Err("Invalid collision outcome table in snapshot");
+        }
+        collision.outcomes.copy_from_slice(&self.collision.outcomes);
+
+        config.force_method = force_method;
+        config.barnes_hut_threshold = self.barnes_hut_threshold as usize;
+        config.collision = collision;
+        config.mass_loss = self.mass_loss;
+        config.disruption = self.disruption;
+        Ok(())
+    }
+}
+
 /// Optional metadata about the snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnapshotMetadata {
@@ -172,6 +377,14 @@ pub struct SnapshotMetadata {
 
     /// Close-encounter integrator switch events
     pub close_encounter_events: Option<Vec<CloseEncounterEvent>>,
+
+    /// Collision (merge/bounce) events
+    #[serde(default)]
+    pub collision_events: Option<Vec<CollisionEvent>>,
+
+    /// Tidal disruption events
+    #[serde(default)]
+    pub disruption_events: Option<Vec<DisruptionEvent>>,
 }
 
 impl Default for SnapshotMetadata {
@@ -183,6 +396,8 @@ impl Default for SnapshotMetadata {
             author: None,
             preset: None,
             close_encounter_events: None,
+            collision_events: None,
+            disruption_events: None,
         }
     }
 }
@@ -220,10 +435,28 @@ impl Snapshot {
             bodies,
             force_config: force_config.into(),
             integrator_config: integrator_config.into(),
+            simulation_config: SerializableSimulationConfig::default(),
             metadata: None,
         }
     }
 
+    /// Record the simulation-level settings (force method, collisions, ...)
+    pub fn with_simulation_config(mut self, config: &SimulationConfig) -> Self {
+        self.simulation_config = config.into();
+        self
+    }
+
+    /// Restore the full `SimulationConfig` stored in this snapshot
+    pub fn simulation_config(&self) -> Result<SimulationConfig, &'static str> {
+        let mut config = SimulationConfig {
+            integrator: (&self.integrator_config).try_into()?,
+            ..SimulationConfig::default()
+        };
+        config.integrator.force_config = (&self.force_config).into();
+        self.simulation_config.apply_to(&mut config)?;
+        Ok(config)
+    }
+
     /// Serialize to JSON string
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
@@ -234,9 +467,40 @@ impl Snapshot {
         serde_json::to_string_pretty(self)
     }
 
-    /// Deserialize from JSON string
+    /// Deserialize from JSON string, migrating older versions to the
+    /// current format
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
-        serde_json::from_str(json)
+        let snapshot: Self = serde_json::from_str(json)?;
+        snapshot.migrate().map_err(serde::de::Error::custom)
+    }
+
+    /// Serialize to the compact binary format (lossless, bit-exact)
+    pub fn to_binary(&self) -> Result<Vec<u8>, CodecError> {
+        codec::encode_snapshot(self, None)
+    }
+
+    /// Serialize to the compact binary format with quantized positions and
+    /// velocities (accelerations are dropped)
+    pub fn to_binary_quantized(&self, quantization: Quantization) -> Result<Vec<u8>, CodecError> {
+        codec::encode_snapshot(self, Some(quantization))
+    }
+
+    /// Deserialize from the compact binary format, migrating older versions
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, CodecError> {
+        Ok(codec::decode_snapshot(bytes)?.migrate()?)
+    }
+
+    /// Upgrade a snapshot from any supported older version to `SNAPSHOT_VERSION`
+    pub fn migrate(mut self) -> Result<Self, &'static str> {
+        if !(MIN_SNAPSHOT_VERSION..=SNAPSHOT_VERSION).contains(&self.version) {
+            return Err("Incompatible snapshot version");
+        }
+        if self.version == 1 {
+            // v1 never stored simulation-level settings; restore used the defaults
+            self.simulation_config = SerializableSimulationConfig::default();
+            self.version = 2;
+        }
+        Ok(self)
     }
 
     /// Add metadata to the snapshot
@@ -247,10 +511,13 @@ impl Snapshot {
 
     /// Validate snapshot integrity
     pub fn validate(&self) -> Result<(), &'static str> {
-        if self.version != SNAPSHOT_VERSION {
+        if !(MIN_SNAPSHOT_VERSION..=SNAPSHOT_VERSION).contains(&self.version) {
             return Err("Incompatible snapshot version");
         }
 
+        // Check all configuration strings are recognised
+        self.simulation_config()?;
+
         // Check all bodies are valid
         for body in &self.bodies {
             if !body.is_valid() {
@@ -293,6 +560,10 @@ pub struct DeltaSnapshot {
     
     /// Current simulation time
     pub time: f64,
+
+    /// Tick count after applying delta
+    #[serde(default)]
+    pub tick: u64,
     
     /// Bodies that changed (subset of full state)
     pub changed_bodies: Vec<Body>,
@@ -302,8 +573,14 @@ pub struct DeltaSnapshot {
 }
 
 impl DeltaSnapshot {
-    /// Create a delta between two snapshots
+    /// Create a delta between two snapshots using the default thresholds
     pub fn from_diff(old: &Snapshot, new: &Snapshot) -> Self {
+        Self::from_diff_with_thresholds(old, new, &DeltaThresholds::default())
+    }
+
+    /// Create a delta between two snapshots, sending bodies whose state moved
+    /// by more than `thresholds`
+    pub fn from_diff_with_thresholds(old: &Snapshot, new: &Snapshot, thresholds: &DeltaThresholds) -> Self {
         let mut changed_bodies = Vec::new();
         let mut removed_body_ids = Vec::new();
 
@@ -312,7 +589,7 @@ impl DeltaSnapshot {
             let old_body = old.bodies.iter().find(|b| b.id == new_body.id);
             
             match old_body {
-                Some(old) if body_changed(old, new_body) => {
+                Some(old) if thresholds.body_changed(old, new_body) => {
                     changed_bodies.push(new_body.clone());
                 }
                 None => {
@@ -338,6 +615,7 @@ impl DeltaSnapshot {
             base_sequence: old.sequence,
             sequence: new.sequence,
             time: new.time,
+            tick: new.tick,
             changed_bodies,
             removed_body_ids,
         }
@@ -352,22 +630,78 @@ impl DeltaSnapshot {
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
+
+    /// Serialize to the compact binary format relative to `base`, the
+    /// snapshot this delta was diffed against
+    pub fn to_binary(&self, base: &Snapshot, quantization: Option<Quantization>) -> Result<Vec<u8>, CodecError> {
+        codec::encode_delta(self, base, quantization)
+    }
+
+    /// Deserialize from the compact binary format relative to `base`
+    pub fn from_binary(bytes: &[u8], base: &Snapshot) -> Result<Self, CodecError> {
+        codec::decode_delta(bytes, base)
+    }
 }
 
-/// Check if two bodies have changed significantly
-fn body_changed(old: &Body, new: &Body) -> bool {
-    // Position threshold: 1 meter
-    const POS_THRESHOLD: f64 = 1.0;
-    // Velocity threshold: 0.01 m/s
-    const VEL_THRESHOLD: f64 = 0.01;
+/// Change thresholds deciding which bodies a delta carries
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DeltaThresholds {
+    /// Position change (m)
+    pub position: f64,
+    /// Velocity change (m/s)
+    pub velocity: f64,
+    /// Mass change (kg)
+    pub mass: f64,
+    /// Radius change (m)
+    pub radius: f64,
+}
 
-    old.is_active != new.is_active
-        || (old.position - new.position).length() > POS_THRESHOLD
-        || (old.velocity - new.velocity).length() > VEL_THRESHOLD
-        || (old.mass - new.mass).abs() > 1.0
-        || (old.radius - new.radius).abs() > 0.1
+impl Default for DeltaThresholds {
+    fn default() -> Self {
+        Self {
+            position: 1.0,
+            velocity: 0.01,
+            mass: 1.0,
+            radius: 0.1,
+        }
+    }
 }
 
+impl DeltaThresholds {
+    /// Check if two bodies have changed significantly
+    pub fn body_changed(&self, old: &Body, new: &Body) -> bool {
+        old.is_active != new.is_active
+            || (old.position - new.position).length() > self.position
+            || (old.velocity - new.velocity).length() > self.velocity
+            || (old.mass - new.mass).abs() > self.mass
+            || (old.radius - new.radius).abs() > self.radius
+    }
+}
+
+/// Error returned when a delta cannot be applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeltaError {
+    /// The delta was diffed against a different sequence than the current state
+    SequenceMismatch { expected: u64, actual: u64 },
+    /// A changed body failed validation
+    InvalidBody(u32),
+}
+
+impl std::fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeltaError::SequenceMismatch { expected, actual } => write!(
+                f,
+                "delta base sequence {} does not match current sequence {}",
+                expected, actual
+            ),
+            DeltaError::InvalidBody(id) => write!(f, "delta contains invalid body {}", id),
+        }
+    }
+}
+
+impl std::error::Error for DeltaError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -434,6 +768,21 @@ mod tests {
         assert!(delta.removed_body_ids.is_empty());
     }
 
+    #[test]
+    fn test_delta_thresholds() {
+        let old = create_test_snapshot();
+        let mut new = old.clone();
+        new.sequence = 2;
+        new.bodies[1].position = Vec3::new(AU + 1000.0, 0.0, 0.0);
+        new.bodies[1].velocity = Vec3::new(0.0, 29784.5, 0.0);
+
+        let coarse = DeltaThresholds { position: 1e4, ..Default::default() };
+        assert_eq!(DeltaSnapshot::from_diff_with_thresholds(&old, &new, &coarse).changed_bodies.len(), 1);
+
+        let coarse = DeltaThresholds { position: 1e4, velocity: 1.0, ..Default::default() };
+        assert!(DeltaSnapshot::from_diff_with_thresholds(&old, &new, &coarse).changed_bodies.is_empty());
+    }
+
     #[test]
     fn test_pretty_json() {
         let snapshot = create_test_snapshot();
@@ -442,4 +791,51 @@ mod tests {
         // Should contain formatted output
         assert!(json.contains('\n'));
     }
+
+    /// Reproduce a v1 file: no simulation_config, version 1
+    fn v1_json(snapshot: &Snapshot) -> String {
+        let mut value = serde_json::to_value(snapshot).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("simulation_config");
+        object.insert("version".to_string(), serde_json::json!(1));
+        value.to_string()
+    }
+
+    #[test]
+    fn test_v1_migration() {
+        let mut snapshot = create_test_snapshot();
+        snapshot.simulation_config.force_method = "BarnesHut".to_string();
+
+        let migrated = Snapshot::from_json(&v1_json(&snapshot)).expect("v1 snapshot should load");
+        assert_eq!(migrated.version, SNAPSHOT_VERSION);
+        assert_eq!(migrated.simulation_config.force_method, "Direct");
+        assert!(migrated.validate().is_ok());
+
+        let config = migrated.simulation_config().unwrap();
+        assert_eq!(config.force_method, ForceMethod::Direct);
+        assert_eq!(config.barnes_hut_threshold, SimulationConfig::default().barnes_hut_threshold);
+    }
+
+    #[test]
+    fn test_unknown_config_strings_rejected() {
+        let mut snapshot = create_test_snapshot();
+        snapshot.integrator_config.close_encounter.integrator = "Bogus".to_string();
+        assert!(snapshot.validate().is_err());
+
+        let mut snapshot = create_test_snapshot();
+        snapshot.integrator_config.method = "Bogus".to_string();
+        assert!(snapshot.validate().is_err());
+
+        let mut snapshot = create_test_snapshot();
+        snapshot.simulation_config.force_method = "Bogus".to_string();
+        assert!(snapshot.validate().is_err());
+    }
+
+    #[test]
+    fn test_future_version_rejected() {
+        let mut snapshot = create_test_snapshot();
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        assert!(snapshot.validate().is_err());
+        assert!(Snapshot::from_json(&snapshot.to_json().unwrap()).is_err());
+    }
 }
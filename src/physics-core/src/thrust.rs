@@ -0,0 +1,288 @@
+//! Engine thrust and impulsive manoeuvres
+//!
+//! Spacecraft and players steer by queueing `Burn`s on their `burns` list:
+//! a constant thrust in newtons that starts after `delay` seconds and lasts
+//! `duration` seconds. The thrust vector is given in one of three frames
+//! measured relative to a reference body (the active parent, otherwise the
+//! body pulling hardest):
+//!
+//! - `Inertial`: the simulation axes
+//! - `Prograde`: x along the relative velocity, y along the orbit normal
+//!   r × v, z = x × y (away from the reference on a circular orbit)
+//! - `Radial`: x radially away from the reference, y transverse in the
+//!   direction of motion, z along the orbit normal
+//!
+//! With a positive `exhaust_velocity` v_e, thrust F burns propellant at
+//! F / v_e kg/s, taken from both `mass` and `propellant_mass`; engines cut
+//! out when the propellant is gone. A zero `exhaust_velocity` leaves the
+//! mass untouched.
+//!
+//! Before each substep the burn windows are clipped to the substep and the
+//! resulting acceleration is frozen in inertial axes in `thrust`, so every
+//! integrator sees an acceleration that is constant across the substep. It
+//! is the thrust divided by the log-mean mass Δm / ln(m₀/m₁), so each
+//! substep delivers the rocket-equation Δv = v_e ln(m₀/m₁) exactly, and a
+//! burn that starts or stops mid-substep delivers exactly its share.
+//! Whenever `thrust` changes the accelerations are refreshed before the
+//! substep, keeping `prev_acceleration`-based schemes consistent.
+//!
+//! Thrust in a frame that is undefined (no reference body, or motion along
+//! the line to it) is not applied.
+
+use crate::body::Body;
+use crate::vector::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Axes a thrust or Δv vector is given in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThrustFrame {
+    /// Simulation x, y, z
+    #[default]
+    Inertial,
+    /// Velocity, orbit normal, velocity × normal
+    Prograde,
+    /// Radial, transverse, orbit normal
+    Radial,
+}
+
+impl ThrustFrame {
+    /// Parse a frame name ("inertial", "prograde", "radial")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "inertial" => Some(Self::Inertial),
+            "prograde" => Some(Self::Prograde),
+            "radial" => Some(Self::Radial),
+            _ => None,
+        }
+    }
+}
+
+/// A constant-thrust engine burn
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Burn {
+    /// Seconds until ignition
+    pub delay: f64,
+    /// Burn length in seconds
+    pub duration: f64,
+    /// Thrust in N, as components in `frame`
+    pub thrust: Vec3,
+    /// Axes of `thrust`
+    pub frame: ThrustFrame,
+}
+
+impl Burn {
+    /// Fraction of the next `dt` seconds during which the engine fires
+    pub fn duty(&self, dt: f64) -> f64 {
+        let on = (self.delay + self.duration).min(dt) - self.delay.max(0.0);
+        if dt > 0.0 { (on / dt).max(0.0) } else { 0.0 }
+    }
+}
+
+/// Reference body for the thrust frames of body `index`: its active
+/// parent, otherwise the massive body pulling hardest on it
+pub fn thrust_reference(bodies: &[Body], index: usize) -> Option<usize> {
+    let body = &bodies[index];
+    if let Some(parent) = body.parent_id {
+        if let Some(k) = bodies.iter().position(|b| b.id == parent && b.is_active) {
+            if k != index {
+                return Some(k);
+            }
+        }
+    }
+    let mut best: Option<(usize, f64)> = None;
+    for (j, other) in bodies.iter().enumerate() {
+        if j == index || !other.is_active || !other.contributes_gravity || other.mass <= 0.0 {
+            continue;
+        }
+        let r2 = body.position.distance_squared(other.position);
+        if r2 == 0.0 {
+            continue;
+        }
+        let pull = other.mass / r2;
+        if best.is_none_or(|(_, p)| pull > p) {
+            best = Some((j, pull));
+        }
+    }
+    best.map(|(j, _)| j)
+}
+
+/// Convert `vector`, given in `frame` for body `index`, to simulation axes
+pub fn frame_to_inertial(bodies: &[Body], index: usize, vector: Vec3, frame: ThrustFrame) -> Vec3 {
+    if frame == ThrustFrame::Inertial {
+        return vector;
+    }
+    let Some(k) = thrust_reference(bodies, index) else {
+        return Vec3::ZERO;
+    };
+    let r = bodies[index].position - bodies[k].position;
+    let v = bodies[index].velocity - bodies[k].velocity;
+    let normal = r.cross(v).normalize();
+    if normal == Vec3::ZERO {
+        return Vec3::ZERO;
+    }
+    let (x, y, z) = match frame {
+        ThrustFrame::Prograde => {
+            let along = v.normalize();
+            (along, normal, along.cross(normal))
+        }
+        _ => {
+            let radial = r.normalize();
+            (radial, normal.cross(radial), normal)
+        }
+    };
+    x * vector.x + y * vector.y + z * vector.z
+}
+
+/// Fraction of the scheduled thrust over the next `dt` that the propellant
+/// on board sustains, and the propellant that burns
+fn propellant_use(body: &Body, dt: f64) -> (f64, f64) {
+    if body.exhaust_velocity <= 0.0 {
+        return (1.0, 0.0);
+    }
+    let needed: f64 = body.burns.iter().map(|b| b.thrust.length() * b.duty(dt) * dt).sum::<f64>() / body.exhaust_velocity;
+    let propellant = body.propellant_mass.clamp(0.0, body.mass);
+    if needed > propellant { (propellant / needed, propellant) } else { (1.0, needed) }
+}
+
+/// Resolve every body's `thrust` for a substep of `dt`.
+/// Returns whether any thrust changed, in which case the accelerations
+/// must be refreshed before the substep.
+pub fn schedule_thrust(bodies: &mut [Body], dt: f64) -> bool {
+    let mut changed = false;
+    for i in 0..bodies.len() {
+        let body = &bodies[i];
+        if body.burns.is_empty() && body.thrust == Vec3::ZERO {
+            continue;
+        }
+        let mut thrust = Vec3::ZERO;
+        if body.is_active && body.mass > 0.0 {
+            for burn in &body.burns {
+                let duty = burn.duty(dt);
+                if duty > 0.0 {
+                    thrust += frame_to_inertial(bodies, i, burn.thrust, burn.frame) * duty;
+                }
+            }
+            let (share, used) = propellant_use(body, dt);
+            let mass = if used > 0.0 && used < body.mass { used / (body.mass / (body.mass - used)).ln() } else { body.mass };
+            thrust *= share / mass;
+        }
+        changed |= thrust != bodies[i].thrust;
+        bodies[i].thrust = thrust;
+    }
+    changed
+}
+
+/// Burn the propellant used over a substep of `dt` and advance the burn
+/// clocks, dropping finished burns
+pub fn advance_burns(bodies: &mut [Body], dt: f64) {
+    for body in bodies.iter_mut().filter(|b| b.is_active && !b.burns.is_empty()) {
+        let (_, used) = propellant_use(body, dt);
+        body.mass -= used;
+        body.propellant_mass -= used;
+        for burn in body.burns.iter_mut() {
+            burn.delay -= dt;
+            if burn.delay < 0.0 {
+                burn.duration += burn.delay;
+                burn.delay = 0.0;
+            }
+        }
+        body.burns.retain(|b| b.duration > 0.0);
+    }
+}
+
+/// Add the thrust acceleration of every body
+pub fn apply_thrust(bodies: &mut [Body]) {
+    for body in bodies.iter_mut() {
+        if body.is_active && body.feels_gravity {
+            body.acceleration += body.thrust;
+        }
+    }
+}
+
+/// Apply an impulsive `delta_v`, given in `frame`, to body `index`.
+/// With a positive `exhaust_velocity` the propellant used follows the rocket
+/// equation, and Δv is cut short when it runs out. Returns the Δv applied
+/// in simulation axes.
+pub fn apply_delta_v(bodies: &mut [Body], index: usize, delta_v: Vec3, frame: ThrustFrame) -> Vec3 {
+    let mut delta_v = frame_to_inertial(bodies, index, delta_v, frame);
+    let body = &mut bodies[index];
+    if body.exhaust_velocity > 0.0 && body.mass > 0.0 {
+        let propellant = body.propellant_mass.clamp(0.0, body.mass);
+        let available = if propellant < body.mass {
+            body.exhaust_velocity * (body.mass / (body.mass - propellant)).ln()
+        } else {
+            f64::INFINITY
+        };
+        let speed = delta_v.length();
+        if speed > available {
+            delta_v *= available / speed;
+        }
+        let used = body.mass * (1.0 - (-delta_v.length() / body.exhaust_velocity).exp());
+        let used = used.min(propellant);
+        body.mass -= used;
+        body.propellant_mass -= used;
+    }
+    body.velocity += delta_v;
+    delta_v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BodyType;
+    use crate::constants::*;
+
+    fn orbiter() -> Vec<Body> {
+        let earth = Body::new(0, "Earth", BodyType::Planet, M_EARTH, R_EARTH, Vec3::ZERO, Vec3::ZERO);
+        let r = R_EARTH + 400.0e3;
+        let craft = Body::new(1, "Craft", BodyType::Spacecraft, 1000.0, 2.0, Vec3::new(r, 0.0, 0.0), Vec3::new(0.0, (G * M_EARTH / r).sqrt(), 0.0));
+        vec![earth, craft]
+    }
+
+    #[test]
+    fn test_frames() {
+        let bodies = orbiter();
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        assert_eq!(frame_to_inertial(&bodies, 1, v, ThrustFrame::Inertial), v);
+        // Prograde: +y velocity, +z normal, +x away from the Earth
+        assert!((frame_to_inertial(&bodies, 1, v, ThrustFrame::Prograde) - Vec3::new(3.0, 1.0, 2.0)).length() < 1e-12);
+        // Radial: +x radial, +y transverse, +z normal
+        assert!((frame_to_inertial(&bodies, 1, v, ThrustFrame::Radial) - v).length() < 1e-12);
+        // No reference body: rotating frames are undefined
+        assert_eq!(frame_to_inertial(&bodies[1..], 0, v, ThrustFrame::Prograde), Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_duty_clips_window() {
+        let burn = Burn { delay: 3.0, duration: 4.0, thrust: Vec3::X, frame: ThrustFrame::Inertial };
+        assert_eq!(burn.duty(2.0), 0.0);
+        assert_eq!(burn.duty(5.0), 0.4);
+        assert_eq!(burn.duty(10.0), 0.4);
+        let mut bodies = orbiter();
+        bodies[1].burns.push(burn);
+        advance_burns(&mut bodies, 5.0);
+        assert_eq!(bodies[1].burns[0].delay, 0.0);
+        assert_eq!(bodies[1].burns[0].duration, 2.0);
+        advance_burns(&mut bodies, 5.0);
+        assert!(bodies[1].burns.is_empty());
+    }
+
+    #[test]
+    fn test_delta_v_follows_rocket_equation() {
+        let mut bodies = orbiter();
+        bodies[1].propellant_mass = 400.0;
+        bodies[1].exhaust_velocity = 3000.0;
+        let v0 = bodies[1].velocity;
+        let applied = apply_delta_v(&mut bodies, 1, Vec3::new(100.0, 0.0, 0.0), ThrustFrame::Prograde);
+        assert!((applied - Vec3::new(0.0, 100.0, 0.0)).length() < 1e-9);
+        assert!((bodies[1].velocity - v0 - applied).length() < 1e-9);
+        let used = 1000.0 * (1.0 - (-100.0f64 / 3000.0).exp());
+        assert!((bodies[1].mass - (1000.0 - used)).abs() < 1e-9);
+
+        // Asking for more than the tank holds empties it
+        let applied = apply_delta_v(&mut bodies, 1, Vec3::new(1.0e4, 0.0, 0.0), ThrustFrame::Inertial);
+        assert!((applied.x - 3000.0 * (1000.0f64 / 600.0).ln() + 100.0).abs() < 1e-6);
+        assert!(bodies[1].propellant_mass.abs() < 1e-9);
+        assert!((bodies[1].mass - 600.0).abs() < 1e-9);
+    }
+}
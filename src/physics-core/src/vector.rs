@@ -279,6 +279,189 @@ impl From<Vec3> for [f64; 3] {
     }
 }
 
+/// A unit quaternion w + xi + yj + zk representing a rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct Quat {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Quat {
+    /// No rotation
+    pub const IDENTITY: Self = Self { w: 1.0, x: 0.0, y: 0.0, z: 0.0 };
+
+    /// Create a new Quat.
+    #[inline]
+    pub const fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+
+    /// Rotation by `angle` radians about `axis` (need not be unit length).
+    /// Returns the identity for a zero axis.
+    #[inline]
+    pub fn from_axis_angle(axis: Vec3, angle: f64) -> Self {
+        let axis = axis.normalize();
+        let (sin, cos) = (0.5 * angle).sin_cos();
+        Self::new(cos, axis.x * sin, axis.y * sin, axis.z * sin)
+    }
+
+    /// Shortest rotation taking unit vector `from` onto unit vector `to`.
+    pub fn from_rotation_arc(from: Vec3, to: Vec3) -> Self {
+        let dot = from.dot(to);
+        if dot < -1.0 + 1e-15 {
+            // Half turn about any axis perpendicular to `from`
+            let perpendicular = if from.x.abs() < 0.9 { from.cross(Vec3::X) } else { from.cross(Vec3::Y) };
+            return Self::from_axis_angle(perpendicular, std::f64::consts::PI);
+        }
+        let axis = from.cross(to);
+        Self::new(1.0 + dot, axis.x, axis.y, axis.z).normalize()
+    }
+
+    /// Squared norm.
+    #[inline]
+    pub fn length_squared(self) -> f64 {
+        self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    /// Normalize to unit length (identity if zero).
+    #[inline]
+    pub fn normalize(self) -> Self {
+        let len = self.length_squared().sqrt();
+        if len > 0.0 {
+            Self::new(self.w / len, self.x / len, self.y / len, self.z / len)
+        } else {
+            Self::IDENTITY
+        }
+    }
+
+    /// Inverse rotation of a unit quaternion.
+    #[inline]
+    pub fn conjugate(self) -> Self {
+        Self::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    /// Rotate a vector.
+    #[inline]
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        // v' = v + 2w (u × v) + 2 u × (u × v), u = (x, y, z)
+        let u = Vec3::new(self.x, self.y, self.z);
+        let t = u.cross(v) * 2.0;
+        v + t * self.w + u.cross(t)
+    }
+
+    /// Rotation matrix of a unit quaternion.
+    pub fn to_mat3(self) -> Mat3 {
+        let Self { w, x, y, z } = self;
+        Mat3::from_rows([
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+        ])
+    }
+
+    /// Convert to an array [w, x, y, z].
+    #[inline]
+    pub const fn to_array(self) -> [f64; 4] {
+        [self.w, self.x, self.y, self.z]
+    }
+}
+
+impl Mul for Quat {
+    type Output = Self;
+    /// Hamilton product: `a * b` rotates by `b`, then by `a`.
+    #[inline]
+    fn mul(self, o: Self) -> Self {
+        Self {
+            w: self.w * o.w - self.x * o.x - self.y * o.y - self.z * o.z,
+            x: self.w * o.x + self.x * o.w + self.y * o.z - self.z * o.y,
+            y: self.w * o.y - self.x * o.z + self.y * o.w + self.z * o.x,
+            z: self.w * o.z + self.x * o.y - self.y * o.x + self.z * o.w,
+        }
+    }
+}
+
+/// A 3×3 matrix of f64, stored by rows.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Mat3 {
+    pub rows: [[f64; 3]; 3],
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mat3 {
+    /// Identity matrix
+    pub const IDENTITY: Self = Self::from_diagonal(Vec3::new(1.0, 1.0, 1.0));
+
+    /// Create from rows.
+    #[inline]
+    pub const fn from_rows(rows: [[f64; 3]; 3]) -> Self {
+        Self { rows }
+    }
+
+    /// Diagonal matrix.
+    #[inline]
+    pub const fn from_diagonal(d: Vec3) -> Self {
+        Self::from_rows([[d.x, 0.0, 0.0], [0.0, d.y, 0.0], [0.0, 0.0, d.z]])
+    }
+
+    /// Transpose.
+    #[inline]
+    pub fn transpose(self) -> Self {
+        let r = self.rows;
+        Self::from_rows([
+            [r[0][0], r[1][0], r[2][0]],
+            [r[0][1], r[1][1], r[2][1]],
+            [r[0][2], r[1][2], r[2][2]],
+        ])
+    }
+
+    /// Determinant.
+    #[inline]
+    pub fn determinant(self) -> f64 {
+        let r = self.rows;
+        Vec3::from_array(r[0]).dot(Vec3::from_array(r[1]).cross(Vec3::from_array(r[2])))
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+    #[inline]
+    fn mul(self, v: Vec3) -> Vec3 {
+        let r = self.rows;
+        Vec3::new(
+            Vec3::from_array(r[0]).dot(v),
+            Vec3::from_array(r[1]).dot(v),
+            Vec3::from_array(r[2]).dot(v),
+        )
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Self;
+    #[inline]
+    fn mul(self, o: Self) -> Self {
+        let t = o.transpose().rows;
+        let row = |r: [f64; 3]| {
+            let r = Vec3::from_array(r);
+            [r.dot(Vec3::from_array(t[0])), r.dot(Vec3::from_array(t[1])), r.dot(Vec3::from_array(t[2]))]
+        };
+        Self::from_rows([row(self.rows[0]), row(self.rows[1]), row(self.rows[2])])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,4 +518,30 @@ mod tests {
         let scaled = a * 2.0;
         assert!(approx_eq(scaled.x, 2.0));
     }
+
+    #[test]
+    fn test_quat_rotation() {
+        let q = Quat::from_axis_angle(Vec3::Z, std::f64::consts::FRAC_PI_2);
+        let v = q.rotate(Vec3::X);
+        assert!(approx_eq(v.x, 0.0) && approx_eq(v.y, 1.0) && approx_eq(v.z, 0.0));
+
+        // Composition, matrix form and inverse agree
+        let p = Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0), 0.7);
+        let w = Vec3::new(0.3, -1.2, 2.5);
+        let composed = (q * p).rotate(w);
+        let stepwise = q.rotate(p.rotate(w));
+        let matrix = (q.to_mat3() * p.to_mat3()) * w;
+        assert!((composed - stepwise).length() < EPSILON && (composed - matrix).length() < EPSILON);
+        assert!((p.conjugate().rotate(p.rotate(w)) - w).length() < EPSILON);
+        assert!(approx_eq(p.to_mat3().determinant(), 1.0));
+    }
+
+    #[test]
+    fn test_rotation_arc() {
+        let to = Vec3::new(0.0, -0.4, 0.9).normalize();
+        for from in [Vec3::Z, to, -to, Vec3::X] {
+            let q = Quat::from_rotation_arc(from, to);
+            assert!((q.rotate(from) - to).length() < EPSILON);
+        }
+    }
 }
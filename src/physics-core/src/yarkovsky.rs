@@ -0,0 +1,234 @@
+//! Yarkovsky drift and YORP spin evolution of asteroids
+//!
+//! Sunlight absorbed by a rotating asteroid is re-emitted with a thermal
+//! lag, and the recoil of that emission is a small force. In the linear
+//! theory for bodies much larger than the thermal skin depth (Vokrouhlický
+//! 1998, 1999) a surface forced at frequency ω responds with amplitude E
+//! and phase lag δ given by
+//!
+//!   E e^{−iδ} = 1 / (1 + (1 + i) Θ/2),   Θ = Γ √ω / (ε σ T*³)
+//!
+//! where Γ is the thermal inertia and T* the subsolar temperature,
+//! ε σ T*⁴ = α F. With Φ = π R² F / (m c) and κ = 4/9 α Φ:
+//!
+//! - the diurnal term turns the subsolar direction projected on the
+//!   equator, r_eq, by δ about the spin axis ŝ:
+//!   f = κ E (cos δ r_eq + sin δ ŝ × r_eq)
+//! - the seasonal term, at the orbital frequency n, lags the spin-axis
+//!   component of r̂ by a quarter orbit scaled by δ:
+//!   f = κ E (cos δ (r̂·ŝ) − sin δ (t̂·ŝ)) ŝ
+//!
+//! Averaged over a circular orbit these give the usual
+//! da/dt = −8/9 αΦ/n W cos γ (diurnal) and 4/9 αΦ/n W sin²γ (seasonal),
+//! with W = −E sin δ and γ the obliquity. Prograde rotators drift outward
+//! and retrograde ones inward.
+//!
+//! The spin axis is the body's pole, `Body::spin_axis()` (`pole_ra`/
+//! `pole_dec`), the same axis rotation, tides and precession use; a
+//! negative `rotation_rate` flips it. The obliquity γ is measured from it
+//! against the current orbit normal. The mass is taken from `bulk_density`
+//! when set.
+//!
+//! YORP is the torque from the same emission. It is modelled with a
+//! dimensionless efficiency Y (`yorp_coefficient`) and torque scale
+//! τ = Y F R³ / c:
+//!
+//!   dω/dt = τ cos 2γ / I,   dγ/dt = −τ sin 2γ / (2 I ω)
+//!
+//! so for Y > 0 bodies near the poles spin up and their obliquity is
+//! driven to 0° or 180°, and for Y < 0 they spin down toward 90°. The
+//! axis is turned toward or away from the orbit normal and written back
+//! with `Body::set_spin_axis`; `axial_tilt` is kept equal to γ.
+
+use crate::body::{Body, BodyType};
+use crate::constants::{C, DEFAULT_THERMAL_INERTIA, G, STEFAN_BOLTZMANN};
+use crate::radiation::brightest_source;
+use crate::tides::moment_of_inertia;
+use crate::vector::Vec3;
+
+/// Thermal emissivity ε of asteroid surfaces
+pub const THERMAL_EMISSIVITY: f64 = 0.9;
+
+/// Whether Yarkovsky and YORP apply to `body`
+pub fn feels_yarkovsky(body: &Body) -> bool {
+    body.is_active && body.body_type == BodyType::Asteroid && body.radius > 0.0
+}
+
+/// Amplitude E and phase lag δ of the surface temperature response for
+/// thermal parameter Θ
+pub fn thermal_response(theta: f64) -> (f64, f64) {
+    let (re, im) = (1.0 + 0.5 * theta, 0.5 * theta);
+    (1.0 / re.hypot(im), im.atan2(re))
+}
+
+/// Direction of the spin angular velocity of `body`: its pole, flipped
+/// for retrograde `rotation_rate`
+pub fn thermal_spin_axis(body: &Body) -> Vec3 {
+    let axis = body.spin_axis();
+    if body.rotation_rate < 0.0 { -axis } else { axis }
+}
+
+/// Mass of `body` from its bulk density, or its mass if it has none
+fn thermal_mass(body: &Body) -> f64 {
+    if body.bulk_density > 0.0 {
+        4.0 / 3.0 * std::f64::consts::PI * body.radius.powi(3) * body.bulk_density
+    } else {
+        body.mass
+    }
+}
+
+/// Yarkovsky acceleration on `body` lit by `star`
+pub fn yarkovsky_acceleration(body: &Body, star: &Body) -> Vec3 {
+    let r = body.position - star.position;
+    let v = body.velocity - star.velocity;
+    let (mass, distance) = (thermal_mass(body), r.length());
+    let normal = r.cross(v).normalize();
+    if mass <= 0.0 || distance == 0.0 || normal == Vec3::ZERO {
+        return Vec3::ZERO;
+    }
+    let radial = r / distance;
+    let transverse = normal.cross(radial);
+    let spin = thermal_spin_axis(body);
+
+    let flux = star.luminosity / (4.0 * std::f64::consts::PI * distance * distance);
+    let absorptivity = 1.0 - body.albedo;
+    let kappa = 4.0 / 9.0 * absorptivity * std::f64::consts::PI * body.radius * body.radius * flux / (mass * C);
+    let inertia = if body.thermal_inertia > 0.0 { body.thermal_inertia } else { DEFAULT_THERMAL_INERTIA };
+    let subsolar_temperature = (absorptivity * flux / (THERMAL_EMISSIVITY * STEFAN_BOLTZMANN)).powf(0.25);
+    let theta = |omega: f64| inertia * omega.sqrt() / (THERMAL_EMISSIVITY * STEFAN_BOLTZMANN * subsolar_temperature.powi(3));
+
+    let mut acc = Vec3::ZERO;
+    let omega = body.rotation_rate.abs();
+    if omega > 0.0 {
+        let (amplitude, lag) = thermal_response(theta(omega));
+        let equatorial = radial - spin * radial.dot(spin);
+        acc += (equatorial * lag.cos() + spin.cross(equatorial) * lag.sin()) * (kappa * amplitude);
+    }
+    let mu = G * (star.mass + body.mass);
+    let a = 1.0 / (2.0 / distance - v.length_squared() / mu);
+    if a > 0.0 {
+        let (amplitude, lag) = thermal_response(theta((mu / a.powi(3)).sqrt()));
+        let lagged = lag.cos() * radial.dot(spin) - lag.sin() * transverse.dot(spin);
+        acc += spin * (kappa * amplitude * lagged);
+    }
+    acc
+}
+
+/// Luminous bodies that can drive thermal forces
+fn light_sources(bodies: &[Body]) -> Vec<usize> {
+    (0..bodies.len())
+        .filter(|&i| bodies[i].is_active && bodies[i].luminosity > 0.0)
+        .collect()
+}
+
+/// Add the Yarkovsky acceleration of every asteroid, driven by its
+/// brightest star
+pub fn apply_yarkovsky(bodies: &mut [Body]) {
+    let sources = light_sources(bodies);
+    if sources.is_empty() {
+        return;
+    }
+    for i in 0..bodies.len() {
+        if !feels_yarkovsky(&bodies[i]) || !bodies[i].feels_gravity {
+            continue;
+        }
+        if let Some(s) = brightest_source(bodies, &sources, i) {
+            let acc = yarkovsky_acceleration(&bodies[i], &bodies[s]);
+            bodies[i].acceleration += acc;
+        }
+    }
+}
+
+/// Advance `rotation_rate` and the spin axis of every asteroid with a YORP
+/// coefficient by `dt`
+pub fn apply_yorp(bodies: &mut [Body], dt: f64) {
+    let sources = light_sources(bodies);
+    for i in 0..bodies.len() {
+        let body = &bodies[i];
+        if !feels_yarkovsky(body) || body.yorp_coefficient == 0.0 {
+            continue;
+        }
+        let Some(s) = brightest_source(bodies, &sources, i) else {
+            continue;
+        };
+        let inertia = moment_of_inertia(body);
+        let star = &bodies[s];
+        let normal = (body.position - star.position).cross(body.velocity - star.velocity).normalize();
+        if inertia <= 0.0 || normal == Vec3::ZERO {
+            continue;
+        }
+        let flux = star.luminosity / (4.0 * std::f64::consts::PI * star.position.distance_squared(body.position));
+        let rate = body.yorp_coefficient * flux * body.radius.powi(3) / (C * inertia);
+        let spin = thermal_spin_axis(body);
+        let omega = body.rotation_rate.abs();
+        let tilt = spin.dot(normal).clamp(-1.0, 1.0).acos();
+
+        let body = &mut bodies[i];
+        // Away from the normal within the plane of normal and axis
+        let lean = spin - normal * spin.dot(normal);
+        if omega > 0.0 && lean.length_squared() > 1e-24 {
+            let turned = (tilt - rate * (2.0 * tilt).sin() / (2.0 * omega) * dt).clamp(0.0, std::f64::consts::PI);
+            let axis = normal * turned.cos() + lean.normalize() * turned.sin();
+            body.set_spin_axis(if body.rotation_rate < 0.0 { -axis } else { axis });
+            body.axial_tilt = turned;
+        } else {
+            body.axial_tilt = tilt;
+        }
+        let spun = (omega + rate * (2.0 * tilt).cos() * dt).max(0.0);
+        body.rotation_rate = if body.rotation_rate < 0.0 { -spun } else { spun };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+
+    fn asteroid(tilt: f64) -> (Body, Body) {
+        let mut sun = Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO);
+        sun.luminosity = L_SUN;
+        let a = 2.5 * AU;
+        let mut rock = Body::new(1, "Rock", BodyType::Asteroid, 0.0, 500.0, Vec3::new(a, 0.0, 0.0), Vec3::new(0.0, (G * M_SUN / a).sqrt(), 0.0));
+        rock.bulk_density = 2500.0;
+        rock.albedo = 0.1;
+        rock.rotation_rate = 2.0 * std::f64::consts::PI / (6.0 * 3600.0);
+        rock.axial_tilt = tilt;
+        rock.set_spin_axis(Vec3::new(tilt.sin(), 0.0, tilt.cos()));
+        (sun, rock)
+    }
+
+    #[test]
+    fn test_diurnal_drift_follows_spin_sense() {
+        let (sun, prograde) = asteroid(0.0);
+        let (_, retrograde) = asteroid(std::f64::consts::PI);
+        let forward = yarkovsky_acceleration(&prograde, &sun);
+        let backward = yarkovsky_acceleration(&retrograde, &sun);
+        assert!(forward.y > 0.0 && backward.y < 0.0);
+        assert!((forward.y + backward.y).abs() < 1e-12 * forward.y);
+        // Zero obliquity: no seasonal force out of the orbital plane
+        assert!(forward.z.abs() < 1e-12 * forward.y);
+    }
+
+    #[test]
+    fn test_yorp_turns_pole() {
+        let (sun, rock) = asteroid(0.3);
+        let mut bodies = vec![sun, rock];
+        bodies[1].mass = 4.0 / 3.0 * std::f64::consts::PI * 500.0_f64.powi(3) * 2500.0;
+        bodies[1].yorp_coefficient = 0.01;
+        apply_yorp(&mut bodies, 1.0e9);
+        let rock = &bodies[1];
+        let obliquity = rock.spin_axis().dot(Vec3::Z).acos();
+        assert!(obliquity < 0.3);
+        assert!((rock.axial_tilt - obliquity).abs() < 1e-9);
+        // Uprighting stays in the plane of the normal and the old axis
+        assert!(rock.spin_axis().y.abs() < 1e-9 && rock.spin_axis().x > 0.0);
+    }
+
+    #[test]
+    fn test_response_limits() {
+        assert_eq!(thermal_response(0.0), (1.0, 0.0));
+        let (amplitude, lag) = thermal_response(1.0e6);
+        assert!(amplitude < 1e-5);
+        assert!((lag - std::f64::consts::FRAC_PI_4).abs() < 1e-5);
+    }
+}
@@ -33,6 +33,7 @@ fn test_barnes_hut_high_n_accuracy() {
     let config = ForceConfig {
         softening: DEFAULT_SOFTENING,
         barnes_hut_theta: 0.5,
+        ..ForceConfig::default()
     };
     
     compute_accelerations_direct(&mut bodies_direct, &config);
@@ -72,6 +73,7 @@ fn test_octree_degenerate_same_position() {
     let config = ForceConfig {
         softening: DEFAULT_SOFTENING,
         barnes_hut_theta: 0.5,
+        ..ForceConfig::default()
     };
     
     // Should not infinite loop or panic
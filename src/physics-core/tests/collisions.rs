@@ -0,0 +1,209 @@
+use physics_core::collision::CollisionOutcome;
+use physics_core::prelude::*;
+
+/// Two small rocks on a head-on course along x (gravity is negligible)
+fn head_on(sim: &mut Simulation, body_type: BodyType, speed: f64, gap: f64) -> (BodyId, BodyId) {
+    let a = sim.add_body(Body::new(
+        0,
+        "A",
+        body_type,
+        1e6,
+        10.0,
+        Vec3::new(-gap / 2.0, 0.0, 0.0),
+        Vec3::new(speed, 0.0, 0.0),
+    ));
+    let b = sim.add_body(Body::new(
+        0,
+        "B",
+        body_type,
+        1e6,
+        10.0,
+        Vec3::new(gap / 2.0, 0.0, 0.0),
+        Vec3::new(-speed, 0.0, 0.0),
+    ));
+    (a, b)
+}
+
+#[test]
+fn test_comet_merges_into_sun() {
+    let mut sim = Simulation::new(1);
+    sim.set_collisions_enabled(true);
+    sim.set_dt(60.0);
+    let sun = sim.add_star("Sun", M_SUN, R_SUN);
+    let comet = sim.add_body(Body::new(
+        0,
+        "Comet",
+        BodyType::Comet,
+        2.2e14,
+        5.5e3,
+        Vec3::new(R_SUN + 1e6, 0.0, 0.0),
+        Vec3::new(-6e5, 1e3, 0.0),
+    ));
+
+    let p0 = sim.total_momentum();
+    let m0: f64 = sim.bodies().iter().map(|b| b.mass).sum();
+    sim.step_n(5);
+
+    assert_eq!(sim.body_count(), 1);
+    assert!(!sim.get_body(comet).unwrap().is_active);
+    let survivor = sim.get_body(sun).unwrap();
+    assert!((survivor.mass - m0).abs() <= 1e-15 * m0);
+
+    let dp = (sim.total_momentum() - p0).length();
+    assert!(dp <= 1e-9 * 2.2e14 * 6e5, "Momentum not conserved: {}", dp);
+
+    let events = sim.take_collision_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].outcome, "merge");
+    assert_eq!(events[0].body_ids, vec![sun, comet]);
+    assert!(sim.take_collision_events().is_empty());
+}
+
+#[test]
+fn test_swept_detection_prevents_tunnelling() {
+    // 10 km/s each way with a 60 s tick: the bodies cross 1200 km per tick
+    // while being only 20 m across, so sampled positions never overlap.
+    let mut sim = Simulation::new(2);
+    sim.set_collisions_enabled(true);
+    sim.set_dt(60.0);
+    let (a, b) = head_on(&mut sim, BodyType::Asteroid, 1e4, 1e5);
+
+    sim.step();
+
+    let events = sim.take_collision_events();
+    assert_eq!(events.len(), 1);
+    assert!((events[0].time - 5.0).abs() < 1e-3, "contact time {}", events[0].time);
+    assert_eq!(sim.body_count(), 1);
+
+    // Equal masses meeting head-on come to rest at the midpoint
+    let survivor = sim.get_body(a).or(sim.get_body(b)).filter(|s| s.is_active).unwrap();
+    assert!(survivor.velocity.length() < 1e-6);
+    assert!(survivor.position.length() < 1e-3);
+}
+
+#[test]
+fn test_bounce_with_restitution() {
+    let mut sim = Simulation::new(3);
+    sim.set_collisions_enabled(true);
+    sim.set_collision_outcome(BodyType::Asteroid, CollisionOutcome::Bounce);
+    sim.set_collision_restitution(0.5);
+    sim.set_dt(1.0);
+    let (a, b) = head_on(&mut sim, BodyType::Asteroid, 1.0, 21.0);
+
+    sim.step();
+
+    let (a, b) = (sim.get_body(a).unwrap(), sim.get_body(b).unwrap());
+    assert!(a.is_active && b.is_active);
+    assert!((a.velocity.x + 0.5).abs() < 1e-6);
+    assert!((b.velocity.x - 0.5).abs() < 1e-6);
+    assert!(b.position.x - a.position.x >= 20.0);
+    assert_eq!(sim.take_collision_events()[0].outcome, "bounce");
+}
+
+#[test]
+fn test_ignored_and_disabled_pass_through() {
+    let mut sim = Simulation::new(4);
+    sim.set_collisions_enabled(true);
+    sim.set_dt(60.0);
+    head_on(&mut sim, BodyType::TestParticle, 1e4, 1e5);
+    sim.step();
+    assert_eq!(sim.body_count(), 2);
+    assert!(sim.take_collision_events().is_empty());
+
+    let mut sim = Simulation::new(5);
+    sim.set_dt(60.0);
+    head_on(&mut sim, BodyType::Asteroid, 1e4, 1e5);
+    sim.step();
+    assert_eq!(sim.body_count(), 2);
+    assert!(sim.take_collision_events().is_empty());
+}
+
+#[test]
+fn test_collision_events_in_snapshot_metadata() {
+    let mut sim = Simulation::new(6);
+    sim.set_collisions_enabled(true);
+    sim.set_dt(60.0);
+    head_on(&mut sim, BodyType::Asteroid, 1e4, 1e5);
+    sim.step();
+
+    let snapshot = Snapshot::from_json(&sim.to_json().unwrap()).unwrap();
+    let events = snapshot.metadata.and_then(|m| m.collision_events).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].outcome, "merge");
+}
+
+/// Two 10 km rubble asteroids of 2000 kg/m³ approaching head-on at a
+/// relative speed of `speed`, with fragmentation enabled for asteroids
+fn rubble_piles(speed: f64) -> Simulation {
+    let mut sim = Simulation::new(7);
+    sim.set_collisions_enabled(true);
+    sim.set_collision_outcome(BodyType::Asteroid, CollisionOutcome::Fragment);
+    sim.set_dt(10.0);
+    let mass = 4.0 / 3.0 * std::f64::consts::PI * 1.0e12 * 2000.0;
+    for (k, sign) in [-1.0, 1.0].into_iter().enumerate() {
+        let mut rock = Body::new(0, "Rock", BodyType::Asteroid, mass, 1.0e4, Vec3::new(-sign * 1.05e4, 0.0, 0.0), Vec3::new(sign * speed / 2.0, 0.0, 0.0));
+        rock.seed = 100 + k as u64;
+        sim.add_body(rock);
+    }
+    sim
+}
+
+#[test]
+fn test_fast_impact_sheds_debris() {
+    let mut sim = rubble_piles(20.0);
+    let m0: f64 = sim.bodies().iter().map(|b| b.mass).sum();
+    let p0 = sim.total_momentum();
+    sim.step_n(20);
+
+    let snapshot = Snapshot::from_json(&sim.to_json().unwrap()).unwrap();
+    let events = snapshot.metadata.and_then(|m| m.collision_events).unwrap();
+    assert_eq!(events[0].fragment_ids.len(), 8);
+
+    let events = sim.take_collision_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].outcome, "partial_accretion");
+    assert_eq!(events[0].body_ids, vec![0, 1]);
+    assert_eq!(events[0].fragment_ids.len(), 8);
+    assert!(!sim.get_body(1).unwrap().is_active);
+
+    let remnant = sim.get_body(0).unwrap();
+    assert!(remnant.mass > m0 / 2.0 && remnant.mass < m0);
+    let mass: f64 = sim.bodies().iter().filter(|b| b.is_active).map(|b| b.mass).sum();
+    assert!((mass / m0 - 1.0).abs() < 1e-14);
+    assert!((sim.total_momentum() - p0).length() < 1e-9 * m0 * 10.0);
+
+    // Reproducible for every peer
+    let mut again = rubble_piles(20.0);
+    again.step_n(20);
+    assert!(sim.bodies().iter().zip(again.bodies()).all(|(a, b)| a.position == b.position && a.mass == b.mass));
+}
+
+#[test]
+fn test_slow_impact_merges() {
+    // 5 m/s is below the 10.6 m/s mutual escape speed
+    let mut sim = rubble_piles(5.0);
+    sim.step_n(200);
+    let events = sim.take_collision_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].outcome, "merge");
+    assert!(events[0].fragment_ids.is_empty());
+    assert_eq!(sim.body_count(), 1);
+}
+
+#[test]
+fn test_asteroid_strike_on_planet_accretes() {
+    let mut sim = Simulation::new(8);
+    sim.set_collisions_enabled(true);
+    sim.set_collision_outcome(BodyType::Asteroid, CollisionOutcome::Fragment);
+    sim.set_dt(1.0);
+    let earth = sim.add_body(Body::new(0, "Earth", BodyType::Planet, M_EARTH, R_EARTH, Vec3::ZERO, Vec3::ZERO));
+    let rock = sim.add_body(Body::new(0, "Impactor", BodyType::Asteroid, 1.0e12, 500.0, Vec3::new(R_EARTH + 1.0e5, 0.0, 0.0), Vec3::new(-2.0e4, 0.0, 0.0)));
+    sim.step_n(10);
+
+    // The ejecta that escape a planet are negligible next to the impactor
+    let events = sim.take_collision_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].outcome, "merge");
+    assert_eq!(events[0].body_ids, vec![earth, rock]);
+    assert_eq!(sim.body_count(), 1);
+}
@@ -0,0 +1,48 @@
+//! Fixtures shared by the force-model integration tests. Each test binary
+//! uses only some of them, hence the per-item `allow(dead_code)`.
+
+use physics_core::prelude::*;
+use physics_core::presets::OrbitalElements;
+
+/// Integrate `sim` with IAS15, one substep per tick of `dt` seconds
+#[allow(dead_code)]
+pub fn use_ias15(sim: &mut Simulation, dt: f64) {
+    sim.set_integrator(IntegratorType::Ias15);
+    sim.set_dt(dt);
+    sim.set_substeps(1);
+}
+
+/// Osculating elements of body `index` about body `primary`, with
+/// μ = G (m₁ + m₂)
+#[allow(dead_code)]
+pub fn elements_about(sim: &Simulation, index: usize, primary: usize) -> OrbitalElements {
+    let (primary, body) = (&sim.bodies()[primary], &sim.bodies()[index]);
+    let mu = G * (primary.mass + body.mass);
+    OrbitalElements::from_cartesian(body.position - primary.position, body.velocity - primary.velocity, mu)
+}
+
+/// Osculating semi-major axis of body `index` about body `primary`
+#[allow(dead_code)]
+pub fn semi_major_axis(sim: &Simulation, index: usize, primary: usize) -> f64 {
+    elements_about(sim, index, primary).semi_major_axis
+}
+
+/// Build the system with and without the force under test (`build(true)`
+/// and `build(false)`) and step both `ticks` ticks
+#[allow(dead_code)]
+pub fn run_with_and_without(build: impl Fn(bool) -> Simulation, ticks: u64) -> (Simulation, Simulation) {
+    let (mut sim, mut reference) = (build(true), build(false));
+    sim.step_n(ticks);
+    reference.step_n(ticks);
+    (sim, reference)
+}
+
+/// Least-squares slope of the (t, y) samples in `points`
+#[allow(dead_code)]
+pub fn least_squares_slope(points: &[(f64, f64)]) -> f64 {
+    let n = points.len() as f64;
+    let (mean_t, mean_y) = points.iter().fold((0.0, 0.0), |(t, y), p| (t + p.0 / n, y + p.1 / n));
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_t) * (p.1 - mean_y)).sum();
+    let var: f64 = points.iter().map(|p| (p.0 - mean_t).powi(2)).sum();
+    cov / var
+}
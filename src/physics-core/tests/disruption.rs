@@ -0,0 +1,80 @@
+use physics_core::force::compute_total_momentum;
+use physics_core::prelude::*;
+
+/// Saturn with a 100 km icy moonlet on a circular orbit of radius `a`
+fn saturn_and_moonlet(a: f64, disruption: bool) -> Simulation {
+    let mut sim = Simulation::new(42);
+    sim.add_body(Body::new(0, "Saturn", BodyType::Planet, 5.683e26, 5.8232e7, Vec3::ZERO, Vec3::ZERO));
+    let mass = 4.0 / 3.0 * std::f64::consts::PI * 1.0e15 * 900.0;
+    let speed = (G * (5.683e26 + mass) / a).sqrt();
+    let mut moon = Body::new(0, "Moonlet", BodyType::Moon, mass, 1.0e5, Vec3::new(a, 0.0, 0.0), Vec3::new(0.0, speed, 0.0));
+    moon.seed = 7;
+    sim.add_body(moon);
+    sim.set_disruption_enabled(disruption);
+    sim.set_disruption_fragments(32);
+    sim.set_dt(120.0);
+    sim.set_substeps(1);
+    sim
+}
+
+fn fragments(sim: &Simulation) -> Vec<&Body> {
+    sim.bodies().iter().filter(|b| b.is_active && b.is_fragment).collect()
+}
+
+#[test]
+fn test_moonlet_inside_roche_limit_breaks_up() {
+    // Roche limit 2.456 R (687/900)^⅓ ≈ 1.31e8 m
+    let mut sim = saturn_and_moonlet(1.2e8, true);
+    let mass0: f64 = sim.bodies().iter().map(|b| b.mass).sum();
+    let momentum0 = compute_total_momentum(sim.bodies());
+    sim.step();
+
+    let events = sim.take_disruption_events();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!((event.body_id, event.primary_id), (1, 0));
+    assert!(event.distance < event.roche_limit && event.roche_limit > 1.3e8);
+    assert_eq!(event.fragment_ids.len(), 32);
+    assert!(!sim.get_body(1).unwrap().is_active);
+    assert_eq!(sim.body_count(), 33);
+
+    let mass: f64 = sim.bodies().iter().filter(|b| b.is_active).map(|b| b.mass).sum();
+    let momentum = compute_total_momentum(sim.bodies());
+    assert!((mass / mass0 - 1.0).abs() < 1e-14);
+    assert!((momentum - momentum0).length() < 1e-9 * (mass0 * 1.0e4));
+
+    // Same seed, same swarm
+    let mut again = saturn_and_moonlet(1.2e8, true);
+    again.step();
+    assert!(fragments(&sim).iter().zip(fragments(&again)).all(|(a, b)| a.position == b.position));
+}
+
+#[test]
+fn test_moonlet_outside_roche_limit_survives() {
+    let mut sim = saturn_and_moonlet(1.4e8, true);
+    sim.step_n(100);
+    assert!(sim.take_disruption_events().is_empty());
+    assert_eq!(sim.body_count(), 2);
+}
+
+#[test]
+fn test_fragments_shear_into_ring() {
+    let mut sim = saturn_and_moonlet(1.2e8, true);
+    sim.step();
+    // About 100 orbits of 11.8 h
+    sim.step_n(35_000);
+    assert_eq!(sim.take_disruption_events().len(), 1, "fragments must not break up again");
+
+    let swarm = fragments(&sim);
+    assert_eq!(swarm.len(), 32);
+    let mut angles: Vec<f64> = swarm.iter().map(|f| f.position.y.atan2(f.position.x)).collect();
+    angles.sort_by(f64::total_cmp);
+    let gaps = angles.windows(2).map(|w| w[1] - w[0]).chain([angles[0] + std::f64::consts::TAU - angles[31]]);
+    let widest = gaps.fold(0.0, f64::max);
+    println!("widest azimuthal gap {:.1}°", widest.to_degrees());
+    assert!(widest < 60f64.to_radians());
+    for f in &swarm {
+        let r = f.position.length();
+        assert!(r > 1.1e8 && r < 1.3e8 && f.position.z.abs() < 1.0e6, "fragment at r = {r}, z = {}", f.position.z);
+    }
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::{run_with_and_without, semi_major_axis, use_ias15};
+use physics_core::prelude::*;
+use physics_core::presets::create_sun_earth_moon;
+
+const EARTH: usize = 1;
+
+/// Sun-Earth-Moon with a 1 t spacecraft (10 m², C_d 2.2) on a circular
+/// equatorial orbit `altitude` above the Earth
+fn satellite_at(altitude: f64, drag: bool) -> (Simulation, usize) {
+    let mut sim = create_sun_earth_moon(42);
+    let earth = sim.bodies()[EARTH].clone();
+    assert_eq!(earth.name, "Earth");
+    let r = earth.radius + altitude;
+    // Circular speed including the J2 term of the rotating Earth
+    let mu = G * earth.mass;
+    let speed = (mu / r * (1.0 + 1.5 * earth.j2 * (earth.radius / r).powi(2))).sqrt();
+    let mut probe = Body::new(
+        0, "Probe", BodyType::Spacecraft, 1000.0, 1.0,
+        earth.position + Vec3::new(r, 0.0, 0.0),
+        earth.velocity + Vec3::new(0.0, speed, 0.0),
+    );
+    probe.drag_coefficient = 2.2;
+    probe.drag_area = 10.0;
+    sim.add_body(probe);
+    sim.set_atmospheric_drag(drag);
+    use_ias15(&mut sim, 10.0);
+    let index = sim.bodies().len() - 1;
+    (sim, index)
+}
+
+fn altitude(sim: &Simulation, probe: usize) -> f64 {
+    let earth = &sim.bodies()[EARTH];
+    (sim.bodies()[probe].position - earth.position).length() - earth.radius
+}
+
+#[test]
+fn test_low_orbit_decay_rate() {
+    let (start, probe) = satellite_at(200_000.0, true);
+    let period = 2.0 * std::f64::consts::PI * (semi_major_axis(&start, probe, EARTH).powi(3) / (G * M_EARTH)).sqrt();
+    let ticks = (period / 10.0).round() as u64;
+    let start_altitude = altitude(&start, probe);
+    let (sim, reference) = run_with_and_without(|drag| satellite_at(200_000.0, drag).0, ticks);
+    let measured = semi_major_axis(&sim, probe, EARTH) - semi_major_axis(&reference, probe, EARTH);
+
+    // da/dt = −ρ (C_d A/m) √(μa) (1 − ωa/v)² for a circular prograde orbit
+    // in co-rotating air, at the mean altitude of the orbit
+    let earth = &sim.bodies()[EARTH];
+    let atmosphere = earth.atmosphere.unwrap();
+    let mean_altitude = 0.5 * (start_altitude + altitude(&sim, probe));
+    let density = atmosphere.surface_density * (-mean_altitude / atmosphere.scale_height).exp();
+    let a = earth.radius + mean_altitude;
+    let speed = (G * M_EARTH / a).sqrt();
+    let corotation = 1.0 - earth.rotation_rate * a / speed;
+    let predicted = -density * (2.2 * 10.0 / 1000.0) * (G * M_EARTH * a).sqrt() * corotation * corotation * ticks as f64 * 10.0;
+    println!("decay over one orbit: predicted {:.1} m, measured {:.1} m", predicted, measured);
+    assert!((measured / predicted - 1.0).abs() < 0.02);
+}
+
+#[test]
+fn test_reentry() {
+    // From 120 km the single-exponential atmosphere brings the probe down
+    // within the first orbit
+    let (mut sim, probe) = satellite_at(120_000.0, true);
+    let mut minutes = 0;
+    while altitude(&sim, probe) > 50_000.0 {
+        assert!(minutes < 90, "still at {:.0} m after 90 min", altitude(&sim, probe));
+        sim.step_n(6);
+        minutes += 1;
+    }
+    println!("reentry after {} min", minutes);
+
+    // Without drag the orbit stays put
+    let (mut reference, _) = satellite_at(120_000.0, false);
+    reference.step_n(6 * minutes);
+    assert!(altitude(&reference, probe) > 110_000.0);
+}
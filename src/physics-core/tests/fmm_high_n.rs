@@ -0,0 +1,149 @@
+use physics_core::prelude::*;
+use physics_core::fmm::compute_accelerations_fmm;
+use physics_core::force::{compute_accelerations_direct, ForceConfig};
+use physics_core::octree::compute_accelerations_barnes_hut;
+use std::time::Instant;
+
+fn build_disk(count: usize) -> Simulation {
+    let mut sim = Simulation::new(42);
+    let mut rng = Pcg32::new(12345);
+
+    sim.add_star("SMBH", 1e36, 1e9);
+
+    for i in 0..count {
+        let name = format!("Body_{}", i);
+        let mass = 1e24 + rng.next_f64() * 1e24;
+        let r = 1e11 + rng.next_f64() * 1e12;
+        let theta = rng.next_f64() * 2.0 * std::f64::consts::PI;
+        let phi = rng.next_f64() * std::f64::consts::PI;
+
+        let id = sim.add_planet(&name, mass, 1e5, 0.0, 0.0);
+        if let Some(body) = sim.get_body_mut(id) {
+            body.position = Vec3::new(
+                r * phi.sin() * theta.cos(),
+                r * phi.sin() * theta.sin(),
+                r * phi.cos(),
+            );
+        }
+    }
+    sim
+}
+
+fn relative_errors(reference: &[Body], approx: &[Body]) -> (f64, f64) {
+    let mut max_err = 0.0f64;
+    let mut mean_err = 0.0;
+    for (a, b) in reference.iter().zip(approx) {
+        let mag = a.acceleration.length();
+        if mag > 0.0 {
+            let err = (a.acceleration - b.acceleration).length() / mag;
+            max_err = max_err.max(err);
+            mean_err += err;
+        }
+    }
+    (max_err, mean_err / reference.len() as f64)
+}
+
+#[test]
+fn test_fmm_high_n_accuracy() {
+    let sim = build_disk(2000);
+    let config = ForceConfig::default();
+
+    let mut bodies_direct = sim.bodies().to_vec();
+    let mut bodies_bh = sim.bodies().to_vec();
+    let mut bodies_fmm = sim.bodies().to_vec();
+    compute_accelerations_direct(&mut bodies_direct, &config);
+    compute_accelerations_barnes_hut(&mut bodies_bh, &config);
+    compute_accelerations_fmm(&mut bodies_fmm, &config);
+
+    let (bh_max, _) = relative_errors(&bodies_direct, &bodies_bh);
+    let (fmm_max, fmm_mean) = relative_errors(&bodies_direct, &bodies_fmm);
+
+    assert!(fmm_mean < 1e-3, "FMM mean error too high: {}", fmm_mean);
+    assert!(fmm_max < 5e-2, "FMM max error too high: {}", fmm_max);
+    assert!(fmm_max < bh_max, "FMM worst case ({}) should beat BH ({}) at default settings", fmm_max, bh_max);
+}
+
+#[test]
+fn test_fmm_beats_barnes_hut_in_uniform_cube() {
+    let mut rng = Pcg32::new(7);
+    let bodies: Vec<Body> = (0..3000)
+        .map(|i| {
+            let position = Vec3::new(rng.next_f64_range(-1e12, 1e12), rng.next_f64_range(-1e12, 1e12), rng.next_f64_range(-1e12, 1e12));
+            Body::new(i, "Body", BodyType::Asteroid, 1e24, 1e5, position, Vec3::ZERO)
+        })
+        .collect();
+    let config = ForceConfig::default();
+
+    let mut bodies_direct = bodies.clone();
+    let mut bodies_bh = bodies.clone();
+    let mut bodies_fmm = bodies;
+    compute_accelerations_direct(&mut bodies_direct, &config);
+    compute_accelerations_barnes_hut(&mut bodies_bh, &config);
+    compute_accelerations_fmm(&mut bodies_fmm, &config);
+
+    let (_, bh_mean) = relative_errors(&bodies_direct, &bodies_bh);
+    let (_, fmm_mean) = relative_errors(&bodies_direct, &bodies_fmm);
+    println!("mean error BH {:.2e}, FMM {:.2e}", bh_mean, fmm_mean);
+    assert!(fmm_mean < 0.5 * bh_mean, "FMM ({}) should beat BH ({}) at default settings", fmm_mean, bh_mean);
+}
+
+/// Fastest of two FMM evaluations on `bodies`, in seconds
+fn fmm_seconds(bodies: &[Body], config: &ForceConfig) -> f64 {
+    (0..2)
+        .map(|_| {
+            let mut copy = bodies.to_vec();
+            let start = Instant::now();
+            compute_accelerations_fmm(&mut copy, config);
+            start.elapsed().as_secs_f64()
+        })
+        .fold(f64::MAX, f64::min)
+}
+
+#[test]
+fn test_fmm_scales_linearly() {
+    // 8× the bodies would cost 64× with pairwise sums; FMM should stay
+    // near 8×
+    let config = ForceConfig::default();
+    let small = fmm_seconds(build_disk(2000).bodies(), &config);
+    let large = fmm_seconds(build_disk(16_000).bodies(), &config);
+    println!("FMM 2000: {:.4}s, 16000: {:.4}s, ratio {:.2}", small, large, large / small);
+    assert!(large / small < 24.0, "FMM cost grew {:.1}× for 8× the bodies", large / small);
+}
+
+#[test]
+fn test_fmm_degenerate_same_position() {
+    let mut sim = Simulation::new(42);
+
+    for i in 0..40 {
+        let name = format!("Degenerate_{}", i);
+        let id = sim.add_planet(&name, 1e20, 1e4, 0.0, 0.0);
+        if let Some(body) = sim.get_body_mut(id) {
+            body.position = Vec3::new(1e10, 1e10, 1e10);
+        }
+    }
+
+    let mut bodies = sim.bodies().to_vec();
+    compute_accelerations_fmm(&mut bodies, &ForceConfig::default());
+
+    for body in bodies {
+        assert!(body.acceleration.is_finite());
+    }
+}
+
+#[test]
+fn test_fmm_simulation_energy() {
+    let mut sim = Simulation::new(7);
+    sim.add_star("Sun", M_SUN, R_SUN);
+    sim.add_planet("Earth", M_EARTH, R_EARTH, AU, 29784.0);
+    sim.add_planet("Mars", 6.39e23, 3.3895e6, 1.524 * AU, 24077.0);
+    sim.add_planet("Jupiter", M_JUPITER, R_JUPITER, 5.2 * AU, 13070.0);
+    sim.set_force_method(ForceMethod::Fmm);
+    sim.set_dt(3600.0);
+
+    let e0 = sim.total_energy();
+    sim.step_n(24 * 30);
+    let e1 = sim.total_energy();
+
+    let drift = ((e1 - e0) / e0).abs();
+    assert!(drift < 1e-6, "Energy drift with FMM: {}", drift);
+}
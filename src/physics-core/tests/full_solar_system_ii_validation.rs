@@ -517,3 +517,69 @@ fn test_barycentric_conservation_10_years() {
     println!("\n✓ All conservation checks passed!");
     println!("========================================\n");
 }
+
+/// Integrate `days` with the given method and tick, returning the final state
+fn run_days(mut sim: Simulation, method: IntegratorType, dt: f64, substeps: u32, days: f64) -> Simulation {
+    sim.set_integrator(method);
+    sim.set_dt(dt);
+    sim.set_substeps(substeps);
+    let steps = (days * 86_400.0 / dt).round() as usize;
+    for _ in 0..steps {
+        sim.step();
+    }
+    sim
+}
+
+/// Test: IAS15 as the accuracy reference for the default Verlet setup
+#[test]
+fn test_ias15_reference_one_year() {
+    let initial = create_full_solar_system_ii(42, true);
+    let initial_energy = initial.total_energy();
+
+    let reference = run_days(create_full_solar_system_ii(42, true), IntegratorType::Ias15, 86_400.0, 1, 365.0);
+    let verlet = run_days(initial, IntegratorType::VelocityVerlet, 3600.0, 4, 365.0);
+
+    let ias15_drift = ((reference.total_energy() - initial_energy) / initial_energy).abs();
+    let verlet_drift = ((verlet.total_energy() - initial_energy) / initial_energy).abs();
+    println!("IAS15 energy drift {:.3e}, Verlet {:.3e}", ias15_drift, verlet_drift);
+    assert!(ias15_drift < 1e-13, "IAS15 energy drift {:.3e} above machine precision", ias15_drift);
+    assert!(ias15_drift < verlet_drift, "IAS15 should beat Verlet on energy");
+
+    // Hourly Verlet stays within 1e-5 AU of the reference for every body
+    for (r, v) in reference.bodies().iter().zip(verlet.bodies()) {
+        let error_au = (r.position - v.position).length() / AU;
+        println!("  {:10} {:.3e} AU", r.name, error_au);
+        assert!(error_au < 1e-5, "{} deviates {:.3e} AU from IAS15", r.name, error_au);
+    }
+}
+
+/// Test: Hermite block steps resolve the Moon inside daily ticks
+#[test]
+fn test_hermite_block_timesteps_one_year() {
+    let initial = create_full_solar_system_ii(42, true);
+    let initial_energy = initial.total_energy();
+
+    let reference = run_days(create_full_solar_system_ii(42, true), IntegratorType::Ias15, 86_400.0, 1, 365.0);
+    let verlet = run_days(create_full_solar_system_ii(42, true), IntegratorType::VelocityVerlet, 86_400.0, 1, 365.0);
+    let hermite = run_days(initial, IntegratorType::Hermite, 86_400.0, 1, 365.0);
+
+    // Tick boundaries land on the same times as every other integrator
+    assert_eq!(hermite.time(), reference.time());
+    assert_eq!(hermite.tick(), reference.tick());
+
+    let hermite_drift = ((hermite.total_energy() - initial_energy) / initial_energy).abs();
+    let verlet_drift = ((verlet.total_energy() - initial_energy) / initial_energy).abs();
+    println!("Hermite energy drift {:.3e}, Verlet {:.3e}", hermite_drift, verlet_drift);
+    assert!(hermite_drift < 1e-10, "Hermite energy drift {:.3e}", hermite_drift);
+    assert!(hermite_drift * 100.0 < verlet_drift, "Hermite should beat Verlet on energy");
+
+    // Daily Verlet loses Mercury and the Moon; per-body steps keep every
+    // body within 1e-5 AU of the reference
+    for ((r, h), v) in reference.bodies().iter().zip(hermite.bodies()).zip(verlet.bodies()) {
+        let hermite_au = (r.position - h.position).length() / AU;
+        let verlet_au = (r.position - v.position).length() / AU;
+        println!("  {:10} hermite {:.3e} AU, verlet {:.3e} AU", r.name, hermite_au, verlet_au);
+        assert!(hermite_au < 1e-5, "{} deviates {:.3e} AU from IAS15", r.name, hermite_au);
+        assert!(hermite_au <= verlet_au, "{}: Hermite {:.3e} vs Verlet {:.3e} AU", r.name, hermite_au, verlet_au);
+    }
+}
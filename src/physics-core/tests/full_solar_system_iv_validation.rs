@@ -105,3 +105,32 @@ fn test_full_solar_system_iv_asteroid_body_types() {
         }
     }
 }
+
+/// IAS15 is the accuracy reference: machine-precision energy, and hourly
+/// Verlet planets stay close to it
+#[test]
+fn test_full_solar_system_iv_ias15_reference() {
+    let days = 10;
+    let initial = create_full_solar_system_iv(42, true);
+    let initial_energy = initial.total_energy();
+
+    let mut reference = create_full_solar_system_iv(42, true);
+    reference.set_integrator(IntegratorType::Ias15);
+    reference.set_dt(86_400.0);
+    reference.step_n(days);
+
+    let mut verlet = initial;
+    verlet.set_dt(3600.0);
+    verlet.step_n(days * 24);
+
+    let drift = ((reference.total_energy() - initial_energy) / initial_energy).abs();
+    assert!(drift < 1e-13, "IAS15 energy drift {:.3e} above machine precision", drift);
+
+    for (r, v) in reference.bodies().iter().zip(verlet.bodies()) {
+        if r.body_type != BodyType::Planet {
+            continue;
+        }
+        let error = (r.position - v.position).length() / r.position.length();
+        assert!(error < 1e-6, "{} deviates {:.3e} (relative) from IAS15", r.name, error);
+    }
+}
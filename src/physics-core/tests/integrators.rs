@@ -1,7 +1,10 @@
 use physics_core::constants::*;
 use physics_core::force::{compute_accelerations_direct, compute_total_energy, ForceConfig};
 use physics_core::integrator::{initialize_accelerations_with, step_with_accel, IntegratorConfig, IntegratorType};
+use physics_core::kepler::kepler_drift;
 use physics_core::octree::compute_accelerations_barnes_hut;
+use physics_core::presets::{create_binary_pulsar, create_integrator_test2, create_integrator_test3};
+use physics_core::snapshot::Snapshot;
 use physics_core::prelude::{Body, BodyType, Pcg32, Simulation, SimulationConfig, ForceMethod, Vec3};
 
 fn build_two_body_system() -> Vec<Body> {
@@ -48,6 +51,193 @@ fn test_integrator_energy_drift_ordering() {
     assert!(leapfrog_drift <= verlet_drift * 3.0, "Leapfrog drift should be comparable to Verlet");
 }
 
+/// Sun plus a planet at perihelion of an e = 0.5, a = 1 AU orbit
+fn build_eccentric_system() -> Vec<Body> {
+    let e = 0.5;
+    let q = AU * (1.0 - e);
+    let v_peri = (G * M_SUN * (1.0 + e) / q).sqrt();
+    vec![
+        Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO),
+        Body::new(1, "Planet", BodyType::Planet, M_EARTH, R_EARTH, Vec3::new(q, 0.0, 0.0), Vec3::new(0.0, v_peri, 0.0)),
+    ]
+}
+
+/// Maximum relative energy error over one orbit of the eccentric system
+fn max_energy_error(method: IntegratorType, steps_per_orbit: usize) -> f64 {
+    let mut bodies = build_eccentric_system();
+    let period = 2.0 * std::f64::consts::PI * (AU.powi(3) / (G * (M_SUN + M_EARTH))).sqrt();
+    let mut config = IntegratorConfig::default();
+    config.dt = period / steps_per_orbit as f64;
+    config.substeps = 1;
+    config.method = method;
+    config.force_config.softening = 0.0;
+
+    initialize_accelerations_with(&mut bodies, &config.force_config, compute_accelerations_direct);
+    let initial_energy = compute_total_energy(&bodies, 0.0);
+    let mut max_error: f64 = 0.0;
+    for _ in 0..steps_per_orbit {
+        step_with_accel(&mut bodies, &config, compute_accelerations_direct);
+        let energy = compute_total_energy(&bodies, 0.0);
+        max_error = max_error.max(((energy - initial_energy) / initial_energy).abs());
+    }
+    max_error
+}
+
+#[test]
+fn test_higher_order_energy_error_vs_verlet() {
+    let verlet = max_energy_error(IntegratorType::VelocityVerlet, 400);
+    for method in [IntegratorType::Yoshida4, IntegratorType::Yoshida6, IntegratorType::ForestRuth, IntegratorType::Pefrl] {
+        let coarse = max_energy_error(method, 400);
+        let fine = max_energy_error(method, 800);
+        assert!(coarse < verlet / 100.0, "{:?} error {} vs Verlet {}", method, coarse, verlet);
+
+        // Halving dt shrinks the error by 2^order
+        let expected = 2f64.powi(method.order() as i32);
+        let ratio = coarse / fine;
+        assert!(ratio > 0.8 * expected && ratio < 1.25 * expected, "{:?} convergence ratio {}", method, ratio);
+    }
+}
+
+#[test]
+fn test_wisdom_holman_two_body_large_steps() {
+    // Ten steps per e = 0.5 orbit: the error is set by the mass ratio, not dt
+    let wh = max_energy_error(IntegratorType::WisdomHolman, 10);
+    let verlet = max_energy_error(IntegratorType::VelocityVerlet, 10);
+    assert!(wh < 10.0 * M_EARTH / M_SUN, "Wisdom-Holman two-body energy error {}", wh);
+    assert!(verlet > 1e3 * wh, "Verlet {} vs Wisdom-Holman {}", verlet, wh);
+}
+
+/// Maximum relative energy error of Sun-Jupiter-Saturn over `years`
+fn jupiter_saturn_energy_error(method: IntegratorType, dt_days: f64, years: f64) -> f64 {
+    let mut sim = create_integrator_test2(1);
+    let mut config = sim.config().clone();
+    config.integrator.method = method;
+    config.integrator.dt = dt_days * 86_400.0;
+    config.integrator.substeps = 1;
+    sim.set_config(config);
+
+    let initial_energy = sim.total_energy();
+    let steps = (years * 365.25 / dt_days) as usize;
+    let mut max_error: f64 = 0.0;
+    for _ in 0..steps {
+        sim.step();
+        max_error = max_error.max(((sim.total_energy() - initial_energy) / initial_energy).abs());
+    }
+    max_error
+}
+
+#[test]
+fn test_wisdom_holman_long_timestep() {
+    // 30-day steps, ~1/140 of Jupiter's period
+    let wh = jupiter_saturn_energy_error(IntegratorType::WisdomHolman, 30.0, 500.0);
+    let verlet = jupiter_saturn_energy_error(IntegratorType::VelocityVerlet, 30.0, 500.0);
+    assert!(wh < 1e-6, "Wisdom-Holman energy error {}", wh);
+    assert!(wh * 20.0 < verlet, "Wisdom-Holman {} vs Verlet {}", wh, verlet);
+}
+
+#[test]
+fn test_ias15_machine_precision() {
+    // Ten ticks per e = 0.5 orbit over 20 orbits: IAS15 subdivides internally
+    let mut bodies = build_eccentric_system();
+    let mu = G * (M_SUN + M_EARTH);
+    let period = 2.0 * std::f64::consts::PI * (AU.powi(3) / mu).sqrt();
+    let mut config = IntegratorConfig::default();
+    config.dt = period / 10.0;
+    config.substeps = 1;
+    config.method = IntegratorType::Ias15;
+    config.force_config.softening = 0.0;
+
+    initialize_accelerations_with(&mut bodies, &config.force_config, compute_accelerations_direct);
+    let initial_energy = compute_total_energy(&bodies, 0.0);
+    let relative = |b: &[Body]| (b[1].position - b[0].position, b[1].velocity - b[0].velocity);
+    let (r0, v0) = relative(&bodies);
+    for _ in 0..200 {
+        step_with_accel(&mut bodies, &config, compute_accelerations_direct);
+    }
+
+    let error = ((compute_total_energy(&bodies, 0.0) - initial_energy) / initial_energy).abs();
+    assert!(error < 1e-13, "IAS15 energy error {}", error);
+
+    // Matches the analytic two-body solution
+    let (expected, _) = kepler_drift(r0, v0, mu, 200.0 * config.dt);
+    let (r, _) = relative(&bodies);
+    assert!((r - expected).length() < 1e-10 * AU, "IAS15 position error {} m", (r - expected).length());
+
+    let js = jupiter_saturn_energy_error(IntegratorType::Ias15, 30.0, 100.0);
+    assert!(js < 1e-13, "IAS15 Jupiter-Saturn energy error {}", js);
+}
+
+#[test]
+fn test_integrator_method_survives_snapshot() {
+    let mut sim = Simulation::new(42);
+    for body in build_eccentric_system() {
+        sim.add_body(body);
+    }
+    sim.set_integrator(IntegratorType::from_name("pefrl").unwrap());
+    sim.step();
+
+    let restored = Simulation::from_json(&sim.to_json().unwrap()).expect("Failed to restore");
+    assert_eq!(restored.config().integrator.method, IntegratorType::Pefrl);
+}
+
+/// Relative energy drift of the binary pulsar over one day of 1-hour ticks
+fn binary_pulsar_energy_error(adaptive: bool) -> f64 {
+    let mut sim = create_binary_pulsar(42);
+    sim.set_dt(3600.0);
+    sim.set_substeps(1);
+    sim.set_adaptive_substeps(adaptive);
+    let e0 = sim.total_energy();
+    sim.step_n(24);
+    ((sim.total_energy() - e0) / e0).abs()
+}
+
+#[test]
+fn test_adaptive_substeps_follow_timescale() {
+    // Same tick length, very different dynamics: a 2.45 h binary and two
+    // Jupiters orbiting on ~12 yr paths
+    let mut pulsar = create_binary_pulsar(42);
+    let mut jupiters = create_integrator_test3(42);
+    for sim in [&mut pulsar, &mut jupiters] {
+        sim.set_dt(3600.0);
+        sim.set_adaptive_substeps(true);
+        sim.step();
+    }
+    assert!(pulsar.last_substeps() >= 64, "pulsar substeps {}", pulsar.last_substeps());
+    assert_eq!(jupiters.last_substeps(), 1);
+
+    let fixed = binary_pulsar_energy_error(false);
+    let adaptive = binary_pulsar_energy_error(true);
+    assert!(adaptive < 1e-3 && adaptive * 1e3 < fixed, "adaptive {} vs fixed {}", adaptive, fixed);
+
+    // The cap bounds the work per tick
+    pulsar.set_adaptive_substep_params(0.02, 16);
+    pulsar.step();
+    assert_eq!(pulsar.last_substeps(), 16);
+}
+
+#[test]
+fn test_adaptive_substeps_deterministic() {
+    let mut sim = create_binary_pulsar(42);
+    sim.set_dt(3600.0);
+    sim.set_adaptive_substeps(true);
+    sim.set_adaptive_substep_params(0.05, 4096);
+    sim.step_n(3);
+
+    // A restored copy picks the same counts and stays bit-identical
+    let mut copy = Simulation::new(0);
+    copy.restore(Snapshot::from_binary(&sim.snapshot().to_binary().unwrap()).unwrap()).expect("Failed to restore");
+    assert!(copy.config().integrator.adaptive.enabled);
+    assert_eq!(copy.config().integrator.adaptive.eta, 0.05);
+    for _ in 0..10 {
+        sim.step();
+        copy.step();
+        assert_eq!(sim.last_substeps(), copy.last_substeps());
+        for (a, b) in sim.bodies().iter().zip(copy.bodies()) {
+            assert_eq!(a.position.to_array().map(f64::to_bits), b.position.to_array().map(f64::to_bits));
+        }
+    }
+}
+
 fn build_random_bodies(seed: u64, count: usize) -> Vec<Body> {
     let mut rng = Pcg32::new(seed);
     let mut bodies = vec![Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO)];
@@ -81,12 +271,14 @@ fn build_simulation(bodies: &[Body], force_method: ForceMethod, barnes_hut_thres
     integrator.force_config = ForceConfig {
         softening: DEFAULT_SOFTENING,
         barnes_hut_theta: 1.0,
+        ..ForceConfig::default()
     };
 
     let config = SimulationConfig {
         integrator,
         force_method,
         barnes_hut_threshold,
+        ..Default::default()
     };
 
     let mut sim = Simulation::with_config(42, config);
@@ -122,6 +314,7 @@ fn test_barnes_hut_accuracy_low_theta() {
     let config = ForceConfig {
         softening: DEFAULT_SOFTENING,
         barnes_hut_theta: 0.5,
+        ..ForceConfig::default()
     };
 
     let mut direct_bodies = bodies.clone();
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{semi_major_axis, use_ias15};
+use physics_core::prelude::*;
+
+/// Sun-like star losing a tenth of its mass over a century, with an Earth
+/// on a circular orbit
+fn windy_star() -> Simulation {
+    let mut sim = Simulation::new(42);
+    let mut star = Body::new(0, "Star", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO);
+    star.mass_loss_rate = 0.1 * M_SUN / (100.0 * SECONDS_PER_YEAR);
+    sim.add_body(star);
+    let speed = (G * (M_SUN + M_EARTH) / AU).sqrt();
+    sim.add_body(Body::new(0, "Earth", BodyType::Planet, M_EARTH, R_EARTH, Vec3::new(AU, 0.0, 0.0), Vec3::new(0.0, speed, 0.0)));
+    use_ias15(&mut sim, SECONDS_PER_DAY);
+    sim
+}
+
+#[test]
+fn test_adiabatic_orbit_expansion() {
+    let mut sim = windy_star();
+    let invariant0 = semi_major_axis(&sim, 1, 0) * (sim.bodies()[0].mass + M_EARTH);
+    let energy0 = sim.total_energy();
+    let gravity0 = sim.bodies()[0].surface_gravity;
+    sim.step_n(36_525);
+
+    // Slow isotropic loss keeps a·M fixed: the orbit has grown by ~11%
+    let star = &sim.bodies()[0];
+    assert!((star.mass / M_SUN - 0.9).abs() < 1e-6, "mass {:.6} M☉", star.mass / M_SUN);
+    assert!((star.surface_gravity / gravity0 - 0.9).abs() < 1e-6);
+    assert!((sim.mass_lost() / M_SUN - 0.1).abs() < 1e-6);
+    let a = semi_major_axis(&sim, 1, 0);
+    let invariant = a * (star.mass + M_EARTH);
+    println!("a = {:.5} AU, a·M drift {:.2e}", a / AU, invariant / invariant0 - 1.0);
+    assert!(a / AU > 1.1);
+    assert!((invariant / invariant0 - 1.0).abs() < 1e-3);
+
+    // The planet is less bound, and the energy budget closes once the
+    // wind's share is counted
+    assert!(sim.total_energy() > energy0);
+    let budget = sim.total_energy() + sim.mass_loss_energy();
+    assert!((budget / energy0 - 1.0).abs() < 1e-6, "budget drift {:.2e}", budget / energy0 - 1.0);
+}
+
+#[test]
+fn test_mass_loss_can_be_disabled() {
+    let mut sim = windy_star();
+    sim.set_mass_loss(MassLossMode::None);
+    let a0 = semi_major_axis(&sim, 1, 0);
+    sim.step_n(3650);
+    assert_eq!(sim.bodies()[0].mass, M_SUN);
+    assert_eq!(sim.mass_lost(), 0.0);
+    assert!((semi_major_axis(&sim, 1, 0) / a0 - 1.0).abs() < 1e-9);
+}
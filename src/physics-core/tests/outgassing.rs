@@ -0,0 +1,59 @@
+mod common;
+
+use common::{elements_about, run_with_and_without, semi_major_axis, use_ias15};
+use physics_core::outgassing::sublimation_g;
+use physics_core::prelude::*;
+use physics_core::presets::create_full_solar_system_iii;
+
+/// Sun and 1P/Halley alone, with the preset's heliocentric state
+fn sun_and_halley(outgassing: bool) -> Simulation {
+    let preset = create_full_solar_system_iii(42, false);
+    let sun = preset.bodies()[0].clone();
+    let halley = preset.bodies().iter().find(|b| b.name == "1P/Halley").unwrap().clone();
+    assert!(halley.nongrav_params[1] > 0.0);
+
+    let mut sim = Simulation::new(42);
+    sim.add_body(sun);
+    sim.add_body(halley);
+    sim.set_outgassing(outgassing);
+    use_ias15(&mut sim, SECONDS_PER_DAY);
+    sim
+}
+
+#[test]
+fn test_halley_period_lengthens() {
+    let start = sun_and_halley(true);
+    let mu = G * (start.bodies()[0].mass + start.bodies()[1].mass);
+    let elements = elements_about(&start, 1, 0);
+    let (a, e) = (elements.semi_major_axis, elements.eccentricity);
+    let period = 2.0 * std::f64::consts::PI * (a.powi(3) / mu).sqrt();
+
+    // Secular Δa per orbit from Gauss's equation: only the transverse term
+    // survives, Δa = ∮ 2a²/h · (p/r) · A2 g(r) · r²/h df
+    let a2 = start.bodies()[1].nongrav_params[1];
+    let p = a * (1.0 - e * e);
+    let h = (mu * p).sqrt();
+    let steps = 100_000;
+    let df = 2.0 * std::f64::consts::PI / steps as f64;
+    let predicted_da: f64 = (0..steps)
+        .map(|i| {
+            let f = -std::f64::consts::PI + (i as f64 + 0.5) * df;
+            let r = p / (1.0 + e * f.cos());
+            2.0 * a * a / h * (p / r) * a2 * sublimation_g(r / AU) * r * r / h * df
+        })
+        .sum();
+
+    // The preset starts Halley near aphelion, so one period covers one
+    // perihelion passage
+    let ticks = (period / SECONDS_PER_DAY).round() as u64;
+    let (sim, reference) = run_with_and_without(sun_and_halley, ticks);
+    let measured_da = semi_major_axis(&sim, 1, 0) - semi_major_axis(&reference, 1, 0);
+    let change_days = 1.5 * period * measured_da / a / SECONDS_PER_DAY;
+    println!(
+        "Δa predicted {:.4e} m, measured {:.4e} m (ΔP = {:+.2} d per orbit)",
+        predicted_da, measured_da, change_days
+    );
+    assert!((measured_da / predicted_da - 1.0).abs() < 0.02);
+    // Halley's orbit lengthens by a few days per revolution
+    assert!(change_days > 2.0 && change_days < 8.0);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::{least_squares_slope, use_ias15};
+use physics_core::prelude::*;
+use physics_core::presets::OrbitalElements;
+use physics_core::radiation::area_to_mass;
+
+/// Black dust grain of radius `size` and density 2 g/cm³
+fn grain(size: f64) -> Body {
+    let mut grain = Body::new(0, "Dust", BodyType::TestParticle, 0.0, size, Vec3::ZERO, Vec3::ZERO);
+    grain.bulk_density = 2000.0;
+    grain.albedo = 0.0;
+    grain
+}
+
+/// Ratio of radiation pressure to solar gravity on `body`
+fn beta(body: &Body) -> f64 {
+    (1.0 + body.albedo) * area_to_mass(body).unwrap() * L_SUN / (4.0 * std::f64::consts::PI * C * G * M_SUN)
+}
+
+/// The Sun plus one body at 1 AU moving at `speed` along +y
+fn sun_and(mut body: Body, speed: f64) -> Simulation {
+    body.position = Vec3::new(AU, 0.0, 0.0);
+    body.velocity = Vec3::new(0.0, speed, 0.0);
+    let mut sim = Simulation::new(42);
+    sim.add_star("Sun", M_SUN, R_SUN);
+    sim.add_body(body);
+    sim.set_radiation(true);
+    use_ias15(&mut sim, SECONDS_PER_DAY);
+    sim
+}
+
+#[test]
+fn test_poynting_robertson_inspiral() {
+    // 10 μm grain on a circular orbit in the reduced gravity (1 − β) GM
+    let dust = grain(1.0e-5);
+    let beta = beta(&dust);
+    assert!((beta - 0.0287).abs() < 0.0005, "beta {}", beta);
+    let mu = (1.0 - beta) * G * M_SUN;
+    let mut sim = sun_and(dust, (mu / AU).sqrt());
+    assert!(sim.bodies()[0].luminosity > 0.0);
+
+    // Osculating a in the reduced potential, sampled daily for a year
+    let semi_major_axis = |sim: &Simulation| {
+        let (sun, dust) = (&sim.bodies()[0], &sim.bodies()[1]);
+        OrbitalElements::from_cartesian(dust.position - sun.position, dust.velocity - sun.velocity, mu).semi_major_axis
+    };
+    let mut points = vec![(sim.time(), semi_major_axis(&sim))];
+    for _ in 0..365 {
+        sim.step();
+        points.push((sim.time(), semi_major_axis(&sim)));
+    }
+    let measured = least_squares_slope(&points);
+
+    // Circular orbit: da/dt = −2 β GM / (c a) (Wyatt & Whipple 1950)
+    let predicted = -2.0 * beta * G * M_SUN / (C * AU);
+    println!("da/dt predicted {:.4} m/s, measured {:.4} m/s", predicted, measured);
+    assert!((measured / predicted - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn test_small_debris_is_blown_out() {
+    // Debris shed at the parent's circular speed escapes when β > 1/2
+    let v_circular = (G * M_SUN / AU).sqrt();
+    for (size, escapes) in [(4.0e-7, true), (1.0e-6, false), (1.0e-4, false)] {
+        let dust = grain(size);
+        let beta = beta(&dust);
+        let mut sim = sun_and(dust, v_circular);
+        sim.step_n(2 * 365);
+        let distance = (sim.bodies()[1].position - sim.bodies()[0].position).length() / AU;
+        println!("{:.0e} m grain, β = {:.3}: {:.2} AU after 2 years", size, beta, distance);
+        assert_eq!(beta > 0.5, escapes);
+        assert_eq!(distance > 3.0, escapes, "β = {:.3} grain at {:.2} AU", beta, distance);
+    }
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use common::{elements_about, least_squares_slope, semi_major_axis, use_ias15};
+use physics_core::prelude::*;
+use physics_core::presets::create_binary_pulsar;
+
+/// Sun and Mercury, Mercury starting at perihelion
+fn sun_mercury(mode: RelativityMode, method: IntegratorType, dt: f64) -> Simulation {
+    let (a, e) = (0.387_098 * AU, 0.205_630);
+    let m_mercury = 3.3011e23;
+    let perihelion = a * (1.0 - e);
+    let speed = (G * (M_SUN + m_mercury) * (1.0 + e) / perihelion).sqrt();
+
+    let mut sim = Simulation::new(42);
+    sim.add_star("Sun", M_SUN, R_SUN);
+    sim.add_planet("Mercury", m_mercury, 2.4397e6, perihelion, speed);
+    sim.set_relativity(mode);
+    sim.set_integrator(method);
+    sim.set_dt(dt);
+    sim.set_substeps(1);
+    sim
+}
+
+/// Argument of periapsis of body 1 about body 0, from the eccentricity vector
+fn periapsis_angle(sim: &Simulation) -> f64 {
+    let (primary, secondary) = (&sim.bodies()[0], &sim.bodies()[1]);
+    let mu = G * (primary.mass + secondary.mass);
+    let r = secondary.position - primary.position;
+    let v = secondary.velocity - primary.velocity;
+    let ecc = v.cross(r.cross(v)) / mu - r.normalize();
+    ecc.y.atan2(ecc.x)
+}
+
+/// Least-squares periapsis rate (rad/s), sampling after every `stride` ticks
+fn measured_apsidal_rate(mut sim: Simulation, samples: usize, stride: u64) -> f64 {
+    let mut last = periapsis_angle(&sim);
+    let mut unwrapped = last;
+    let mut points = vec![(sim.time(), unwrapped)];
+    for _ in 0..samples {
+        sim.step_n(stride);
+        let angle = periapsis_angle(&sim);
+        unwrapped += (angle - last + std::f64::consts::PI).rem_euclid(std::f64::consts::TAU) - std::f64::consts::PI;
+        last = angle;
+        points.push((sim.time(), unwrapped));
+    }
+
+    least_squares_slope(&points)
+}
+
+/// 1PN apsidal rate of a two-body orbit: 3 (GM)^{3/2} / (c² a^{5/2} (1 − e²))
+fn predicted_apsidal_rate(sim: &Simulation) -> f64 {
+    let mu = G * (sim.bodies()[0].mass + sim.bodies()[1].mass);
+    let elements = elements_about(sim, 1, 0);
+    let (a, e) = (elements.semi_major_axis, elements.eccentricity);
+    3.0 * mu.powf(1.5) / (C * C * a.powf(2.5) * (1.0 - e * e))
+}
+
+fn arcsec_per_century(rate: f64) -> f64 {
+    rate.to_degrees() * 3600.0 * 100.0 * SECONDS_PER_YEAR
+}
+
+#[test]
+fn test_mercury_perihelion_advance() {
+    let predicted = arcsec_per_century(predicted_apsidal_rate(&sun_mercury(
+        RelativityMode::None,
+        IntegratorType::Ias15,
+        SECONDS_PER_DAY,
+    )));
+    assert!((predicted - 42.98).abs() < 0.05, "predicted {:.3}″/cy", predicted);
+
+    // 20 years, sampled daily
+    let days = (20.0 * 365.25) as usize;
+    let runs = [
+        (IntegratorType::Ias15, SECONDS_PER_DAY, 1),
+        (IntegratorType::VelocityVerlet, 3600.0, 24),
+    ];
+    for (method, dt, stride) in runs {
+        // The Newtonian run removes the integrator's own apsidal drift
+        let newtonian = measured_apsidal_rate(sun_mercury(RelativityMode::None, method, dt), days, stride);
+        for mode in [RelativityMode::Schwarzschild, RelativityMode::Eih] {
+            let relativistic = measured_apsidal_rate(sun_mercury(mode, method, dt), days, stride);
+            let advance = arcsec_per_century(relativistic - newtonian);
+            println!("{:?} {:?}: {:.3}″/cy (Newtonian drift {:.3}″/cy)", method, mode, advance, arcsec_per_century(newtonian));
+            assert!(
+                (advance / predicted - 1.0).abs() < 0.01,
+                "{:?} {:?}: {:.3}″/cy vs {:.3}″/cy",
+                method,
+                mode,
+                advance,
+                predicted
+            );
+        }
+    }
+}
+
+#[test]
+fn test_binary_pulsar_periastron_advance() {
+    // PSR J0737-3039 advances ~16.9°/yr; EIH handles the comparable masses
+    let mut sim = create_binary_pulsar(42);
+    sim.set_relativity(RelativityMode::Eih);
+    use_ias15(&mut sim, 60.0);
+    let predicted = predicted_apsidal_rate(&sim);
+
+    // 10 days, sampled every 10 minutes
+    let measured = measured_apsidal_rate(sim, 6 * 24 * 10, 10);
+    let per_year = |rate: f64| rate.to_degrees() * SECONDS_PER_YEAR;
+    println!("predicted {:.3}°/yr, measured {:.3}°/yr", per_year(predicted), per_year(measured));
+    assert!((per_year(predicted) - 16.90).abs() < 0.05);
+    assert!((measured / predicted - 1.0).abs() < 0.01, "{:.3}°/yr", per_year(measured));
+}
+
+/// Orbital period of body 1 about body 0 from the osculating semi-major axis
+fn osculating_period(sim: &Simulation) -> f64 {
+    let mu = G * (sim.bodies()[0].mass + sim.bodies()[1].mass);
+    let a = semi_major_axis(sim, 1, 0);
+    std::f64::consts::TAU * (a.powi(3) / mu).sqrt()
+}
+
+/// Least-squares dP/dt over `samples` samples taken every `stride` ticks
+fn measured_period_derivative(mut sim: Simulation, samples: usize, stride: u64) -> f64 {
+    let mut points = vec![(sim.time(), osculating_period(&sim))];
+    for _ in 0..samples {
+        sim.step_n(stride);
+        points.push((sim.time(), osculating_period(&sim)));
+    }
+    least_squares_slope(&points)
+}
+
+fn pulsar_with_radiation_reaction(scale: f64) -> Simulation {
+    let mut sim = create_binary_pulsar(42);
+    sim.set_radiation_reaction(scale);
+    use_ias15(&mut sim, 60.0);
+    sim
+}
+
+#[test]
+fn test_binary_pulsar_orbital_decay() {
+    // Peters-Mathews quadrupole decay for the preset orbit
+    let sim = pulsar_with_radiation_reaction(1.0);
+    let (m1, m2) = (sim.bodies()[0].mass, sim.bodies()[1].mass);
+    let e: f64 = 0.0878;
+    let period = osculating_period(&sim);
+    let enhancement = (1.0 + 73.0 / 24.0 * e * e + 37.0 / 96.0 * e.powi(4)) / (1.0 - e * e).powf(3.5);
+    let predicted = -192.0 * std::f64::consts::PI / (5.0 * C.powi(5))
+        * (std::f64::consts::TAU * G / period).powf(5.0 / 3.0)
+        * m1 * m2 / (m1 + m2).powf(1.0 / 3.0)
+        * enhancement;
+
+    // Observed intrinsic Pb-dot of PSR J0737-3039 (Kramer et al. 2021)
+    let observed = -1.2479e-12;
+
+    // 10 days, sampled every 10 minutes
+    let measured = measured_period_derivative(sim, 6 * 24 * 10, 10);
+    println!("Pb-dot: predicted {:.4e}, measured {:.4e}, observed {:.4e}", predicted, measured, observed);
+    assert!((measured / predicted - 1.0).abs() < 0.01, "measured {:.4e}", measured);
+    assert!((measured / observed - 1.0).abs() < 0.005, "measured {:.4e}", measured);
+
+    // The strength scales linearly, and the orbit does not decay without it
+    let boosted = measured_period_derivative(pulsar_with_radiation_reaction(1.0e4), 6 * 24, 10);
+    assert!((boosted / (1.0e4 * predicted) - 1.0).abs() < 0.01, "boosted {:.4e}", boosted);
+    let off = measured_period_derivative(pulsar_with_radiation_reaction(0.0), 6 * 24 * 10, 10);
+    assert!(off.abs() < 0.01 * predicted.abs(), "decay without radiation reaction {:.4e}", off);
+}
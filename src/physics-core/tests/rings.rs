@@ -0,0 +1,48 @@
+use physics_core::prelude::*;
+use physics_core::presets::create_saturn_system;
+
+#[test]
+fn test_ring_particles_stay_on_circular_orbits() {
+    let mut sim = create_saturn_system(42);
+    let saturn = sim.bodies()[0].clone();
+    let ids = sim.add_ring_particles(saturn.id, 3000);
+    assert_eq!(ids.len(), 3000);
+    assert!(sim.add_ring_particles(1, 100).is_empty(), "Titan has no rings");
+
+    let radii = |sim: &Simulation| -> Vec<f64> {
+        let center = sim.bodies()[0].position;
+        ids.iter().map(|&id| sim.get_body(id).unwrap().position.distance(center)).collect()
+    };
+    let start = radii(&sim);
+    sim.set_dt(60.0);
+    sim.step_n(1440);
+
+    // A day is several orbits of the inner ring; apart from the few
+    // particles passing close to a moon, every orbit stays circular
+    let end = radii(&sim);
+    let drifted = start.iter().zip(&end).filter(|(a, b)| ((*b - *a) / *a).abs() > 1e-3).count();
+    println!("{} of {} particles drifted", drifted, ids.len());
+    assert!(drifted < 30);
+    for &id in &ids {
+        let p = sim.get_body(id).unwrap();
+        assert!(p.is_active && p.position.z.abs() < 1.0e3);
+    }
+}
+
+#[test]
+fn test_ring_particles_keep_direct_summation() {
+    // More bodies than the Barnes-Hut threshold, but only 8 gravity sources
+    let build = |threshold: usize| {
+        let mut sim = create_saturn_system(7);
+        sim.add_ring_particles(0, 12_000);
+        let mut config = sim.config().clone();
+        config.barnes_hut_threshold = threshold;
+        sim.set_config(config);
+        sim.set_dt(60.0);
+        sim.step_n(3);
+        sim
+    };
+    let (auto, direct) = (build(10_000), build(usize::MAX));
+    assert!(auto.body_count() > 10_000);
+    assert!(auto.bodies().iter().zip(direct.bodies()).all(|(a, b)| a.position == b.position));
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::use_ias15;
+use physics_core::force::compute_angular_momentum;
+use physics_core::prelude::*;
+use physics_core::tides::moment_of_inertia;
+
+/// Spin angular momentum of body `index`
+fn spin(sim: &Simulation, index: usize) -> Vec3 {
+    let body = &sim.bodies()[index];
+    body.spin_axis() * (moment_of_inertia(body) * body.rotation_rate)
+}
+
+/// An Earth with its real pole, J2 and moment of inertia
+fn earth(position: Vec3, velocity: Vec3) -> Body {
+    let mut earth = Body::new(0, "Earth", BodyType::Planet, M_EARTH, R_EARTH, position, velocity);
+    earth.rotation_rate = OMEGA_EARTH;
+    earth.pole_ra = Some(0.0);
+    earth.pole_dec = Some(std::f64::consts::FRAC_PI_2);
+    earth.j2 = 1.082_63e-3;
+    earth.moment_of_inertia_factor = 0.3307;
+    earth
+}
+
+/// Precessing simulation of `primary` and `companion`, stepped hourly
+fn pair(primary: Body, companion: Body) -> Simulation {
+    let mut sim = Simulation::new(42);
+    sim.add_body(primary);
+    sim.add_body(companion);
+    sim.set_precession(true);
+    use_ias15(&mut sim, 3600.0);
+    sim
+}
+
+/// The Sun and the Earth on a circular orbit
+fn sun_and_earth() -> Simulation {
+    let speed = (G * (M_SUN + M_EARTH) / AU).sqrt();
+    let sun = Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO);
+    pair(sun, earth(Vec3::new(AU, 0.0, 0.0), Vec3::new(0.0, speed, 0.0)))
+}
+
+/// Ecliptic longitude of the projection of body 1's spin axis
+fn pole_longitude(sim: &Simulation) -> f64 {
+    let axis = sim.bodies()[1].spin_axis();
+    axis.y.atan2(axis.x)
+}
+
+#[test]
+fn test_solar_precession_of_earth() {
+    let mut sim = sun_and_earth();
+    let obliquity = sim.bodies()[1].spin_axis().z.acos();
+    let longitude0 = pole_longitude(&sim);
+    sim.step_n(24 * 365);
+
+    // Orbit-averaged rate (3/2) (n²/ω) (C − A)/C cos ε, ~15.9″/yr; the
+    // axis moves westward
+    let years = sim.time() / SECONDS_PER_YEAR;
+    let n2 = G * (M_SUN + M_EARTH) / AU.powi(3);
+    let predicted = -1.5 * n2 / OMEGA_EARTH * (1.082_63e-3 / 0.3307) * obliquity.cos();
+    let measured = (pole_longitude(&sim) - longitude0) / sim.time();
+    let arcsec_per_year = |rate: f64| rate.to_degrees() * 3600.0 * SECONDS_PER_YEAR;
+    println!("precession predicted {:.3}″/yr, measured {:.3}″/yr over {:.2} yr", arcsec_per_year(predicted), arcsec_per_year(measured), years);
+    assert!((measured / predicted - 1.0).abs() < 0.01);
+    assert!((sim.bodies()[1].spin_axis().z.acos() - obliquity).abs() < 1e-6);
+}
+
+#[test]
+fn test_lunar_torque_conserves_angular_momentum() {
+    // A Moon in the ecliptic, 23° from the Earth's equator and well
+    // within the range of the J2 force
+    let distance = 3.844e8;
+    let speed = (G * (M_EARTH + M_MOON) / distance).sqrt();
+    let moon = Body::new(0, "Moon", BodyType::Moon, M_MOON, R_MOON, Vec3::new(distance, 0.0, 0.0), Vec3::new(0.0, speed, 0.0));
+    let mut sim = pair(earth(Vec3::ZERO, Vec3::ZERO), moon);
+
+    let total0 = compute_angular_momentum(sim.bodies(), sim.center_of_mass()) + spin(&sim, 0);
+    let spin0 = spin(&sim, 0);
+    sim.step_n(24 * 30);
+
+    // The torque on the spin is balanced by the J2 reaction on the orbit
+    let total = compute_angular_momentum(sim.bodies(), sim.center_of_mass()) + spin(&sim, 0);
+    let exchanged = (spin(&sim, 0) - spin0).length();
+    assert!(exchanged > 0.0);
+    assert!((total - total0).length() < 1e-2 * exchanged, "{:.3e} vs {:.3e}", (total - total0).length(), exchanged);
+}
+
+#[test]
+fn test_orientation_survives_snapshots() {
+    let mut sim = sun_and_earth();
+    sim.step_n(7);
+    let mut restored = Simulation::new(0);
+    restored.restore(sim.snapshot()).unwrap();
+    sim.step_n(5);
+    restored.step_n(5);
+    assert_eq!(sim.orientations_flat(), restored.orientations_flat());
+    assert_eq!(sim.angular_velocities_flat(), restored.angular_velocities_flat());
+
+    // Twelve hours are half a sidereal turn: the prime meridian faces the
+    // other way
+    let mut sim = sun_and_earth();
+    sim.set_precession(false);
+    let start = sim.bodies()[1].orientation.rotate(Vec3::X);
+    let half_turn = (std::f64::consts::PI / OMEGA_EARTH / 60.0).round() as u64;
+    sim.set_dt(60.0);
+    sim.step_n(half_turn);
+    let end = sim.bodies()[1].orientation.rotate(Vec3::X);
+    assert!((end + start).length() < 1e-3, "{:?} vs {:?}", end, start);
+}
@@ -1,5 +1,23 @@
 use physics_core::prelude::*;
-use physics_core::snapshot::{Snapshot, DeltaSnapshot, SNAPSHOT_VERSION};
+use physics_core::codec::Quantization;
+use physics_core::presets::create_asteroid_belt;
+use physics_core::snapshot::{Snapshot, DeltaError, DeltaSnapshot, DeltaThresholds, SNAPSHOT_VERSION};
+
+fn assert_bodies_bit_exact(a: &[Body], b: &[Body]) {
+    assert_eq!(a.len(), b.len());
+    for (x, y) in a.iter().zip(b) {
+        // Shortest round-trip float formatting is unique per bit pattern
+        assert_eq!(
+            serde_json::to_string(x).unwrap(),
+            serde_json::to_string(y).unwrap(),
+            "Body {} differs after binary roundtrip",
+            x.name
+        );
+        for (u, v) in [(x.position, y.position), (x.velocity, y.velocity), (x.acceleration, y.acceleration)] {
+            assert_eq!(u.to_array().map(f64::to_bits), v.to_array().map(f64::to_bits));
+        }
+    }
+}
 
 #[test]
 fn test_snapshot_roundtrip_all_fields() {
@@ -71,3 +89,162 @@ fn test_delta_snapshot_correctness() {
     assert_eq!(delta.changed_bodies.len(), 1);
     assert_eq!(delta.changed_bodies[0].id, id2);
 }
+
+#[test]
+fn test_apply_delta_syncs_client() {
+    let mut server = Simulation::new(42);
+    server.add_star("Sun", M_SUN, R_SUN);
+    let earth = server.add_planet("Earth", M_EARTH, R_EARTH, AU, 29784.0);
+    let mars = server.add_planet("Mars", 6.42e23, 3.39e6, 1.524 * AU, 24077.0);
+    let mut client = Simulation::from_json(&server.to_json().unwrap()).unwrap();
+
+    let base = server.snapshot();
+    server.step_n(3);
+    server.remove_body(mars);
+    let moon = server.add_planet("Moon", M_MOON, R_MOON, AU + 3.844e8, 29784.0 + 1022.0);
+    // Zero thresholds send every body that moved at all, for an exact sync
+    let exact = DeltaThresholds { position: 0.0, velocity: 0.0, mass: 0.0, radius: 0.0 };
+    let delta = DeltaSnapshot::from_diff_with_thresholds(&base, &server.snapshot(), &exact);
+    assert_eq!(delta.removed_body_ids, vec![mars]);
+
+    client.apply_delta(delta).expect("Failed to apply delta");
+    assert_eq!(client.time(), server.time());
+    assert_eq!(client.tick(), server.tick());
+    assert_eq!(client.snapshot().sequence, server.snapshot().sequence);
+    assert!(!client.get_body(mars).unwrap().is_active);
+    assert_eq!(client.get_body(moon).unwrap().name, "Moon");
+    assert_bodies_bit_exact(client.bodies(), server.bodies());
+
+    // Both sides keep stepping in lockstep and allocate the same IDs
+    server.step();
+    client.step();
+    assert_bodies_bit_exact(client.bodies(), server.bodies());
+    let id = client.add_star("Companion", M_SUN, R_SUN);
+    assert!(id > moon && client.get_body(earth).is_some());
+}
+
+#[test]
+fn test_apply_delta_sequence_mismatch() {
+    let mut server = Simulation::new(42);
+    server.add_star("Sun", M_SUN, R_SUN);
+    server.add_planet("Earth", M_EARTH, R_EARTH, AU, 29784.0);
+    let mut client = Simulation::from_json(&server.to_json().unwrap()).unwrap();
+
+    server.step();
+    let base = server.snapshot();
+    server.step();
+    let delta = DeltaSnapshot::from_diff(&base, &server.snapshot());
+
+    let before = client.snapshot();
+    let err = client.apply_delta(delta).unwrap_err();
+    assert_eq!(err, DeltaError::SequenceMismatch { expected: base.sequence, actual: before.sequence });
+    assert_bodies_bit_exact(client.bodies(), &before.bodies);
+}
+
+#[test]
+fn test_snapshot_preserves_simulation_config() {
+    let mut sim = Simulation::new(42);
+    sim.add_star("Sun", M_SUN, R_SUN);
+    sim.add_planet("Earth", M_EARTH, R_EARTH, AU, 29784.0);
+    sim.set_force_method(ForceMethod::BarnesHut);
+    sim.set_collisions_enabled(true);
+    sim.set_collision_outcome(BodyType::Planet, CollisionOutcome::Bounce);
+    sim.set_collision_restitution(0.8);
+    let mut config = sim.config().clone();
+    config.barnes_hut_threshold = 123;
+    sim.set_config(config);
+
+    let restored = Simulation::from_json(&sim.to_json().unwrap()).expect("Failed to restore");
+    let config = restored.config();
+
+    assert_eq!(config.force_method, ForceMethod::BarnesHut);
+    assert_eq!(config.barnes_hut_threshold, 123);
+    assert!(config.collision.enabled);
+    assert_eq!(config.collision.outcome(BodyType::Planet), CollisionOutcome::Bounce);
+    assert_eq!(config.collision.restitution, 0.8);
+}
+
+#[test]
+fn test_binary_snapshot_bit_exact() {
+    let mut sim = Preset::FullSolarSystemII.create(42);
+    sim.set_collisions_enabled(true);
+    sim.step_n(10);
+    let snapshot = sim.snapshot();
+
+    let bytes = snapshot.to_binary().expect("Failed to encode");
+    let restored = Snapshot::from_binary(&bytes).expect("Failed to decode");
+
+    assert_eq!(restored.version, SNAPSHOT_VERSION);
+    assert_eq!(restored.sequence, snapshot.sequence);
+    assert_eq!(restored.time.to_bits(), snapshot.time.to_bits());
+    assert_eq!(restored.rng_state, snapshot.rng_state);
+    assert_bodies_bit_exact(&snapshot.bodies, &restored.bodies);
+    assert_eq!(restored.to_binary().unwrap(), bytes);
+    assert_eq!(restored.to_json().unwrap(), snapshot.to_json().unwrap());
+
+    // A restored simulation continues exactly like the original
+    let mut sim2 = Simulation::new(0);
+    sim2.restore(restored).expect("Failed to restore");
+    sim.step_n(5);
+    sim2.step_n(5);
+    assert_bodies_bit_exact(sim.bodies(), sim2.bodies());
+}
+
+#[test]
+fn test_binary_delta_roundtrip() {
+    let mut sim = create_asteroid_belt(7, 1000);
+    let base = sim.snapshot();
+    sim.step();
+    sim.get_body_mut(3).unwrap().mass *= 2.0;
+    let current = sim.snapshot();
+
+    let delta = DeltaSnapshot::from_diff(&base, &current);
+    let bytes = delta.to_binary(&base, None).expect("Failed to encode delta");
+    let decoded = DeltaSnapshot::from_binary(&bytes, &base).expect("Failed to decode delta");
+
+    assert_eq!(decoded.base_sequence, delta.base_sequence);
+    assert_eq!(decoded.sequence, delta.sequence);
+    assert_eq!(decoded.removed_body_ids, delta.removed_body_ids);
+    assert_bodies_bit_exact(&delta.changed_bodies, &decoded.changed_bodies);
+
+    // A delta only decodes against the snapshot it was made from
+    assert!(DeltaSnapshot::from_binary(&bytes, &current).is_err());
+}
+
+#[test]
+fn test_binary_size_and_quantization() {
+    let mut sim = create_asteroid_belt(7, 1000);
+    sim.step();
+    let base = sim.snapshot();
+    sim.step();
+    let current = sim.snapshot();
+
+    let json_len = current.to_json().unwrap().len();
+    let binary_len = current.to_binary().unwrap().len();
+    assert!(binary_len * 3 < json_len, "binary {} vs json {}", binary_len, json_len);
+
+    let quantization = Quantization::new(1000.0, 1e-3);
+    let quantized = current.to_binary_quantized(quantization).unwrap();
+    assert!(quantized.len() < binary_len);
+    let decoded = Snapshot::from_binary(&quantized).unwrap();
+    for (a, b) in current.bodies.iter().zip(&decoded.bodies) {
+        let dp = (a.position - b.position).to_array();
+        let dv = (a.velocity - b.velocity).to_array();
+        assert!(dp.iter().all(|d| d.abs() <= 500.0 * (1.0 + 1e-9)));
+        assert!(dv.iter().all(|d| d.abs() <= 5e-4 * (1.0 + 1e-9)));
+    }
+
+    // Per-tick quantized deltas carry only small relative offsets
+    let delta = DeltaSnapshot::from_diff(&base, &current);
+    let lossless = delta.to_binary(&base, None).unwrap();
+    let compact = delta.to_binary(&base, Some(quantization)).unwrap();
+    assert!(compact.len() * 3 < lossless.len(), "quantized {} vs lossless {}", compact.len(), lossless.len());
+    assert!(compact.len() < delta.changed_bodies.len() * 40);
+
+    let decoded = DeltaSnapshot::from_binary(&compact, &base).unwrap();
+    for (a, b) in delta.changed_bodies.iter().zip(&decoded.changed_bodies) {
+        assert!((a.position - b.position).length() <= 500.0 * 3f64.sqrt());
+        assert!((a.orientation.rotate(Vec3::X) - b.orientation.rotate(Vec3::X)).length() < 1e-6);
+        assert_eq!(a.name, b.name);
+    }
+}
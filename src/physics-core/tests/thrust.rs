@@ -0,0 +1,141 @@
+use physics_core::prelude::*;
+
+/// A 1 t spacecraft at rest in empty space carrying `propellant` kg
+fn lone_craft(method: IntegratorType, propellant: f64, exhaust_velocity: f64) -> (Simulation, u32) {
+    let mut sim = Simulation::new(42);
+    let id = sim.add_body(Body::new(0, "Craft", BodyType::Spacecraft, 1000.0, 2.0, Vec3::ZERO, Vec3::ZERO));
+    assert!(sim.set_propulsion(id, propellant, exhaust_velocity));
+    sim.set_integrator(method);
+    sim.set_dt(1.0);
+    sim.set_substeps(4);
+    (sim, id)
+}
+
+/// The Earth and a 1 t spacecraft on a circular orbit 400 km up, with
+/// negligible softening
+fn orbiter() -> (Simulation, u32) {
+    let mut sim = Simulation::new(42);
+    let mut earth = Body::new(0, "Earth", BodyType::Planet, M_EARTH, R_EARTH, Vec3::ZERO, Vec3::ZERO);
+    earth.softening_length = 1.0;
+    sim.add_body(earth);
+    let r = R_EARTH + 400.0e3;
+    let speed = (G * M_EARTH / r).sqrt();
+    let mut craft = Body::new(0, "Craft", BodyType::Spacecraft, 1000.0, 2.0, Vec3::new(r, 0.0, 0.0), Vec3::new(0.0, speed, 0.0));
+    craft.softening_length = 1.0;
+    let id = sim.add_body(craft);
+    (sim, id)
+}
+
+fn craft(sim: &Simulation, id: u32) -> &Body {
+    sim.get_body(id).unwrap()
+}
+
+#[test]
+fn test_continuous_burn_follows_rocket_equation() {
+    for method in [
+        IntegratorType::VelocityVerlet,
+        IntegratorType::Leapfrog,
+        IntegratorType::Yoshida4,
+        IntegratorType::Ias15,
+        IntegratorType::Hermite,
+        IntegratorType::WisdomHolman,
+    ] {
+        let (mut sim, id) = lone_craft(method, 300.0, 3000.0);
+        // A window that starts and ends mid-substep
+        assert!(sim.schedule_burn(id, 2.6, 123.4, Vec3::new(1000.0, 0.0, 0.0), ThrustFrame::Inertial));
+        sim.step_n(150);
+        let after_burn = craft(&sim, id).velocity;
+        sim.step_n(50);
+        let body = craft(&sim, id);
+        assert!(body.burns.is_empty());
+        assert_eq!(body.velocity, after_burn, "{:?} keeps thrusting", method);
+
+        let used = 1000.0 * 123.4 / 3000.0;
+        assert!((body.mass - (1000.0 - used)).abs() < 1e-9, "{:?}: mass {}", method, body.mass);
+        assert!((body.propellant_mass - (300.0 - used)).abs() < 1e-9);
+        let predicted = 3000.0 * (1000.0 / (1000.0 - used)).ln();
+        assert!((body.velocity.x / predicted - 1.0).abs() < 1e-9, "{:?}: {} vs {}", method, body.velocity.x, predicted);
+    }
+}
+
+#[test]
+fn test_engine_cuts_out_when_propellant_runs_out() {
+    let (mut sim, id) = lone_craft(IntegratorType::VelocityVerlet, 100.0, 2000.0);
+    // 1 kg/s for a 1000 s window, but only 100 s of propellant
+    assert!(sim.schedule_burn(id, 0.0, 1000.0, Vec3::new(0.0, 2000.0, 0.0), ThrustFrame::Inertial));
+    sim.step_n(1001);
+    let body = craft(&sim, id);
+    assert!(body.burns.is_empty());
+    assert!(body.propellant_mass.abs() < 1e-9);
+    assert!((body.mass - 900.0).abs() < 1e-9);
+    let predicted = 2000.0 * (1000.0f64 / 900.0).ln();
+    assert!((body.velocity.y / predicted - 1.0).abs() < 1e-9, "{} vs {}", body.velocity.y, predicted);
+}
+
+#[test]
+fn test_prograde_impulse_raises_apoapsis() {
+    let (mut sim, id) = orbiter();
+    let r = craft(&sim, id).position.length();
+    let applied = sim.apply_delta_v(id, Vec3::new(100.0, 0.0, 0.0), ThrustFrame::Prograde).unwrap();
+    assert!((applied - Vec3::new(0.0, 100.0, 0.0)).length() < 1e-9);
+
+    // Vis-viva: half an orbit later the craft is at apoapsis 2a − r
+    let mu = G * (M_EARTH + 1000.0);
+    let a = 1.0 / (2.0 / r - craft(&sim, id).velocity.length_squared() / mu);
+    let half_period = std::f64::consts::PI * (a.powi(3) / mu).sqrt();
+    sim.set_dt(half_period / 3000.0);
+    sim.set_substeps(1);
+    sim.step_n(3000);
+    let apoapsis = craft(&sim, id).position.length();
+    assert!((apoapsis - (2.0 * a - r)).abs() < 10.0, "apoapsis {} vs {}", apoapsis, 2.0 * a - r);
+    assert!(apoapsis - r > 340.0e3);
+}
+
+#[test]
+fn test_burns_survive_snapshots() {
+    let (mut sim, id) = orbiter();
+    sim.set_dt(10.0);
+    sim.set_propulsion(id, 200.0, 3000.0);
+    assert!(sim.schedule_burn(id, 25.0, 300.0, Vec3::new(500.0, 0.0, 50.0), ThrustFrame::Prograde));
+    assert!(sim.schedule_burn(id, 600.0, 120.0, Vec3::new(-200.0, 0.0, 0.0), ThrustFrame::Radial));
+    sim.step_n(15);
+    let mut restored = Simulation::new(0);
+    restored.restore(sim.snapshot()).unwrap();
+    sim.step_n(60);
+    restored.step_n(60);
+    assert_eq!(craft(&sim, id).position, craft(&restored, id).position);
+    assert_eq!(craft(&sim, id).mass, craft(&restored, id).mass);
+    assert!(craft(&sim, id).burns.is_empty());
+
+    // Past windows are clipped to what is left
+    assert!(!sim.schedule_burn(id, 0.0, 100.0, Vec3::X, ThrustFrame::Inertial));
+    assert!(sim.schedule_burn(id, sim.time() - 10.0, 100.0, Vec3::X, ThrustFrame::Inertial));
+    assert_eq!(craft(&sim, id).burns[0].duration, 90.0);
+    assert!(sim.cancel_burns(id));
+    assert!(craft(&sim, id).burns.is_empty());
+}
+
+#[test]
+fn test_prograde_burn_raises_energy_with_every_integrator() {
+    // 1 kN prograde for 100 s in low orbit (the reviewer's scratch case)
+    let energy_gain = |method: IntegratorType| {
+        let (mut sim, id) = orbiter();
+        sim.set_integrator(method);
+        sim.set_dt(10.0);
+        sim.set_propulsion(id, 200.0, 3000.0);
+        let specific_energy = |sim: &Simulation| {
+            let body = craft(sim, id);
+            0.5 * body.velocity.length_squared() - G * M_EARTH / body.position.length()
+        };
+        let e0 = specific_energy(&sim);
+        assert!(sim.schedule_burn(id, 0.0, 100.0, Vec3::new(1000.0, 0.0, 0.0), ThrustFrame::Prograde));
+        sim.step_n(20);
+        specific_energy(&sim) - e0
+    };
+    let reference = energy_gain(IntegratorType::VelocityVerlet);
+    assert!(reference > 7.0e5, "energy gain {} J/kg", reference);
+    for method in [IntegratorType::Hermite, IntegratorType::WisdomHolman, IntegratorType::Ias15] {
+        let gain = energy_gain(method);
+        assert!((gain / reference - 1.0).abs() < 1e-3, "{:?}: {} vs {} J/kg", method, gain, reference);
+    }
+}
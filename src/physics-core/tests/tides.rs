@@ -0,0 +1,139 @@
+mod common;
+
+use common::{elements_about, least_squares_slope, use_ias15};
+use physics_core::force::compute_angular_momentum;
+use physics_core::prelude::*;
+use physics_core::presets::{create_jupiter_system, create_trappist1};
+use physics_core::tides::moment_of_inertia;
+
+/// Osculating eccentricity of body 1 about body 0
+fn eccentricity(sim: &Simulation) -> f64 {
+    elements_about(sim, 1, 0).eccentricity
+}
+
+/// Two-body system with body 1 starting at periapsis of an orbit (a, e),
+/// stepped every `dt` seconds
+fn binary(primary: Body, mut secondary: Body, a: f64, e: f64, dt: f64) -> Simulation {
+    let mu = G * (primary.mass + secondary.mass);
+    let periapsis = a * (1.0 - e);
+    secondary.position = Vec3::new(periapsis, 0.0, 0.0);
+    secondary.velocity = Vec3::new(0.0, (mu * (1.0 + e) / periapsis).sqrt(), 0.0);
+
+    let mut sim = Simulation::new(42);
+    sim.add_body(primary);
+    sim.add_body(secondary);
+    sim.set_tides(true);
+    use_ias15(&mut sim, dt);
+    sim
+}
+
+/// Hot Earth, synchronously rotating, on an eccentric orbit
+fn hot_planet(e: f64, k2: f64, dt: f64) -> Simulation {
+    let a = 0.01 * AU;
+    let star = Body::new(0, "Star", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO);
+    let mut planet = Body::new(0, "Planet", BodyType::Planet, M_EARTH, R_EARTH, Vec3::ZERO, Vec3::ZERO);
+    planet.k2 = k2;
+    planet.tidal_time_lag = 698.0;
+    planet.rotation_rate = (G * (M_SUN + M_EARTH) / a.powi(3)).sqrt();
+    binary(star, planet, a, e, dt)
+}
+
+/// Least-squares d(ln e)/dt over `days`, sampled daily
+fn eccentricity_decay_rate(mut sim: Simulation, days: usize) -> f64 {
+    let ticks = (SECONDS_PER_DAY / sim.config().integrator.dt).round() as u64;
+    let mut points = vec![(sim.time(), eccentricity(&sim).ln())];
+    for _ in 0..days {
+        sim.step_n(ticks);
+        points.push((sim.time(), eccentricity(&sim).ln()));
+    }
+    least_squares_slope(&points)
+}
+
+#[test]
+fn test_eccentricity_damps_at_tidal_rate() {
+    let sim = hot_planet(0.01, 0.3, 1800.0);
+    let (star, planet) = (&sim.bodies()[0], &sim.bodies()[1]);
+    let a = 0.01 * AU;
+    let n = (G * (star.mass + planet.mass) / a.powi(3)).sqrt();
+
+    // Synchronous body: ė/e = −21/2 (k2/Q) (M/m) (R/a)⁵ n with 1/Q = n Δt
+    // (Goldreich & Soter 1966)
+    let predicted = -10.5 * planet.k2 * n * planet.tidal_time_lag * (star.mass / planet.mass) * (planet.radius / a).powi(5) * n;
+
+    // Subtract the run without tides to remove secular noise
+    let measured = eccentricity_decay_rate(sim, 365) - eccentricity_decay_rate(hot_planet(0.01, 0.0, 1800.0), 365);
+    println!("d(ln e)/dt predicted {:.4e}/s, measured {:.4e}/s", predicted, measured);
+    assert!((measured / predicted - 1.0).abs() < 0.03, "{:.4e} vs {:.4e}", measured, predicted);
+}
+
+#[test]
+fn test_tides_conserve_total_angular_momentum() {
+    // A fast-spinning planet spins down, handing its spin to the orbit.
+    // The step resolves the despin time (~1 week), as the explicit spin
+    // update requires.
+    let mut sim = hot_planet(0.05, 0.3, 600.0);
+    let spin_angular_momentum = |sim: &Simulation| {
+        let planet = &sim.bodies()[1];
+        moment_of_inertia(planet) * planet.rotation_rate
+    };
+    sim.get_body_mut(1).unwrap().rotation_rate *= 3.0;
+    let com = sim.center_of_mass();
+    let orbit0 = compute_angular_momentum(sim.bodies(), com).z;
+    let spin0 = spin_angular_momentum(&sim);
+    sim.step_n(6 * 24 * 30);
+
+    let com = sim.center_of_mass();
+    let orbit = compute_angular_momentum(sim.bodies(), com).z;
+    let spin = spin_angular_momentum(&sim);
+    assert!(spin < 0.9 * spin0, "spin {:.4e} -> {:.4e}", spin0, spin);
+    let exchanged = spin0 - spin;
+    assert!(((orbit - orbit0) - exchanged).abs() < 1e-3 * exchanged, "orbit gained {:.6e}, spin lost {:.6e}", orbit - orbit0, exchanged);
+    assert!(sim.bodies()[1].tidal_heating > 0.0);
+}
+
+#[test]
+fn test_io_tidal_heating() {
+    // Io alone around a point-mass Jupiter, on its observed eccentric orbit
+    let preset = create_jupiter_system(42);
+    let mut jupiter = preset.bodies()[0].clone();
+    (jupiter.j2, jupiter.j4, jupiter.oblateness, jupiter.rotation_rate, jupiter.k2) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    let mut io = preset.bodies()[1].clone();
+    let (a, e): (f64, f64) = (4.217e8, 0.0041);
+    let n = (G * (jupiter.mass + io.mass) / a.powi(3)).sqrt();
+    io.rotation_rate = n;
+    io.pole_ra = None;
+    io.pole_dec = None;
+    let predicted = 10.5 * io.k2 * io.tidal_time_lag * n * n * G * jupiter.mass.powi(2) * io.radius.powi(5) * e * e / a.powi(6);
+
+    let mut sim = binary(jupiter, io, a, e, 600.0);
+    // Average over 10 orbits of 1.77 days
+    let samples = 10 * 255;
+    let mut total = 0.0;
+    for _ in 0..samples {
+        sim.step();
+        total += sim.bodies()[1].tidal_heating;
+    }
+    let mean = total / samples as f64;
+    println!("Io heating predicted {:.3e} W, mean {:.3e} W", predicted, mean);
+    assert!((mean / predicted - 1.0).abs() < 0.05, "{:.3e} W", mean);
+    assert!(mean > 5.0e13 && mean < 2.0e14);
+}
+
+#[test]
+fn test_trappist1b_locks() {
+    // TRAPPIST-1b starts without spin and is locked within a few decades
+    let mut sim = create_trappist1(42);
+    sim.set_tides(true);
+    sim.set_dt(3600.0);
+    sim.set_substeps(1);
+    assert_eq!(sim.bodies()[1].rotation_rate, 0.0);
+    sim.step_n(24 * 365 * 20);
+
+    let (star, planet) = (&sim.bodies()[0], &sim.bodies()[1]);
+    let a = 0.01154 * AU;
+    let n = (G * (star.mass + planet.mass) / a.powi(3)).sqrt();
+    println!("TRAPPIST-1b spin / mean motion = {:.4}", planet.rotation_rate / n);
+    assert!((planet.rotation_rate / n - 1.0).abs() < 0.02);
+    // The outer planets are still spinning up
+    assert!(sim.bodies()[7].rotation_rate < 0.5 * n * (0.01154_f64 / 0.06189).powf(1.5));
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{run_with_and_without, semi_major_axis, use_ias15};
+use physics_core::prelude::*;
+use physics_core::presets::create_asteroid_belt;
+use physics_core::yarkovsky::{thermal_response, yarkovsky_acceleration, THERMAL_EMISSIVITY};
+use std::f64::consts::PI;
+
+const A: f64 = 2.5 * AU;
+const RADIUS: f64 = 500.0;
+const DENSITY: f64 = 2500.0;
+const ALBEDO: f64 = 0.1;
+const SPIN: f64 = 2.0 * PI / (6.0 * 3600.0);
+
+/// The Sun and a 1 km asteroid on a circular orbit at 2.5 AU
+fn sun_and_asteroid(tilt: f64, yarkovsky: bool) -> Simulation {
+    let mut sim = Simulation::new(42);
+    let mut sun = Body::new(0, "Sun", BodyType::Star, M_SUN, R_SUN, Vec3::ZERO, Vec3::ZERO);
+    sun.luminosity = L_SUN;
+    sim.add_body(sun);
+    let mass = 4.0 / 3.0 * PI * RADIUS.powi(3) * DENSITY;
+    let speed = (G * (M_SUN + mass) / A).sqrt();
+    let mut rock = Body::new(0, "Rock", BodyType::Asteroid, mass, RADIUS, Vec3::new(A, 0.0, 0.0), Vec3::new(0.0, speed, 0.0));
+    rock.albedo = ALBEDO;
+    rock.rotation_rate = SPIN;
+    rock.axial_tilt = tilt;
+    rock.set_spin_axis(Vec3::new(tilt.sin(), 0.0, tilt.cos()));
+    sim.add_body(rock);
+    sim.set_yarkovsky(yarkovsky);
+    use_ias15(&mut sim, SECONDS_PER_DAY);
+    sim
+}
+
+/// Drift of a over `orbits` orbits relative to the run without Yarkovsky
+fn measured_drift(tilt: f64, orbits: f64) -> (f64, f64) {
+    let period = 2.0 * PI * (A.powi(3) / (G * M_SUN)).sqrt();
+    let ticks = (orbits * period / SECONDS_PER_DAY).round() as u64;
+    let (sim, reference) = run_with_and_without(|yarkovsky| sun_and_asteroid(tilt, yarkovsky), ticks);
+    (semi_major_axis(&sim, 1, 0) - semi_major_axis(&reference, 1, 0), ticks as f64 * SECONDS_PER_DAY)
+}
+
+/// Bottke et al. (2006) orbit-averaged rates: −8/9 αΦ/n W(ω) cos γ and
+/// 4/9 αΦ/n W(n) sin²γ, with W = −E sin δ
+fn predicted_rate(tilt: f64) -> f64 {
+    let flux = L_SUN / (4.0 * PI * A * A);
+    let alpha = 1.0 - ALBEDO;
+    let phi = PI * RADIUS * RADIUS * flux / (4.0 / 3.0 * PI * RADIUS.powi(3) * DENSITY * C);
+    let n = (G * M_SUN / A.powi(3)).sqrt();
+    let temperature = (alpha * flux / (THERMAL_EMISSIVITY * STEFAN_BOLTZMANN)).powf(0.25);
+    let w = |omega: f64| {
+        let theta = DEFAULT_THERMAL_INERTIA * omega.sqrt() / (THERMAL_EMISSIVITY * STEFAN_BOLTZMANN * temperature.powi(3));
+        let (amplitude, lag) = thermal_response(theta);
+        -amplitude * lag.sin()
+    };
+    -8.0 / 9.0 * alpha * phi / n * w(SPIN) * tilt.cos() + 4.0 / 9.0 * alpha * phi / n * w(n) * tilt.sin().powi(2)
+}
+
+#[test]
+fn test_diurnal_drift() {
+    for tilt in [0.0, PI] {
+        let (drift, time) = measured_drift(tilt, 4.0);
+        let predicted = predicted_rate(tilt) * time;
+        println!("γ = {:.0}°: Δa predicted {:.2} m, measured {:.2} m", tilt.to_degrees(), predicted, drift);
+        assert!((drift / predicted - 1.0).abs() < 0.02);
+    }
+    // ~2e-4 AU/Myr outward for a 1 km prograde rotator
+    let per_myr = predicted_rate(0.0) * 1.0e6 * SECONDS_PER_YEAR / AU;
+    assert!(per_myr > 1.0e-4 && per_myr < 5.0e-4, "{:.2e} AU/Myr", per_myr);
+}
+
+#[test]
+fn test_seasonal_drift() {
+    let (drift, time) = measured_drift(PI / 2.0, 4.0);
+    let predicted = predicted_rate(PI / 2.0) * time;
+    println!("γ = 90°: Δa predicted {:.2} m, measured {:.2} m", predicted, drift);
+    assert!(predicted < 0.0);
+    assert!((drift / predicted - 1.0).abs() < 0.02);
+}
+
+#[test]
+fn test_yorp_spins_up_and_uprights() {
+    let tilt = 0.3;
+    let mut sim = sun_and_asteroid(tilt, false);
+    sim.get_body_mut(1).unwrap().yorp_coefficient = 0.01;
+    sim.set_yorp(true);
+    sim.step_n(3650);
+
+    let rock = &sim.bodies()[1];
+    let flux = L_SUN / (4.0 * PI * A * A);
+    let inertia = 0.4 * rock.mass * RADIUS * RADIUS;
+    let rate = 0.01 * flux * RADIUS.powi(3) / (C * inertia);
+    let time = 3650.0 * SECONDS_PER_DAY;
+    let expected = SPIN + rate * (2.0 * tilt).cos() * time;
+    let obliquity = rock.spin_axis().dot(Vec3::Z).acos();
+    println!("spin {:.6e} -> {:.6e} rad/s, obliquity {:.4} rad", SPIN, rock.rotation_rate, obliquity);
+    assert!(((rock.rotation_rate - SPIN) / (expected - SPIN) - 1.0).abs() < 1e-3);
+    assert!(obliquity < tilt);
+    assert!((rock.axial_tilt - obliquity).abs() < 1e-9);
+}
+
+#[test]
+fn test_belt_spin_axes_are_isotropic() {
+    let sim = create_asteroid_belt(42, 200);
+    let sun = &sim.bodies()[0];
+    assert!(sun.luminosity > 0.0);
+    let (mut outward, mut inward, mut cos_sum, mut count) = (0, 0, 0.0, 0);
+    for rock in sim.bodies().iter().filter(|b| b.name.starts_with("Asteroid_")) {
+        let normal = (rock.position - sun.position).cross(rock.velocity - sun.velocity).normalize();
+        let obliquity = rock.spin_axis().dot(normal).clamp(-1.0, 1.0).acos();
+        assert!((rock.axial_tilt - obliquity).abs() < 1e-9);
+        cos_sum += obliquity.cos();
+        count += 1;
+        // da/dt has the sign of the power a·v
+        if yarkovsky_acceleration(rock, sun).dot(rock.velocity - sun.velocity) > 0.0 {
+            outward += 1;
+        } else {
+            inward += 1;
+        }
+    }
+    println!("{} outward, {} inward, mean cos γ {:.3}", outward, inward, cos_sum / count as f64);
+    assert_eq!(count, 200);
+    assert!((cos_sum / count as f64).abs() < 0.15);
+    assert!(outward > 60 && inward > 60);
+}
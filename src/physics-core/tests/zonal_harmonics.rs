@@ -0,0 +1,108 @@
+mod common;
+
+use common::least_squares_slope;
+use physics_core::prelude::*;
+use physics_core::presets::create_jupiter_system;
+
+/// Galilean moons tilted together by `inclination` about the x axis, so they
+/// start coplanar with ascending nodes at Ω = 0 on Jupiter's equator
+fn inclined_jupiter_system(inclination: f64, oblate: bool) -> Simulation {
+    let mut sim = create_jupiter_system(42);
+    let (sin_i, cos_i) = inclination.sin_cos();
+    let tilt = |v: Vec3| Vec3::new(v.x, cos_i * v.y - sin_i * v.z, sin_i * v.y + cos_i * v.z);
+    let ids: Vec<BodyId> = sim.bodies().iter().map(|b| b.id).collect();
+    for id in ids {
+        let body = sim.get_body_mut(id).unwrap();
+        if body.body_type == BodyType::Moon {
+            body.position = tilt(body.position);
+            body.velocity = tilt(body.velocity);
+        }
+        if !oblate {
+            body.j2 = 0.0;
+            body.j4 = 0.0;
+        }
+    }
+    sim.set_dt(600.0);
+    sim.set_substeps(1);
+    sim
+}
+
+/// Longitude of the ascending node of `moon` about Jupiter (equatorial frame)
+fn node_longitude(sim: &Simulation, moon: usize) -> f64 {
+    let (jupiter, body) = (&sim.bodies()[0], &sim.bodies()[moon]);
+    let h = (body.position - jupiter.position).cross(body.velocity - jupiter.velocity);
+    h.x.atan2(-h.y)
+}
+
+/// Least-squares node rate (rad/s) of every moon over `days`
+fn measured_node_rates(mut sim: Simulation, days: usize) -> Vec<f64> {
+    let moons = 1..sim.bodies().len();
+    let mut samples: Vec<Vec<(f64, f64)>> = moons.clone().map(|_| Vec::new()).collect();
+    let mut last: Vec<f64> = moons.clone().map(|m| node_longitude(&sim, m)).collect();
+    let mut unwrapped = last.clone();
+    for _ in 0..days * 4 {
+        sim.step_n(36);
+        for (k, m) in moons.clone().enumerate() {
+            let node = node_longitude(&sim, m);
+            let delta = (node - last[k] + std::f64::consts::PI).rem_euclid(std::f64::consts::TAU) - std::f64::consts::PI;
+            unwrapped[k] += delta;
+            last[k] = node;
+            samples[k].push((sim.time(), unwrapped[k]));
+        }
+    }
+
+    samples
+        .iter()
+        .map(|points| least_squares_slope(points))
+        .collect()
+}
+
+/// Secular node regression from J2 and J4 for a near-equatorial circular orbit:
+/// dΩ/dt = −n [3/2 J2 (R/a)² − 15/4 J4 (R/a)⁴]
+fn predicted_node_rate(jupiter: &Body, moon: &Body) -> f64 {
+    let a = (moon.position - jupiter.position).length();
+    let n = (G * (jupiter.mass + moon.mass) / a.powi(3)).sqrt();
+    let s2 = (jupiter.radius / a).powi(2);
+    -n * (1.5 * jupiter.j2 * s2 - 3.75 * jupiter.j4 * s2 * s2)
+}
+
+#[test]
+fn test_galilean_nodal_precession() {
+    let inclination = 0.5_f64.to_radians();
+    let sim = inclined_jupiter_system(inclination, true);
+    let predicted: Vec<f64> = sim.bodies()[1..].iter().map(|m| predicted_node_rate(&sim.bodies()[0], m)).collect();
+    let oblate = measured_node_rates(sim, 60);
+    let point_mass = measured_node_rates(inclined_jupiter_system(inclination, false), 60);
+
+    let names = ["Io", "Europa", "Ganymede", "Callisto"];
+    // Io regresses ~47°/yr, Callisto only ~0.25°/yr; the residual comes
+    // from the moons' mutual pull once their nodes separate
+    let tolerances = [0.005, 0.01, 0.01, 0.02];
+    for k in 0..4 {
+        let per_year = |rate: f64| rate.to_degrees() * SECONDS_PER_YEAR;
+        println!(
+            "{:9} predicted {:8.3}°/yr, J2/J4 {:8.3}°/yr, point mass {:8.3}°/yr",
+            names[k],
+            per_year(predicted[k]),
+            per_year(oblate[k]),
+            per_year(point_mass[k])
+        );
+        let error = (oblate[k] / predicted[k] - 1.0).abs();
+        assert!(error < tolerances[k], "{} node rate off by {:.1}%", names[k], error * 100.0);
+        assert!(point_mass[k].abs() < 0.1 * predicted[k].abs(), "{} precesses without J2", names[k]);
+    }
+}
+
+#[test]
+fn test_zonal_energy_and_momentum_conserved() {
+    let mut sim = inclined_jupiter_system(5.0_f64.to_radians(), true);
+    let e0 = sim.total_energy();
+    let p0 = sim.total_momentum();
+    sim.set_integrator(IntegratorType::Ias15);
+    sim.step_n(24 * 6 * 30);
+
+    let energy_error = ((sim.total_energy() - e0) / e0).abs();
+    assert!(energy_error < 1e-12, "energy error {:.3e}", energy_error);
+    let scale = sim.bodies()[1].mass * sim.bodies()[1].velocity.length();
+    assert!((sim.total_momentum() - p0).length() < 1e-12 * scale);
+}
@@ -14,6 +14,13 @@ export const SECONDS_PER_DAY = 86400.0;
 export const SECONDS_PER_YEAR = 365.25 * SECONDS_PER_DAY;
 export const DEFAULT_SOFTENING = 10000.0;
 export const DEFAULT_BARNES_HUT_THETA = 0.5;
+export const DEFAULT_FMM_ORDER = 3;
+export const DEFAULT_FMM_THETA = 0.5;
+export const DEFAULT_IAS15_EPSILON = 1e-9;
+export const DEFAULT_ADAPTIVE_ETA = 0.02;
+export const DEFAULT_MAX_SUBSTEPS = 1024;
+export const DEFAULT_DRAG_COEFFICIENT = 2.2;
+export const DEFAULT_THERMAL_INERTIA = 200.0;
 export const MAX_MASSIVE_BODIES = 100;
 export const MAX_TOTAL_OBJECTS = 500;
 export const DEFAULT_SUBSTEPS = 4;
@@ -25,6 +32,7 @@ export const G_SURFACE_EARTH = 9.80665;
 export const T_SURFACE_EARTH = 288.0;
 export const OMEGA_EARTH = 7.2921159e-5;
 export const AXIAL_TILT_EARTH = 0.4091;
+export const OBLIQUITY_J2000 = 0.409092804222329;
 export const K_BOLTZMANN = 1.380649e-23;
 export const N_AVOGADRO = 6.02214076e23;
 export const R_GAS = K_BOLTZMANN * N_AVOGADRO;